@@ -0,0 +1,28 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+};
+
+use granny2::Granny2;
+
+use super::Arguments;
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let arguments = Arguments::parse(args, &[])?;
+    let [input, output] = arguments.files.as_slice() else {
+        return Err("Expected an input and an output file.".into());
+    };
+
+    let reader = File::open(input).map_err(|error| format!("{}: {}", input, error))?;
+    let mut writer =
+        BufWriter::new(File::create(output).map_err(|error| format!("{}: {}", output, error))?);
+    Granny2::decompress_with_options(
+        BufReader::new(reader),
+        &mut writer,
+        &arguments.parse_options(),
+    )?;
+    writer.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::{
+    error::Error,
+    io::{BufWriter, Write},
+};
+
+use granny2::granny2::element::{Data, Element};
+
+use super::Arguments;
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let arguments = Arguments::parse(args, &["--depth", "--path"])?;
+    let depth = arguments
+        .value("--depth")
+        .map(str::parse::<usize>)
+        .transpose()
+        .map_err(|error| format!("Invalid depth: {}", error))?;
+    let paths = arguments.values("--path").collect::<Vec<_>>();
+    let file = arguments.open()?;
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    if paths.is_empty() {
+        for element in &file.root {
+            print_element(&mut out, &element.name, element, 0, depth)?;
+        }
+    }
+    for path in paths {
+        let mut matches = file.query(path);
+        let mut found = false;
+        while let Some(element) = matches.next() {
+            print_element(&mut out, &matches.path(), element, 0, depth)?;
+            found = true;
+        }
+        if !found {
+            out.flush()?;
+            return Err(format!("No element matches {}.", path).into());
+        }
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+fn print_element<T: Write>(
+    out: &mut T,
+    name: &str,
+    element: &Element,
+    level: usize,
+    depth: Option<usize>,
+) -> std::io::Result<()> {
+    if depth.is_some_and(|depth| level >= depth) {
+        return Ok(());
+    }
+    let expand = depth.is_none_or(|depth| level + 1 < depth);
+    let values = element
+        .data
+        .iter()
+        .filter_map(format_value)
+        .collect::<Vec<_>>();
+    let value = match values.as_slice() {
+        [] => String::new(),
+        [value] => format!(" = {}", value),
+        values => format!(" = [{}]", values.join(", ")),
+    };
+    let hidden = if !expand && !element.children.is_empty() {
+        " ..."
+    } else {
+        ""
+    };
+    writeln!(
+        out,
+        "{:indent$}{}: {:?}{}{}",
+        "",
+        name,
+        element.info.element_type,
+        value,
+        hidden,
+        indent = level * 2
+    )?;
+
+    if expand {
+        for child in element.children.iter() {
+            print_element(out, &child.name, child, level + 1, depth)?;
+        }
+    }
+    Ok(())
+}
+
+fn format_value(data: &Data) -> Option<String> {
+    let value = match data {
+        Data::Empty => return None,
+        Data::Int8(value) => value.to_string(),
+        Data::UInt8(value) => value.to_string(),
+        Data::Int16(value) => value.to_string(),
+        Data::UInt16(value) => value.to_string(),
+        Data::Int32(value) => value.to_string(),
+        Data::UInt32(value) => value.to_string(),
+        Data::Int8Norm(value) => value.to_string(),
+        Data::UInt8Norm(value) => value.to_string(),
+        Data::Int16Norm(value) => value.to_string(),
+        Data::UInt16Norm(value) => value.to_string(),
+        Data::Real16(value) => format!("{:?}", value),
+        Data::Real32(value) => format!("{:?}", value),
+        Data::Int8Array(values) => format!("{:?}", values),
+        Data::UInt8Array(values) => format!("{:?}", values),
+        Data::Int16Array(values) => format!("{:?}", values),
+        Data::UInt16Array(values) => format!("{:?}", values),
+        Data::Int32Array(values) => format!("{:?}", values),
+        Data::UInt32Array(values) => format!("{:?}", values),
+        Data::Int8NormArray(values) => format!("{:?}", values),
+        Data::UInt8NormArray(values) => format!("{:?}", values),
+        Data::Int16NormArray(values) => format!("{:?}", values),
+        Data::UInt16NormArray(values) => format!("{:?}", values),
+        Data::Real16Array(values) => format!("{:?}", values),
+        Data::Real32Array(values) => format!("{:?}", values),
+        Data::Transform(transform) => format!(
+            "{{ flags: {}, position: {:?}, orientation: {:?}, scale/shear: {:?} }}",
+            transform.flags, transform.translation, transform.rotation, transform.scale_shear
+        ),
+        Data::String(value) => format!("{:?}", value),
+        Data::Array(count, address) => format!("{} items at {:#x}", count, address),
+        Data::Reference(0) | Data::Variant(_, 0) => "null".to_owned(),
+        Data::Reference(address) => format!("{:#x}", address),
+        Data::ArrayOfReferences(references) => format!("{} references", references.len()),
+        Data::Variant(_, address) => format!("variant at {:#x}", address),
+        Data::VariantArray(count, _, address) => {
+            format!("{} variant items at {:#x}", count, address)
+        }
+    };
+    Some(value)
+}
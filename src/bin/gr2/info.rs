@@ -0,0 +1,66 @@
+use std::error::Error;
+
+use granny2::granny2::format::{Endianness, PointerWidth};
+
+use super::Arguments;
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let file = Arguments::parse(args, &[])?.open()?;
+    let header = &file.header;
+
+    let endianness = match header.format.endianness {
+        Endianness::Little => "little endian",
+        Endianness::Big => "big endian",
+    };
+    let pointer_width = match header.format.pointer_width {
+        PointerWidth::Bits32 => 32,
+        PointerWidth::Bits64 => 64,
+    };
+    println!("Format: {}, {} bit pointers", endianness, pointer_width);
+    println!("Version: {}", header.version);
+    println!("File size: {}", header.file_size);
+    println!("Header size: {}", header.header_size);
+    println!("Checksum: {:#010x}", header.checksum);
+    println!("Compression type: {}", header.compression_type);
+    println!(
+        "Root type: section {}, offset {:#x}",
+        header.root_node_type.section, header.root_node_type.offset
+    );
+    println!(
+        "Root object: section {}, offset {:#x}",
+        header.root_node_object.section, header.root_node_object.offset
+    );
+    println!("User tag: {:#010x}", u32::from_le_bytes(header.user_tag));
+
+    println!();
+    println!(
+        "{:>3}  {:<11}  {:>10}  {:>10}  {:>12}  {:>9}  {:>10}  {:>10}  {:>11}  {:>12}",
+        "#",
+        "Compression",
+        "Offset",
+        "Compressed",
+        "Decompressed",
+        "Alignment",
+        "Stop 0",
+        "Stop 1",
+        "Relocations",
+        "Marshallings"
+    );
+    for (index, section) in file.sections.iter().enumerate() {
+        println!(
+            "{:>3}  {:<11}  {:>10}  {:>10}  {:>12}  {:>9}  {:>10}  {:>10}  {:>11}  {:>12}",
+            index,
+            format!("{:?}", section.compression_mode),
+            section.section_offset,
+            section.compressed_size,
+            section.decompressed_size,
+            section.alignment_size,
+            section.stop_0,
+            section.stop_1,
+            section.relocation_header.count,
+            section.marshalling_header.count
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+mod decompress;
+mod dump;
+mod info;
+mod schema_diff;
+
+use std::{error::Error, fs::File, io::BufReader, process::ExitCode};
+
+use granny2::{Granny2, ParseOptions};
+use log::{LevelFilter, Log, Metadata, Record};
+
+const USAGE: &str = "\
+Usage: gr2 <command> [options] <file>...
+
+Commands:
+  info <file>         Prints the header and the section table
+  dump <file>         Prints the element tree
+    --depth <n>       Prints n levels of elements at most, nothing for 0
+    --path <path>     Prints only the elements at path, segments are
+                      separated by / and * matches any name, such as
+                      Models/*/Name. May be given more than once, fails
+                      when nothing matches
+  decompress <in> <out>
+                      Writes in with all sections uncompressed to out
+  schema-diff <a> <b> Prints the members added, removed or retyped on the
+                      types of b compared to a
+
+Options:
+  --skip-checksum     Doesn't verify the header checksum
+";
+
+/// Prints the causes logged by the library before the error is returned.
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "{}: {}",
+                record.level().as_str().to_lowercase(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger;
+
+fn main() -> ExitCode {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Warn);
+    }
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let Some((command, args)) = args.split_first() else {
+        eprint!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let result = match command.as_str() {
+        "info" => info::run(args),
+        "dump" => dump::run(args),
+        "decompress" => decompress::run(args),
+        "schema-diff" => schema_diff::run(args),
+        "help" | "--help" | "-h" => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("Unknown command {}, see gr2 help.", command).into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Arguments given to a command.
+struct Arguments {
+    skip_checksum: bool,
+    options: Vec<(String, String)>,
+    files: Vec<String>,
+}
+
+impl Arguments {
+    /// Splits `args` into options and files, `valued` being the options
+    /// the command accepts, which all take a value.
+    fn parse(args: &[String], valued: &[&str]) -> Result<Self, Box<dyn Error>> {
+        let mut arguments = Self {
+            skip_checksum: false,
+            options: Vec::new(),
+            files: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--skip-checksum" {
+                arguments.skip_checksum = true;
+            } else if valued.contains(&arg.as_str()) {
+                let Some(value) = args.next() else {
+                    return Err(format!("Option {} needs a value.", arg).into());
+                };
+                arguments.options.push((arg.clone(), value.clone()));
+            } else if arg.starts_with("--") {
+                return Err(format!("Unknown option {}.", arg).into());
+            } else {
+                arguments.files.push(arg.clone());
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    /// Last value given to `option`.
+    fn value<'a>(&'a self, option: &'a str) -> Option<&'a str> {
+        self.values(option).last()
+    }
+
+    fn values<'a>(&'a self, option: &'a str) -> impl Iterator<Item = &'a str> {
+        self.options
+            .iter()
+            .filter(move |(name, _)| name == option)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The single file the command works on.
+    fn file(&self) -> Result<&str, Box<dyn Error>> {
+        match self.files.as_slice() {
+            [file] => Ok(file),
+            [] => Err("Missing file.".into()),
+            _ => Err("Only one file can be given.".into()),
+        }
+    }
+
+    fn open(&self) -> Result<Granny2, Box<dyn Error>> {
+        self.open_file(self.file()?)
+    }
+
+    fn open_file(&self, path: &str) -> Result<Granny2, Box<dyn Error>> {
+        let file = File::open(path).map_err(|error| format!("{}: {}", path, error))?;
+        Ok(Granny2::parse_with_options(
+            BufReader::new(file),
+            &self.parse_options(),
+        )?)
+    }
+
+    fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            verify_checksum: !self.skip_checksum,
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::{
+    error::Error,
+    io::{BufWriter, Write},
+};
+
+use super::Arguments;
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let arguments = Arguments::parse(args, &[])?;
+    let [before, after] = arguments.files.as_slice() else {
+        return Err("Expected the two files to compare.".into());
+    };
+
+    let before = arguments.open_file(before)?.definition();
+    let after = arguments.open_file(after)?.definition();
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let changes = before.diff(&after);
+    if changes.is_empty() {
+        writeln!(out, "No changes.")?;
+    }
+    let mut path = None;
+    for change in &changes {
+        if path != Some(&change.path) {
+            let name = if change.path.is_empty() {
+                "(root)"
+            } else {
+                &change.path
+            };
+            writeln!(out, "{}", name)?;
+            path = Some(&change.path);
+        }
+        writeln!(out, "  {}", change.kind)?;
+    }
+    out.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,564 @@
+mod model;
+
+use std::{error::Error, fmt::Display, io::Read};
+
+use model::Model;
+
+/// Magic found at the start of every BitKnit stream.
+const MAGIC: u16 = 0x75b1;
+/// Size of the blocks the stream is split into.
+const QUANTUM_SIZE: usize = 0x10000;
+
+/// BitKnit decoder.
+///
+/// BitKnit is an LZ77 variant whose literals, lengths and distances are
+/// entropy coded by two interleaved rANS states with adaptive models.
+#[derive(Debug)]
+pub struct Bitknit {
+    recent_distances: [usize; 8],
+    recent_distance_mask: u32,
+    last_match_distance: usize,
+    literals: Vec<Model>,
+    distance_lsbs: Vec<Model>,
+    distance_bits: Model,
+}
+
+impl Bitknit {
+    fn new() -> Self {
+        Self {
+            recent_distances: [1; 8],
+            recent_distance_mask: (7 << 21)
+                | (6 << 18)
+                | (5 << 15)
+                | (4 << 12)
+                | (3 << 9)
+                | (2 << 6)
+                | (1 << 3),
+            last_match_distance: 1,
+            literals: (0..4).map(|_| Model::literal()).collect(),
+            distance_lsbs: (0..4).map(|_| Model::distance_lsb()).collect(),
+            distance_bits: Model::distance_bits(),
+        }
+    }
+
+    pub fn decompress<T: Read>(
+        reader: &mut T,
+        compressed_size: usize,
+        decompressed_size: usize,
+    ) -> Result<Vec<u8>, BitknitError> {
+        let compressed = {
+            let mut buffer = vec![0; compressed_size];
+            reader.read_exact(&mut buffer)?;
+            buffer
+        };
+        let mut decompressed = vec![0; decompressed_size];
+
+        let mut compressed_stream = compressed.as_slice();
+
+        if Self::read_u16(&mut compressed_stream)? != MAGIC {
+            return Err(BitknitError::MagicMismatch);
+        }
+
+        let mut decoder = Self::new();
+
+        let mut position = 0;
+        while position < decompressed_size {
+            let end = (position + QUANTUM_SIZE).min(decompressed_size);
+
+            if compressed_stream.get(..2) == Some(&[0, 0]) {
+                // Quantum stored without compression
+                compressed_stream = &compressed_stream[2..];
+                let Some(raw) = compressed_stream.get(..(end - position)) else {
+                    return Err(BitknitError::Decompress);
+                };
+                decompressed[position..end].copy_from_slice(raw);
+                compressed_stream = &compressed_stream[(end - position)..];
+            } else {
+                decoder.decompress_quantum(
+                    &mut compressed_stream,
+                    &mut decompressed,
+                    position,
+                    end,
+                )?;
+            }
+
+            position = end;
+        }
+
+        Ok(decompressed)
+    }
+
+    fn decompress_quantum(
+        &mut self,
+        stream: &mut &[u8],
+        decompressed: &mut [u8],
+        mut position: usize,
+        end: usize,
+    ) -> Result<(), BitknitError> {
+        // Two rANS states are packed into the first bytes of the quantum
+        let packed = Self::read_u32(stream)?;
+        if packed < 0x10000 {
+            return Err(BitknitError::Decompress);
+        }
+        let split = packed & 0xf;
+        let mut low = packed >> 4;
+        if low < 0x10000 {
+            low = (low << 16) | u32::from(Self::read_u16(stream)?);
+        }
+        let mut state = low >> split;
+        if state < 0x10000 {
+            state = (state << 16) | u32::from(Self::read_u16(stream)?);
+        }
+        low = (low << 16) | u32::from(Self::read_u16(stream)?);
+        let mask = (1 << (split + 16)) - 1;
+        let mut states = [state, (1 << (split + 16)) | (low & mask)];
+
+        if position == 0 {
+            // First byte of the stream is stored as is
+            decompressed[0] = states[0].to_le_bytes()[0];
+            states[0] >>= 8;
+            Self::renormalize(stream, &mut states)?;
+            position += 1;
+        }
+
+        while position < end {
+            let symbol = self.literals[position & 3].decode(&mut states[0]);
+            Self::renormalize(stream, &mut states)?;
+
+            if symbol < 256 {
+                let Ok(literal) = u8::try_from(symbol) else {
+                    unreachable!("Literal must be smaller than u8.");
+                };
+                decompressed[position] =
+                    literal.wrapping_add(decompressed[position - self.last_match_distance]);
+                position += 1;
+                continue;
+            }
+
+            let length_symbol = if symbol >= 288 {
+                let Ok(bit_count) = u32::try_from(symbol - 287) else {
+                    unreachable!("Bit count must be smaller than u32.");
+                };
+                let extra = states[0] & ((1 << bit_count) - 1);
+                states[0] >>= bit_count;
+                Self::renormalize(stream, &mut states)?;
+                let Ok(extra) = usize::try_from(extra + (1 << bit_count)) else {
+                    unreachable!("Length must be smaller than usize.");
+                };
+                extra + 286
+            } else {
+                symbol
+            };
+            let length = length_symbol - 254;
+
+            let distance_symbol = self.distance_lsbs[position & 3].decode(&mut states[0]);
+            Self::renormalize(stream, &mut states)?;
+
+            let distance = if distance_symbol >= 8 {
+                let Ok(bit_count) = u32::try_from(self.distance_bits.decode(&mut states[0])) else {
+                    unreachable!("Bit count must be smaller than u32.");
+                };
+                Self::renormalize(stream, &mut states)?;
+
+                let mut extra = states[0] & ((1 << (bit_count & 0xf)) - 1);
+                states[0] >>= bit_count & 0xf;
+                Self::renormalize(stream, &mut states)?;
+                if bit_count >= 16 {
+                    extra = (extra << 16) | u32::from(Self::read_u16(stream)?);
+                }
+
+                let Ok(distance) = usize::try_from((32 << bit_count) + (extra << 5))
+                    .map(|distance| distance + distance_symbol - 39)
+                else {
+                    unreachable!("Distance must be smaller than usize.");
+                };
+
+                self.push_distance(distance);
+                distance
+            } else {
+                self.recent_distance(distance_symbol)
+            };
+
+            if distance > position || position + length > end {
+                return Err(BitknitError::Decompress);
+            }
+
+            // Copies byte by byte since source and destination may overlap
+            for i in position..(position + length) {
+                decompressed[i] = decompressed[i - distance];
+            }
+            position += length;
+            self.last_match_distance = distance;
+        }
+
+        Ok(())
+    }
+
+    /// Keeps a new match distance among the recent ones.
+    fn push_distance(&mut self, distance: usize) {
+        let evicted = self.recent_slot(7);
+        let replaced = self.recent_slot(6);
+        self.recent_distances[evicted] = self.recent_distances[replaced];
+        self.recent_distances[replaced] = distance;
+    }
+
+    /// Recent distance picked by `symbol`, which moves to the front.
+    fn recent_distance(&mut self, symbol: usize) -> usize {
+        let Ok(shift) = u32::try_from(3 * symbol) else {
+            unreachable!("Shift must be smaller than u32.");
+        };
+        let slot = self.recent_slot(symbol);
+        let keep = !7u32 << shift;
+        let Ok(slot_bits) = u32::try_from(slot) else {
+            unreachable!("Slot must be smaller than u32.");
+        };
+        self.recent_distance_mask = (self.recent_distance_mask & keep)
+            | ((slot_bits + 8 * self.recent_distance_mask) & !keep);
+
+        self.recent_distances[slot]
+    }
+
+    fn recent_slot(&self, index: usize) -> usize {
+        let Ok(slot) = usize::try_from((self.recent_distance_mask >> (3 * index)) & 7) else {
+            unreachable!("Slot must be smaller than usize.");
+        };
+        slot
+    }
+
+    /// Refills the active state if needed, then swaps states.
+    fn renormalize(stream: &mut &[u8], states: &mut [u32; 2]) -> Result<(), BitknitError> {
+        if states[0] < 0x10000 {
+            states[0] = (states[0] << 16) | u32::from(Self::read_u16(stream)?);
+        }
+        states.swap(0, 1);
+        Ok(())
+    }
+
+    fn read_u16(stream: &mut &[u8]) -> Result<u16, BitknitError> {
+        let Some((bytes, rest)) = stream.split_first_chunk::<2>() else {
+            return Err(BitknitError::Decompress);
+        };
+        *stream = rest;
+        Ok(u16::from_le_bytes(*bytes))
+    }
+
+    fn read_u32(stream: &mut &[u8]) -> Result<u32, BitknitError> {
+        let Some((bytes, rest)) = stream.split_first_chunk::<4>() else {
+            return Err(BitknitError::Decompress);
+        };
+        *stream = rest;
+        Ok(u32::from_le_bytes(*bytes))
+    }
+}
+
+#[derive(Debug)]
+pub enum BitknitError {
+    MagicMismatch,
+    Decompress,
+    Io,
+}
+
+impl Display for BitknitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MagicMismatch => write!(f, "BitKnit stream magic did not match."),
+            Self::Decompress => write!(f, "Failed to decompress."),
+            Self::Io => write!(f, "BitKnit failed to read compressed data."),
+        }
+    }
+}
+
+impl Error for BitknitError {}
+
+impl From<std::io::Error> for BitknitError {
+    fn from(value: std::io::Error) -> Self {
+        log::error!("{}", value);
+        Self::Io
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{model::Model, Bitknit, BitknitError, MAGIC, QUANTUM_SIZE};
+
+    /// Longest match a single length symbol and its extra bits can hold.
+    const MAX_LENGTH: usize = 32 + 8191;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Token {
+        Literal(u8),
+        Match { distance: usize, length: usize },
+    }
+
+    /// Step of the decoder on one of the two rANS states.
+    enum Op {
+        Symbol { low: u32, frequency: u32 },
+        Bits { count: u32, value: u32 },
+    }
+
+    /// Minimal BitKnit encoder, mirroring the decoder step by step.
+    ///
+    /// Returns the stream and the data `tokens` expand to.
+    fn encode(tokens: &[Token]) -> (Vec<u8>, Vec<u8>) {
+        let mut data = vec![];
+        for token in tokens {
+            match *token {
+                Token::Literal(byte) => data.push(byte),
+                Token::Match { distance, length } => {
+                    for _ in 0..length {
+                        data.push(data[data.len() - distance]);
+                    }
+                }
+            }
+        }
+
+        let mut model = Bitknit::new();
+        let mut stream = MAGIC.to_le_bytes().to_vec();
+        let mut ops = vec![];
+        let mut position = 0;
+        for token in tokens {
+            let (distance, mut length) = match *token {
+                Token::Literal(_) => (0, 1),
+                Token::Match { distance, length } => (distance, length),
+            };
+            while length > 0 {
+                let chunk = length
+                    .min(QUANTUM_SIZE - position % QUANTUM_SIZE)
+                    .min(MAX_LENGTH);
+                if position == 0 {
+                    ops.push(Op::Bits {
+                        count: 8,
+                        value: u32::from(data[0]),
+                    });
+                    length -= 1;
+                    position += 1;
+                    continue;
+                } else if chunk == 1 || distance == 0 {
+                    let literal =
+                        data[position].wrapping_sub(data[position - model.last_match_distance]);
+                    symbol(
+                        &mut ops,
+                        &mut model.literals[position & 3],
+                        usize::from(literal),
+                    );
+                    length -= 1;
+                    position += 1;
+                } else {
+                    encode_match(&mut model, &mut ops, position, distance, chunk);
+                    length -= chunk;
+                    position += chunk;
+                }
+                if position % QUANTUM_SIZE == 0 {
+                    stream.extend(finish(&ops));
+                    ops.clear();
+                }
+            }
+        }
+        if !ops.is_empty() {
+            stream.extend(finish(&ops));
+        }
+
+        (stream, data)
+    }
+
+    fn encode_match(
+        model: &mut Bitknit,
+        ops: &mut Vec<Op>,
+        position: usize,
+        distance: usize,
+        length: usize,
+    ) {
+        let literals = &mut model.literals[position & 3];
+        if length <= 33 {
+            symbol(ops, literals, length + 254);
+        } else {
+            let extra = length - 32;
+            let count = extra.ilog2();
+            symbol(ops, literals, 287 + usize::try_from(count).unwrap());
+            ops.push(Op::Bits {
+                count,
+                value: u32::try_from(extra - (1 << count)).unwrap(),
+            });
+        }
+
+        let recent = (0..8).find(|i| model.recent_distances[model.recent_slot(*i)] == distance);
+        if let Some(recent) = recent {
+            symbol(ops, &mut model.distance_lsbs[position & 3], recent);
+            model.recent_distance(recent);
+        } else {
+            let lsb = 8 + (distance + 31) % 32;
+            let extra = (distance + 39 - lsb) / 32;
+            let count = extra.ilog2();
+            assert!(count < 16, "Distance {} needs raw words.", distance);
+            symbol(ops, &mut model.distance_lsbs[position & 3], lsb);
+            symbol(
+                ops,
+                &mut model.distance_bits,
+                usize::try_from(count).unwrap(),
+            );
+            ops.push(Op::Bits {
+                count,
+                value: u32::try_from(extra - (1 << count)).unwrap(),
+            });
+            model.push_distance(distance);
+        }
+        model.last_match_distance = distance;
+    }
+
+    /// Records `symbol`, updating `model` the same way decoding it does.
+    fn symbol(ops: &mut Vec<Op>, model: &mut Model, symbol: usize) {
+        let low = u32::from(model.cumulative[symbol]);
+        let frequency = u32::from(model.cumulative[symbol + 1]) - low;
+        let mut state = low;
+        assert_eq!(model.decode(&mut state), symbol);
+        ops.push(Op::Symbol { low, frequency });
+    }
+
+    /// Encodes the ops of a quantum backwards, alternating states as the
+    /// decoder does, and packs the final states into the quantum header.
+    fn finish(ops: &[Op]) -> Vec<u8> {
+        let mut states = [0x10000u32; 2];
+        let mut words = vec![];
+        for (i, op) in ops.iter().enumerate().rev() {
+            let state = &mut states[i % 2];
+            let (limit, count) = match op {
+                Op::Symbol { frequency, .. } => (u64::from(*frequency) << 17, 0),
+                Op::Bits { count, .. } => (1u64 << (32 - count), *count),
+            };
+            if u64::from(*state) >= limit {
+                words.push(u16::try_from(*state & 0xffff).unwrap());
+                *state >>= 16;
+            }
+            *state = match op {
+                Op::Symbol { low, frequency } => {
+                    ((*state / frequency) << 15) + *state % frequency + low
+                }
+                Op::Bits { value, .. } => (*state << count) | value,
+            };
+        }
+        words.reverse();
+
+        let [first, second] = states;
+        let split = second.ilog2() - 16;
+        let rest = second - (1 << (split + 16));
+        let (low, first_word) = if u64::from(first) < 1u64 << (32 - split) {
+            ((first << split) | (rest >> 16), None)
+        } else {
+            (
+                ((first >> 16) << split) | (rest >> 16),
+                Some(first & 0xffff),
+            )
+        };
+        let (packed, low_word) = if low < 1 << 28 {
+            ((low << 4) | split, None)
+        } else {
+            (((low >> 16) << 4) | split, Some(low & 0xffff))
+        };
+
+        let mut quantum = packed.to_le_bytes().to_vec();
+        let header = [low_word, first_word, Some(rest & 0xffff)];
+        for word in header.into_iter().flatten() {
+            quantum.extend(u16::try_from(word).unwrap().to_le_bytes());
+        }
+        quantum.extend(words.iter().flat_map(|word| word.to_le_bytes()));
+        quantum
+    }
+
+    /// Xorshift, so test data does not depend on a random crate.
+    fn random(seed: &mut u32) -> u32 {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 17;
+        *seed ^= *seed << 5;
+        *seed
+    }
+
+    fn decompress(stream: &[u8], size: usize) -> Result<Vec<u8>, BitknitError> {
+        Bitknit::decompress(&mut &stream[..], stream.len(), size)
+    }
+
+    #[test]
+    fn stored_quantum() {
+        let stream = [0xb1, 0x75, 0, 0, b'h', b'e', b'l', b'l', b'o'];
+        assert_eq!(decompress(&stream, 5).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn fixed_vector() {
+        // "abracadabra abracadabra" as a literal run, a match at a new
+        // distance and a match at a recent one
+        let stream = [
+            0xb1, 0x75, 0xda, 0x73, 0x06, 0x00, 0xb1, 0x87, 0xb3, 0x80, 0x39, 0x08, 0x71, 0x7b,
+            0x79, 0x01, 0x39, 0x08, 0xcf, 0x73, 0xa7, 0x3f, 0x00, 0x83,
+        ];
+        assert_eq!(decompress(&stream, 23).unwrap(), b"abracadabra abracadabra");
+    }
+
+    #[test]
+    fn magic_mismatch() {
+        let stream = [0x75, 0xb1, 0, 0, b'h', b'e', b'l', b'l', b'o'];
+        assert!(matches!(
+            decompress(&stream, 5),
+            Err(BitknitError::MagicMismatch)
+        ));
+    }
+
+    #[test]
+    fn truncated_stream() {
+        let tokens = (0..64u8).map(Token::Literal).collect::<Vec<_>>();
+        let (stream, data) = encode(&tokens);
+        assert!(matches!(
+            decompress(&stream[..(stream.len() / 2)], data.len()),
+            Err(BitknitError::Decompress)
+        ));
+    }
+
+    #[test]
+    fn literals_and_matches() {
+        // Enough literals and new distances for every model to adapt
+        let mut seed = 0x1234_5678;
+        let mut tokens = vec![];
+        let mut size = 0;
+        while size < 48 * 1024 {
+            let token = match random(&mut seed) % 4 {
+                0 | 1 if size > 0 => Token::Match {
+                    distance: 1 + usize::try_from(random(&mut seed)).unwrap() % size.min(40000),
+                    length: 2 + usize::try_from(random(&mut seed) % 40).unwrap(),
+                },
+                2 if size > 0 => Token::Match {
+                    distance: 1 + usize::try_from(random(&mut seed) % 4)
+                        .unwrap()
+                        .min(size - 1),
+                    length: 2 + usize::try_from(random(&mut seed) % 8).unwrap(),
+                },
+                _ => Token::Literal(u8::try_from(random(&mut seed) & 0xff).unwrap()),
+            };
+            size += match token {
+                Token::Literal(_) => 1,
+                Token::Match { length, .. } => length,
+            };
+            tokens.push(token);
+        }
+
+        let (stream, data) = encode(&tokens);
+        assert_eq!(decompress(&stream, data.len()).unwrap(), data);
+    }
+
+    #[test]
+    fn long_matches_across_quanta() {
+        let tokens = [
+            Token::Literal(7),
+            Token::Literal(42),
+            Token::Match {
+                distance: 2,
+                length: QUANTUM_SIZE + 20000,
+            },
+            Token::Literal(1),
+            Token::Match {
+                distance: 3,
+                length: 100,
+            },
+        ];
+        let (stream, data) = encode(&tokens);
+        assert_eq!(decompress(&stream, data.len()).unwrap(), data);
+    }
+}
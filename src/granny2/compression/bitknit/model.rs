@@ -0,0 +1,140 @@
+/// Adaptive frequency model used by the rANS coder.
+///
+/// Cumulative frequencies always sum to `0x8000`. Every decoded symbol gets
+/// its frequency bumped and, after 1024 symbols, the cumulative table is
+/// moved halfway towards the frequencies seen since the last adaptation.
+#[derive(Debug)]
+pub struct Model {
+    pub lookup: Vec<u16>,
+    pub cumulative: Vec<u16>,
+    pub frequencies: Vec<u16>,
+    pub adapt_interval: u32,
+    lookup_shift: u32,
+}
+
+impl Model {
+    const ADAPT_INTERVAL: u32 = 1024;
+    const INCREMENT: u16 = 31;
+
+    /// Model for literals and match lengths.
+    ///
+    /// The first 264 symbols (literals and short lengths) share most of the
+    /// probability space, the remaining ones start with a frequency of one.
+    pub fn literal() -> Self {
+        let cumulative = (0..=300u32)
+            .map(|i| {
+                let value = if i < 264 {
+                    (0x8000 - 300 + 264) * i / 264
+                } else {
+                    (0x8000 - 300) + i
+                };
+                let Ok(value) = u16::try_from(value) else {
+                    unreachable!("Cumulative frequency must be smaller than u16.");
+                };
+                value
+            })
+            .collect();
+        Self::new(cumulative, 6)
+    }
+
+    /// Model for the low bits of match distances and recent distances.
+    pub fn distance_lsb() -> Self {
+        Self::new(Self::uniform(40), 9)
+    }
+
+    /// Model for the bit count of match distances.
+    pub fn distance_bits() -> Self {
+        Self::new(Self::uniform(21), 9)
+    }
+
+    fn uniform(symbols: u32) -> Vec<u16> {
+        (0..=symbols)
+            .map(|i| {
+                let Ok(value) = u16::try_from(0x8000 * i / symbols) else {
+                    unreachable!("Cumulative frequency must be smaller than u16.");
+                };
+                value
+            })
+            .collect()
+    }
+
+    fn new(cumulative: Vec<u16>, lookup_shift: u32) -> Self {
+        let symbols = cumulative.len() - 1;
+        let mut model = Self {
+            lookup: vec![0; 0x8000 >> lookup_shift],
+            cumulative,
+            frequencies: vec![1; symbols],
+            adapt_interval: Self::ADAPT_INTERVAL,
+            lookup_shift,
+        };
+        model.rebuild_lookup();
+        model
+    }
+
+    fn rebuild_lookup(&mut self) {
+        let mut symbol = 0;
+        for (bucket, lookup) in self.lookup.iter_mut().enumerate() {
+            let start = bucket << self.lookup_shift;
+            while usize::from(self.cumulative[symbol + 1]) <= start {
+                symbol += 1;
+            }
+            let Ok(value) = u16::try_from(symbol) else {
+                unreachable!("Symbol must be smaller than u16.");
+            };
+            *lookup = value;
+        }
+    }
+
+    /// Decodes a symbol from `state`, updating the state and the model.
+    pub fn decode(&mut self, state: &mut u32) -> usize {
+        let masked = *state & 0x7fff;
+        let Ok(bucket) = usize::try_from(masked >> self.lookup_shift) else {
+            unreachable!("Bucket must be smaller than usize.");
+        };
+        let mut symbol = usize::from(self.lookup[bucket]);
+        while masked >= u32::from(self.cumulative[symbol + 1]) {
+            symbol += 1;
+        }
+
+        let low = u32::from(self.cumulative[symbol]);
+        let frequency = u32::from(self.cumulative[symbol + 1]) - low;
+        *state = (*state >> 15)
+            .wrapping_mul(frequency)
+            .wrapping_add(masked)
+            .wrapping_sub(low);
+
+        self.frequencies[symbol] += Self::INCREMENT;
+        self.adapt_interval -= 1;
+        if self.adapt_interval == 0 {
+            self.adapt(symbol);
+        }
+
+        symbol
+    }
+
+    fn adapt(&mut self, symbol: usize) {
+        // Tops the total up to 0x8000 (give or take the rounding of the halving)
+        let symbols = self.frequencies.len();
+        let Ok(remainder) = u32::try_from(symbols)
+            .map(|symbols| 0x8000 + 1 - symbols - u32::from(Self::INCREMENT) * Self::ADAPT_INTERVAL)
+            .and_then(u16::try_from)
+        else {
+            unreachable!("Remainder must be smaller than u16.");
+        };
+
+        self.adapt_interval = Self::ADAPT_INTERVAL;
+        self.frequencies[symbol] += remainder;
+
+        let mut sum = 0i32;
+        for (i, frequency) in self.frequencies.iter_mut().enumerate() {
+            sum += i32::from(std::mem::replace(frequency, 1));
+            let current = i32::from(self.cumulative[i + 1]);
+            let Ok(value) = u16::try_from(current + ((sum - current) >> 1)) else {
+                unreachable!("Cumulative frequency must be smaller than u16.");
+            };
+            self.cumulative[i + 1] = value;
+        }
+
+        self.rebuild_lookup();
+    }
+}
@@ -35,9 +35,37 @@ impl Buffer {
         let remain = backref_size % backref_offset;
 
         for _ in 0..repeat {
-            front.copy_from_slice(back);
+            front[..backref_offset].copy_from_slice(back);
             front = &mut front[backref_offset..];
         }
         front[..remain].copy_from_slice(&back[..remain]);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Buffer;
+
+    fn buffer(data: &[u8], length: usize) -> Buffer {
+        let mut buffer = Buffer::new(length);
+        for value in data {
+            buffer.push(*value);
+            buffer.advance(1);
+        }
+        buffer
+    }
+
+    #[test]
+    fn backref_longer_than_offset() {
+        let mut buffer = buffer(b"abc", 11);
+        buffer.backref(8, 3);
+        assert_eq!(buffer.inner(), b"abcabcabcab");
+    }
+
+    #[test]
+    fn backref_multiple_of_offset() {
+        let mut buffer = buffer(b"xab", 7);
+        buffer.backref(4, 2);
+        assert_eq!(buffer.inner(), b"xababab");
+    }
+}
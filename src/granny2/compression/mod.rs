@@ -1,4 +1,8 @@
+mod bitknit;
 mod buffer;
 mod oodle;
 
-pub use self::oodle::{Oodle, OodleError};
+pub use self::{
+    bitknit::{Bitknit, BitknitError},
+    oodle::{Oodle, OodleError},
+};
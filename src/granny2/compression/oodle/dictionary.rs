@@ -10,8 +10,6 @@ pub struct Dictionary {
     pub decoded_value_max: u32,
     pub backref_value_max: u32,
     pub lowbit_value_max: u32,
-    pub midbit_value_max: u32,
-    pub highbit_value_max: u32,
 
     pub lowbit_window: WeightWindow,
     pub highbit_window: WeightWindow,
@@ -50,8 +48,6 @@ where
             decoded_value_max: parameters.decoded_value_max,
             backref_value_max: parameters.backref_value_max,
             lowbit_value_max,
-            midbit_value_max,
-            highbit_value_max,
             lowbit_window: WeightWindow::new(lowbit_value_max - 1, lowbit_value_max_16),
             midbit_window: (0..highbit_value_max)
                 .map(|_| WeightWindow::new(midbit_value_max - 1, midbit_value_max_u16))
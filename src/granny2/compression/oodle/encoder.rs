@@ -0,0 +1,85 @@
+/// Range encoder matching the decoding done by [`super::Oodle`].
+///
+/// The decoder starts with 7 bits of code and shifts in 8 bits at a time,
+/// so the stream is produced a bit early and shifted into place by
+/// [`Encoder::finish`].
+#[derive(Debug)]
+pub struct Encoder {
+    low: u64,
+    range: u32,
+    window: u32,
+    stream: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self {
+            low: 0,
+            range: 0x80,
+            window: 1,
+            stream: Vec::new(),
+        }
+    }
+
+    /// Narrows the range to `[val, val + err)` out of `max`.
+    pub fn encode(&mut self, max: u16, val: u16, err: u16) {
+        while self.range <= 0x800000 {
+            if self.window < 4 {
+                self.window += 1;
+            } else {
+                self.stream.push(self.low.to_be_bytes()[4]);
+                self.low &= 0xffffff;
+            }
+            self.low <<= 8;
+            self.range <<= 8;
+        }
+
+        let next_range = self.range / u32::from(max);
+        self.low += u64::from(next_range * u32::from(val));
+        if val + err < max {
+            self.range = next_range * u32::from(err);
+        } else {
+            self.range -= next_range * u32::from(val);
+        }
+
+        let limit = 1u64 << (8 * self.window);
+        if self.low >= limit {
+            self.low -= limit;
+            self.carry();
+        }
+    }
+
+    pub fn encode_value(&mut self, max: u16, val: u16) {
+        self.encode(max, val, 1);
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        let window = self.low.to_be_bytes();
+        let Ok(start) = usize::try_from(8 - self.window) else {
+            unreachable!("Window must be smaller than usize.");
+        };
+        self.stream.extend_from_slice(&window[start..]);
+
+        // The decoder skips the first bit of the stream
+        let mut shifted = self
+            .stream
+            .windows(2)
+            .map(|bytes| (bytes[0] << 1) | (bytes[1] >> 7))
+            .collect::<Vec<_>>();
+        if let Some(last) = self.stream.last() {
+            shifted.push(last << 1);
+        }
+        shifted
+    }
+
+    fn carry(&mut self) {
+        for byte in self.stream.iter_mut().rev() {
+            let (value, overflow) = byte.overflowing_add(1);
+            *byte = value;
+            if !overflow {
+                return;
+            }
+        }
+        unreachable!("Carry can't go past the start of the stream.");
+    }
+}
@@ -0,0 +1,88 @@
+/// Finds back references using chains of positions sharing a hash of
+/// their first bytes.
+pub struct Matcher<'a> {
+    data: &'a [u8],
+    head: Vec<usize>,
+    previous: Vec<usize>,
+    inserted: usize,
+}
+
+impl<'a> Matcher<'a> {
+    const MIN_SIZE: usize = 3;
+    const MAX_SIZE: usize = 512;
+    const HASH_BITS: u32 = 15;
+    const MAX_CHAIN: usize = 64;
+    const NONE: usize = usize::MAX;
+
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            head: vec![Self::NONE; 1 << Self::HASH_BITS],
+            previous: vec![Self::NONE; data.len()],
+            inserted: 0,
+        }
+    }
+
+    /// Longest match for the data at `position` that ends before `stop`
+    /// and is at most `max_offset` bytes back, as a `(size, offset)` pair.
+    pub fn find(
+        &mut self,
+        position: usize,
+        stop: usize,
+        max_offset: usize,
+    ) -> Option<(usize, usize)> {
+        self.insert_until(position);
+        if position + Self::MIN_SIZE > stop {
+            return None;
+        }
+
+        let max_size = (stop - position).min(Self::MAX_SIZE);
+        let mut best = None;
+        let mut best_size = Self::MIN_SIZE - 1;
+
+        let mut candidate = self.head[self.hash(position)];
+        for _ in 0..Self::MAX_CHAIN {
+            if candidate == Self::NONE || position - candidate > max_offset {
+                break;
+            }
+
+            let size = self.data[candidate..]
+                .iter()
+                .zip(&self.data[position..(position + max_size)])
+                .take_while(|(back, front)| back == front)
+                .count();
+            if size > best_size {
+                best_size = size;
+                best = Some((size, position - candidate));
+                if size == max_size {
+                    break;
+                }
+            }
+
+            candidate = self.previous[candidate];
+        }
+
+        best
+    }
+
+    fn insert_until(&mut self, position: usize) {
+        while self.inserted < position {
+            if self.inserted + Self::MIN_SIZE <= self.data.len() {
+                let hash = self.hash(self.inserted);
+                self.previous[self.inserted] = self.head[hash];
+                self.head[hash] = self.inserted;
+            }
+            self.inserted += 1;
+        }
+    }
+
+    fn hash(&self, position: usize) -> usize {
+        let bytes = &self.data[position..(position + Self::MIN_SIZE)];
+        let value = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+        let Ok(hash) = usize::try_from(value.wrapping_mul(0x9e3779b1) >> (32 - Self::HASH_BITS))
+        else {
+            unreachable!("Hash must be smaller than usize.");
+        };
+        hash
+    }
+}
@@ -1,4 +1,6 @@
 mod dictionary;
+mod encoder;
+mod matcher;
 mod pair;
 mod parameters;
 mod weight_window;
@@ -7,10 +9,27 @@ use std::{error::Error, fmt::Display, io::Read};
 
 use crate::granny2::compression::buffer::Buffer;
 
-use {dictionary::Dictionary, pair::Pair, parameters::Parameters, weight_window::WeightWindow};
+use {
+    dictionary::Dictionary, encoder::Encoder, matcher::Matcher, pair::Pair, parameters::Parameters,
+    weight_window::WeightWindow,
+};
 
 static SIZES: [usize; 4] = [128, 192, 256, 512];
 
+/// Parameters used for every block written by [`Oodle::compress`].
+///
+/// Literals are plain bytes and back references reach as far as the
+/// decoder can represent. The counts are large enough to hold every
+/// value, so there is always room for a value not yet seen.
+const COMPRESS_PARAMETERS: Parameters = Parameters {
+    decoded_value_max: 256,
+    backref_value_max: 0xffff,
+    decoded_count: 256,
+    _padding: 0,
+    highbit_count: 64,
+    sizes_count: [65; 4],
+};
+
 #[derive(Debug)]
 pub struct Oodle {
     numerator: u32,
@@ -19,6 +38,14 @@ pub struct Oodle {
 }
 
 impl Oodle {
+    /// Decompresses a stream of three blocks, switching parameters at
+    /// `stop_0` and `stop_1`.
+    ///
+    /// Tests only cover streams written by [`Oodle::compress`]. How the
+    /// models are read, literals taken from the low byte of the decoded
+    /// value and the first range escaping new values, is yet to be checked
+    /// against sections written by the Granny runtime, which the ignored
+    /// `samples` test does on the files found under `GRANNY2_SAMPLES`.
     pub fn decompress<T: Read>(
         reader: &mut T,
         compressed_size: usize,
@@ -62,6 +89,213 @@ impl Oodle {
         Ok(decompressed.inner())
     }
 
+    /// Compresses `data` into a stream readable by [`Oodle::decompress`]
+    /// with the same `stop_0` and `stop_1`.
+    pub fn compress(data: &[u8], stop_0: usize, stop_1: usize) -> Result<Vec<u8>, OodleError> {
+        if stop_0 > stop_1 {
+            return Err(OodleError::StopOutOfBounds(stop_0));
+        }
+        if stop_1 > data.len() {
+            return Err(OodleError::StopOutOfBounds(stop_1));
+        }
+
+        let mut compressed = vec![];
+        for _ in 0..3 {
+            COMPRESS_PARAMETERS.write(&mut compressed)?;
+        }
+
+        let mut encoder = Encoder::new();
+        let mut matcher = Matcher::new(data);
+
+        let mut position = 0;
+        for stop in [stop_0, stop_1, data.len()] {
+            let mut dictionary = Dictionary::from(&COMPRESS_PARAMETERS);
+            let start = position;
+
+            while position < stop {
+                let Ok(backref_max) = usize::try_from(dictionary.backref_value_max) else {
+                    unreachable!("Backref Value Max must be smaller than usize.");
+                };
+                let (backref_size, backref_offset) = matcher
+                    .find(position, stop, backref_max.min(position - start))
+                    .map_or((1, 0), |(size, offset)| (Self::backref_size(size), offset));
+
+                Self::compress_block(
+                    &mut encoder,
+                    &mut dictionary,
+                    backref_size,
+                    backref_offset,
+                    data[position],
+                );
+                position += backref_size;
+            }
+        }
+
+        compressed.extend(encoder.finish());
+        Ok(compressed)
+    }
+
+    /// Largest size a back reference can encode that is not above `size`.
+    fn backref_size(size: usize) -> usize {
+        SIZES
+            .iter()
+            .rev()
+            .copied()
+            .find(|backref_size| *backref_size <= size)
+            .unwrap_or(size.min(61))
+    }
+
+    fn compress_block(
+        encoder: &mut Encoder,
+        dictionary: &mut Dictionary,
+        backref_size: usize,
+        backref_offset: usize,
+        literal: u8,
+    ) {
+        let size_value = if backref_offset == 0 {
+            0
+        } else if let Some(position) = SIZES.iter().position(|size| *size == backref_size) {
+            61 + position
+        } else {
+            backref_size - 1
+        };
+        let Ok(size_value) = u16::try_from(size_value) else {
+            unreachable!("Size value must be smaller than u16.");
+        };
+
+        if Self::try_compress_block(
+            encoder,
+            &mut dictionary.size_window[dictionary.backref_size],
+            size_value,
+        ) {
+            encoder.encode_value(65, size_value);
+        }
+        dictionary.backref_size = usize::from(size_value);
+
+        if dictionary.backref_size > 0 {
+            let Ok(decoded_size) = u32::try_from(dictionary.decoded_size) else {
+                unreachable!("Decoded size must be smaller than u32");
+            };
+            let Ok(backref_range) = u16::try_from(dictionary.backref_value_max.min(decoded_size))
+            else {
+                unreachable!("Backref Range should be smaller than u16.");
+            };
+
+            let Ok(offset) = u16::try_from(backref_offset - 1) else {
+                unreachable!("Backref offset must be smaller than u16.");
+            };
+            let (d3, d4, d5) = (offset & 3, offset >> 10, (offset >> 2) & 0xff);
+
+            if Self::try_compress_block(encoder, &mut dictionary.lowbit_window, d3) {
+                let Ok(lowbit) = u16::try_from(dictionary.lowbit_value_max) else {
+                    unreachable!("Lowbit Value Max should be smaller than u16.");
+                };
+                encoder.encode_value(lowbit, d3);
+            }
+
+            if Self::try_compress_block(encoder, &mut dictionary.highbit_window, d4) {
+                encoder.encode_value(backref_range / 1024 + 1, d4);
+            }
+
+            if Self::try_compress_block(encoder, &mut dictionary.midbit_window[usize::from(d4)], d5)
+            {
+                encoder.encode_value((backref_range / 4 + 1).min(256), d5);
+            }
+
+            dictionary.decoded_size += backref_size;
+        } else {
+            let i = 0;
+            if Self::try_compress_block(
+                encoder,
+                &mut dictionary.decoded_window[i],
+                u16::from(literal),
+            ) {
+                let Ok(decoded_max) = u16::try_from(dictionary.decoded_value_max) else {
+                    unreachable!("Decoded Value Max should be smaller than u16.");
+                };
+                encoder.encode_value(decoded_max, u16::from(literal));
+            }
+
+            dictionary.decoded_size += 1;
+        }
+    }
+
+    /// Mirrors [`Oodle::try_decompress_block`], returning `true` when
+    /// `value` is new to the window and must be written in full.
+    fn try_compress_block(
+        encoder: &mut Encoder,
+        weight_window: &mut WeightWindow,
+        value: u16,
+    ) -> bool {
+        if weight_window.weight_total >= weight_window.threshold_range_rebuild {
+            if weight_window.threshold_range_rebuild >= weight_window.threshold_weight_rebuild {
+                weight_window.rebuild_weights();
+            }
+            weight_window.rebuild_ranges();
+        }
+
+        // A known value whose range rounded down to nothing can't be
+        // decoded, so it is sent again as a new value
+        let ranged = weight_window.ranges.len() - 1;
+        let range = (1..ranged)
+            .find(|range| {
+                weight_window.values[*range] == value
+                    && weight_window.ranges[range + 1] > weight_window.ranges[*range]
+            })
+            .unwrap_or(0);
+
+        let range_start = weight_window.ranges[range];
+        let range_size = weight_window.ranges[range + 1] - range_start;
+        if range_size == 0 {
+            // The new value range only empties once the window is full,
+            // and by then every value has a range of its own
+            unreachable!("New value range must not be empty.");
+        }
+        encoder.encode(0x4000, range_start, range_size);
+
+        weight_window.weights[range] += 1;
+        weight_window.weight_total += 1;
+
+        if range > 0 {
+            return false;
+        }
+
+        if weight_window.weights.len() >= weight_window.ranges.len() {
+            let Ok(len_diff) =
+                u16::try_from(weight_window.weights.len() - weight_window.ranges.len() + 1)
+            else {
+                unreachable!("Length difference must be smaller than u16");
+            };
+            let unranged = weight_window.values[ranged..]
+                .iter()
+                .position(|known| *known == value);
+
+            if let Some(position) = unranged {
+                encoder.encode_value(2, 1);
+                let Ok(position_u16) = u16::try_from(position) else {
+                    unreachable!("Position must be smaller than u16.");
+                };
+                encoder.encode_value(len_diff, position_u16);
+
+                weight_window.weights[ranged + position] += 2;
+                weight_window.weight_total += 2;
+
+                return false;
+            }
+            encoder.encode_value(2, 0);
+        }
+
+        weight_window.values.push(value);
+        weight_window.weights.push(2);
+        weight_window.weight_total += 2;
+
+        if weight_window.weights.len() == usize::from(weight_window.count_cap) {
+            weight_window.weight_total -= std::mem::take(&mut weight_window.weights[0]);
+        }
+
+        true
+    }
+
     fn decompress_block(
         &mut self,
         block: &mut &[u8],
@@ -136,7 +370,7 @@ impl Oodle {
                 d2.value = new_val;
             }
 
-            decompressed.push(d2.value.to_le_bytes()[1]);
+            decompressed.push(d2.value.to_le_bytes()[0]);
             dictionary.decoded_size += 1;
 
             Ok(1)
@@ -172,7 +406,7 @@ impl Oodle {
         weight_window.weights[range] += 1;
         weight_window.weight_total += 1;
 
-        if range == 0 {
+        if range > 0 {
             Pair {
                 index: 0xffff,
                 value: weight_window.values[range],
@@ -251,6 +485,7 @@ impl Oodle {
 #[derive(Debug)]
 pub enum OodleError {
     Decompress,
+    StopOutOfBounds(usize),
     Io,
 }
 
@@ -258,6 +493,9 @@ impl Display for OodleError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Decompress => write!(f, "Failed to decompress."),
+            Self::StopOutOfBounds(stop) => {
+                write!(f, "Stop {} is outside of the data to compress.", stop)
+            }
             Self::Io => write!(f, "Oodle failed to read compressed data."),
         }
     }
@@ -271,3 +509,103 @@ impl From<std::io::Error> for OodleError {
         Self::Io
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Oodle, OodleError};
+
+    fn round_trip(data: &[u8], stop_0: usize, stop_1: usize) {
+        let compressed = Oodle::compress(data, stop_0, stop_1).unwrap();
+        let decompressed = Oodle::decompress(
+            &mut compressed.as_slice(),
+            compressed.len(),
+            data.len(),
+            stop_0,
+            stop_1,
+        )
+        .unwrap();
+        assert_eq!(decompressed, data);
+    }
+
+    /// Xorshift, so test data does not depend on a random crate.
+    fn random(seed: &mut u64) -> u64 {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        *seed
+    }
+
+    #[test]
+    fn decompress_vector() {
+        // Repeated literals, and a back reference overlapping the data it
+        // copies
+        let compressed = [
+            0xff, 0xff, 0x00, 0x80, 0x40, 0x00, 0x00, 0x80, 0x41, 0x41, 0x41, 0x41, 0xff, 0xff,
+            0x00, 0x80, 0x40, 0x00, 0x00, 0x80, 0x41, 0x41, 0x41, 0x41, 0xff, 0xff, 0x00, 0x80,
+            0x40, 0x00, 0x00, 0x80, 0x41, 0x41, 0x41, 0x41, 0x01, 0x98, 0x11, 0xfe, 0x35, 0x6e,
+            0xd8, 0x47, 0x2f, 0x79, 0x08, 0xb4, 0x12, 0x12, 0xdf, 0x29, 0x6d, 0x0b, 0x40, 0x98,
+            0xce, 0x51, 0x99, 0x24, 0xbb, 0x0e, 0x8f, 0xea, 0x70, 0xb4, 0x78, 0xc2, 0xa6, 0x00,
+        ];
+        let data = b"granny granny granny grrrrrrrrrrrrranny";
+        let decompressed =
+            Oodle::decompress(&mut &compressed[..], compressed.len(), data.len(), 7, 14).unwrap();
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn round_trip_small() {
+        round_trip(&[], 0, 0);
+        round_trip(&[42], 0, 1);
+        round_trip(b"abababababababababab", 4, 10);
+        round_trip(&[0; 1000], 0, 0);
+    }
+
+    #[test]
+    fn round_trip_every_value() {
+        // Fills every literal window and rebuilds their weights many times
+        let mut seed = 0x1234_5678;
+        let data = (0..200_000)
+            .map(|_| random(&mut seed).to_le_bytes()[0])
+            .collect::<Vec<_>>();
+        round_trip(&data, 50_000, 120_000);
+    }
+
+    #[test]
+    fn round_trip_matches() {
+        let mut seed = 0x9abc_def0;
+        for _ in 0..8 {
+            let size = usize::try_from(random(&mut seed) % 150_000).unwrap();
+            let alphabet = 1 + random(&mut seed) % 256;
+            let mut data = Vec::with_capacity(size);
+            while data.len() < size {
+                if data.len() > 8 && random(&mut seed).is_multiple_of(3) {
+                    // Copies from up to past the furthest back reference
+                    let offset =
+                        1 + usize::try_from(random(&mut seed)).unwrap() % data.len().min(80_000);
+                    let length = 1 + random(&mut seed) % 700;
+                    for _ in 0..length {
+                        data.push(data[data.len() - offset]);
+                    }
+                } else {
+                    data.push(u8::try_from(random(&mut seed) % alphabet).unwrap());
+                }
+            }
+            let stop_0 = usize::try_from(random(&mut seed)).unwrap() % (data.len() + 1);
+            let stop_1 =
+                stop_0 + usize::try_from(random(&mut seed)).unwrap() % (data.len() - stop_0 + 1);
+            round_trip(&data, stop_0, stop_1);
+        }
+    }
+
+    #[test]
+    fn stops_out_of_bounds() {
+        assert!(matches!(
+            Oodle::compress(b"abc", 2, 1),
+            Err(OodleError::StopOutOfBounds(2))
+        ));
+        assert!(matches!(
+            Oodle::compress(b"abc", 0, 4),
+            Err(OodleError::StopOutOfBounds(4))
+        ));
+    }
+}
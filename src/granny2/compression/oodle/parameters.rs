@@ -1,4 +1,4 @@
-use std::io::Read;
+use std::io::{Read, Write};
 
 #[derive(Debug)]
 pub struct Parameters {
@@ -45,4 +45,13 @@ impl Parameters {
             sizes_count,
         })
     }
+
+    pub fn write<T: Write>(&self, writer: &mut T) -> Result<(), std::io::Error> {
+        let top = (self.decoded_value_max << 23) | self.backref_value_max;
+        let bottom = (self.decoded_count << 23) | (self._padding << 13) | self.highbit_count;
+
+        writer.write_all(&top.to_le_bytes())?;
+        writer.write_all(&bottom.to_le_bytes())?;
+        writer.write_all(&self.sizes_count)
+    }
 }
@@ -42,10 +42,8 @@ impl WeightWindow {
     }
 
     pub fn rebuild_ranges(&mut self) {
-        if self.ranges.len() != self.weights.len() + 1 {
-            assert!(self.ranges.len() > self.weights.len() + 1);
-            self.ranges.truncate(self.weights.len() + 1);
-        }
+        // Values added since the last rebuild get a range of their own
+        self.ranges.resize(self.weights.len() + 1, 0);
 
         let Ok(range_weight) = u16::try_from((8 * 0x4000) / u32::from(self.weight_total)) else {
             unreachable!("Range Weight must be smaller than u16.");
@@ -83,7 +81,7 @@ impl WeightWindow {
         while i < self.weights.len() {
             while i < self.weights.len() && self.weights[i] == 0 {
                 self.weights.swap_remove(i);
-                self.values.swap_remove(1);
+                self.values.swap_remove(i);
             }
             i += 1;
         }
@@ -93,6 +91,7 @@ impl WeightWindow {
                 unreachable!("Weights must have more than 2 values");
             };
             if let Some(position) = self.weights[1..].iter().rposition(|weight| weight == max) {
+                let position = position + 1;
                 let len = self.weights.len();
                 self.weights.swap(position, len - 1);
                 self.values.swap(position, len - 1);
@@ -105,3 +104,49 @@ impl WeightWindow {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::WeightWindow;
+
+    fn window(values: &[u16], weights: &[u16]) -> WeightWindow {
+        let mut window = WeightWindow::new(255, 256);
+        window.values = values.to_vec();
+        window.weights = weights.to_vec();
+        window.weight_total = weights.iter().sum();
+        window
+    }
+
+    #[test]
+    fn rebuild_weights_keeps_values_with_weights() {
+        let mut window = window(&[0, 10, 20, 30, 40], &[4, 6, 1, 9, 1]);
+        window.rebuild_weights();
+
+        // Values whose weight halves to nothing are dropped along with it
+        assert_eq!(window.values.len(), window.weights.len());
+        let mut pairs = window
+            .values
+            .iter()
+            .zip(&window.weights)
+            .map(|(value, weight)| (*value, *weight))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, [(0, 2), (10, 3), (30, 4)]);
+        assert_eq!(window.weight_total, 9);
+    }
+
+    #[test]
+    fn rebuild_weights_moves_heaviest_value_last() {
+        let mut window = window(&[0, 10, 20, 30], &[2, 8, 4, 2]);
+        window.rebuild_weights();
+        assert_eq!(window.values, [0, 30, 20, 10]);
+        assert_eq!(window.weights, [1, 1, 2, 4]);
+    }
+
+    #[test]
+    fn rebuild_ranges_covers_new_values() {
+        let mut window = window(&[0, 10, 20], &[4, 2, 2]);
+        window.rebuild_ranges();
+        assert_eq!(window.ranges, [0, 0x2000, 0x3000, 0x4000]);
+    }
+}
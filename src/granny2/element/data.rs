@@ -1,6 +1,14 @@
 use crate::granny2::transform::Transform;
 
+/// Value of a member.
+///
+/// Numeric members holding more than one value are read at once into one
+/// of the `*Array` variants. Half floats are widened to `f32` on read, while
+/// normalized integers keep their raw value, see [`Element::normalized`].
+///
+/// [`Element::normalized`]: super::Element::normalized
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Data {
     Empty,
     Int8(i8),
@@ -9,7 +17,24 @@ pub enum Data {
     UInt16(u16),
     Int32(i32),
     UInt32(u32),
+    Int8Norm(i8),
+    UInt8Norm(u8),
+    Int16Norm(i16),
+    UInt16Norm(u16),
+    Real16(f32),
     Real32(f32),
+    Int8Array(Vec<i8>),
+    UInt8Array(Vec<u8>),
+    Int16Array(Vec<i16>),
+    UInt16Array(Vec<u16>),
+    Int32Array(Vec<i32>),
+    UInt32Array(Vec<u32>),
+    Int8NormArray(Vec<i8>),
+    UInt8NormArray(Vec<u8>),
+    Int16NormArray(Vec<i16>),
+    UInt16NormArray(Vec<u16>),
+    Real16Array(Vec<f32>),
+    Real32Array(Vec<f32>),
     Transform(Transform),
     String(Box<str>),
     Array(u64, u64),
@@ -0,0 +1,413 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::{Hash, Hasher},
+    io::{BufRead, Seek},
+    sync::Arc,
+};
+
+use crate::granny2::format::Format;
+
+use super::{ElementError, Info, TypeId};
+
+/// Layout of an object, as stored on the type definitions of a file.
+///
+/// Definitions are compared and hashed by their members alone, so the same
+/// type found on different files, or at different positions, is equal.
+/// Displaying one gives its C declaration.
+///
+/// Recursive types are cut where parsing first reaches a type it is already
+/// inside of, so the same type parsed from another one may be cut at another
+/// member and not be equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeDefinition {
+    pub members: Vec<MemberDefinition>,
+}
+
+/// Member of a [`TypeDefinition`].
+///
+/// Its `extra_ptr` is left out of comparisons and hashes, being a pointer
+/// value that differs between files holding the same type.
+#[derive(Debug, Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemberDefinition {
+    pub name: Box<str>,
+    pub member_type: TypeId,
+    pub array_size: usize,
+    pub extra: [u8; 12],
+    pub extra_ptr: usize,
+    /// Type of the referenced or inlined objects.
+    ///
+    /// Variants carry their type on the object instead, so only the
+    /// definition of an [`Element`](super::Element) holding one knows it.
+    /// A member referencing a type it is part of has none either, which
+    /// member that is depending on the type parsing started from.
+    pub definition: Option<Arc<TypeDefinition>>,
+}
+
+/// Type definitions parsed so far, identified by their position.
+#[derive(Default)]
+pub(super) struct Definitions {
+    parsed: HashMap<u64, Arc<TypeDefinition>>,
+    parsing: HashSet<u64>,
+}
+
+impl TypeDefinition {
+    /// Type definition at `types_pos`, with the definitions it references.
+    pub fn parse<T: BufRead + Seek>(
+        reader: &mut T,
+        types_pos: u64,
+        format: Format,
+    ) -> Result<Arc<Self>, ElementError> {
+        Self::parse_cached(reader, types_pos, format, &mut Definitions::default())
+    }
+
+    pub(super) fn parse_cached<T: BufRead + Seek>(
+        reader: &mut T,
+        types_pos: u64,
+        format: Format,
+        definitions: &mut Definitions,
+    ) -> Result<Arc<Self>, ElementError> {
+        if let Some(definition) = definitions.parsed.get(&types_pos) {
+            return Ok(definition.clone());
+        }
+        definitions.parsing.insert(types_pos);
+
+        let mut members = vec![];
+        for info in Info::parse(reader, types_pos, format)? {
+            let children = match (info.element_type, info.children_offset) {
+                (TypeId::EmptyReference, 0) => None,
+                (
+                    TypeId::Inline
+                    | TypeId::Reference
+                    | TypeId::EmptyReference
+                    | TypeId::ReferenceToArray
+                    | TypeId::ArrayOfReferences,
+                    children_offset,
+                ) if !definitions.parsing.contains(&children_offset) => Some(children_offset),
+                _ => None,
+            };
+            let definition = children
+                .map(|children| Self::parse_cached(reader, children, format, definitions))
+                .transpose()?;
+            members.push(MemberDefinition::new(
+                info.read_name(reader)?,
+                &info,
+                definition,
+            ));
+        }
+
+        definitions.parsing.remove(&types_pos);
+        let definition = Arc::new(Self { members });
+        definitions.parsed.insert(types_pos, definition.clone());
+        Ok(definition)
+    }
+
+    fn write_members(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        writeln!(f, "struct {{")?;
+        for member in &self.members {
+            member.write_declaration(f, indent + 1)?;
+        }
+        write!(f, "{:indent$}}}", "", indent = indent * 4)
+    }
+}
+
+impl Display for TypeDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_members(f, 0)?;
+        write!(f, ";")
+    }
+}
+
+impl PartialEq for MemberDefinition {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.member_type == other.member_type
+            && self.array_size == other.array_size
+            && self.extra == other.extra
+            && self.definition == other.definition
+    }
+}
+
+impl Hash for MemberDefinition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.member_type.hash(state);
+        self.array_size.hash(state);
+        self.extra.hash(state);
+        self.definition.hash(state);
+    }
+}
+
+impl MemberDefinition {
+    pub fn new(name: Box<str>, info: &Info, definition: Option<Arc<TypeDefinition>>) -> Self {
+        Self {
+            name,
+            member_type: info.element_type,
+            array_size: info.array_size,
+            extra: info.extra,
+            extra_ptr: info.extra_ptr,
+            definition,
+        }
+    }
+
+    /// Writes the member as the fields of a C struct, following the names
+    /// of the Granny SDK.
+    fn write_declaration(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        indent: usize,
+    ) -> std::fmt::Result {
+        let name = &self.name;
+        let pad = indent * 4;
+        let array = if self.array_size > 1 {
+            format!("[{}]", self.array_size)
+        } else {
+            String::new()
+        };
+        let scalar = match self.member_type {
+            TypeId::Int8 => Some("granny_int8"),
+            TypeId::UInt8 => Some("granny_uint8"),
+            TypeId::Int8Norm => Some("granny_int8 /* normalized */"),
+            TypeId::UInt8Norm => Some("granny_uint8 /* normalized */"),
+            TypeId::Int16 => Some("granny_int16"),
+            TypeId::UInt16 => Some("granny_uint16"),
+            TypeId::Int16Norm => Some("granny_int16 /* normalized */"),
+            TypeId::UInt16Norm => Some("granny_uint16 /* normalized */"),
+            TypeId::Int32 => Some("granny_int32"),
+            TypeId::UInt32 => Some("granny_uint32"),
+            TypeId::Real16 => Some("granny_real16"),
+            TypeId::Real32 => Some("granny_real32"),
+            TypeId::Transform => Some("granny_transform"),
+            TypeId::String => Some("char *"),
+            _ => None,
+        };
+        if let Some(scalar) = scalar {
+            let space = if scalar.ends_with('*') { "" } else { " " };
+            return writeln!(f, "{:pad$}{}{}{}{};", "", scalar, space, name, array);
+        }
+
+        match self.member_type {
+            TypeId::None | TypeId::Removed => writeln!(f, "{:pad$}/* removed {} */", "", name),
+            member_type => {
+                let pointer = match member_type {
+                    TypeId::VariantReference => {
+                        writeln!(f, "{:pad$}granny_data_type_definition *{}Type;", "", name)?;
+                        "*"
+                    }
+                    TypeId::ReferenceToVariantArray => {
+                        writeln!(f, "{:pad$}granny_data_type_definition *{}Type;", "", name)?;
+                        writeln!(f, "{:pad$}granny_int32 {}Count;", "", name)?;
+                        "*"
+                    }
+                    TypeId::ReferenceToArray => {
+                        writeln!(f, "{:pad$}granny_int32 {}Count;", "", name)?;
+                        "*"
+                    }
+                    TypeId::ArrayOfReferences => {
+                        writeln!(f, "{:pad$}granny_int32 {}Count;", "", name)?;
+                        "**"
+                    }
+                    TypeId::Inline => "",
+                    _ => "*",
+                };
+                write!(f, "{:pad$}", "")?;
+                match (&self.definition, member_type) {
+                    (Some(definition), _) => {
+                        definition.write_members(f, indent)?;
+                        writeln!(f, " {}{}{};", pointer, name, array)
+                    }
+                    (
+                        None,
+                        TypeId::EmptyReference
+                        | TypeId::VariantReference
+                        | TypeId::ReferenceToVariantArray,
+                    ) => writeln!(f, "void {}{}{};", pointer, name, array),
+                    (None, _) => writeln!(f, "void {}{}{}; /* recursive */", pointer, name, array),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        hash::{DefaultHasher, Hash, Hasher},
+        io::Cursor,
+        sync::Arc,
+    };
+
+    use crate::granny2::format::Format;
+
+    use super::{TypeDefinition, TypeId};
+
+    const RECORD: usize = 32;
+
+    /// Member type, name, index of the list of its children and array size.
+    type Member<'a> = (TypeId, &'a str, Option<usize>, u32);
+
+    /// Little endian 32-bit lists of type records, each ended by an empty
+    /// one and followed by the names, with the position of every list.
+    fn types(lists: &[&[Member]]) -> (Vec<u8>, Vec<usize>) {
+        let mut positions = vec![];
+        let mut end = 0;
+        for list in lists {
+            positions.push(end);
+            end += (list.len() + 1) * RECORD;
+        }
+
+        let mut bytes = vec![0; end];
+        for (list, start) in lists.iter().zip(&positions) {
+            for (i, (member_type, name, children, array_size)) in list.iter().enumerate() {
+                let pos = start + i * RECORD;
+                let name_pos = u32::try_from(bytes.len()).unwrap();
+                bytes.extend_from_slice(name.as_bytes());
+                bytes.push(0);
+                let children = children.map_or(0, |list| positions[list]);
+                let children = u32::try_from(children).unwrap();
+                bytes[pos..(pos + 4)].copy_from_slice(&u32::from(*member_type).to_le_bytes());
+                bytes[(pos + 4)..(pos + 8)].copy_from_slice(&name_pos.to_le_bytes());
+                bytes[(pos + 8)..(pos + 12)].copy_from_slice(&children.to_le_bytes());
+                bytes[(pos + 12)..(pos + 16)].copy_from_slice(&array_size.to_le_bytes());
+            }
+        }
+        (bytes, positions)
+    }
+
+    fn parse(bytes: &[u8], pos: usize) -> Arc<TypeDefinition> {
+        let pos = u64::try_from(pos).unwrap();
+        TypeDefinition::parse(&mut Cursor::new(bytes), pos, Format::LITTLE_ENDIAN_32).unwrap()
+    }
+
+    fn hash(definition: &TypeDefinition) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        definition.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn displays_c_declarations() {
+        let (bytes, positions) = types(&[
+            &[
+                (TypeId::Int32, "Count", None, 0),
+                (TypeId::Real32, "Origin", None, 3),
+                (TypeId::String, "Name", None, 0),
+                (TypeId::ReferenceToArray, "Bones", Some(1), 0),
+                (TypeId::Inline, "Layout", Some(2), 0),
+                (TypeId::ArrayOfReferences, "Maps", Some(1), 0),
+                (TypeId::VariantReference, "Extended", None, 0),
+                (TypeId::Removed, "Old", None, 0),
+            ],
+            &[(TypeId::Int16Norm, "Weight", None, 0)],
+            &[(TypeId::UInt8, "Shift", None, 4)],
+        ]);
+        assert_eq!(
+            parse(&bytes, positions[0]).to_string(),
+            "struct {
+    granny_int32 Count;
+    granny_real32 Origin[3];
+    char *Name;
+    granny_int32 BonesCount;
+    struct {
+        granny_int16 /* normalized */ Weight;
+    } *Bones;
+    struct {
+        granny_uint8 Shift[4];
+    } Layout;
+    granny_int32 MapsCount;
+    struct {
+        granny_int16 /* normalized */ Weight;
+    } **Maps;
+    granny_data_type_definition *ExtendedType;
+    void *Extended;
+    /* removed Old */
+};"
+        );
+    }
+
+    #[test]
+    fn equal_types_of_different_files_are_equal() {
+        let bone: &[Member] = &[
+            (TypeId::String, "Name", None, 0),
+            (TypeId::Real32, "Origin", None, 3),
+        ];
+        let (bytes, positions) = types(&[&[(TypeId::ReferenceToArray, "Bones", Some(1), 0)], bone]);
+        let (mut other_bytes, other_positions) = types(&[
+            &[(TypeId::Int32, "Count", None, 0)],
+            &[(TypeId::ReferenceToArray, "Bones", Some(2), 0)],
+            bone,
+        ]);
+        // Pointer values of the two files differ
+        other_bytes[other_positions[2] + 28] = 0x42;
+        let definition = parse(&bytes, positions[0]);
+        let other = parse(&other_bytes, other_positions[1]);
+        let other_bone = other.members[0].definition.clone().unwrap();
+        assert_eq!(other_bone.members[0].extra_ptr, 0x42);
+        assert_eq!(definition, other);
+        assert_eq!(hash(&definition), hash(&other));
+
+        // Any other field of the members counts
+        let (mut bytes, positions) = types(&[&[
+            (TypeId::String, "Name", None, 0),
+            (TypeId::Real32, "Origin", None, 4),
+        ]]);
+        assert_ne!(parse(&bytes, positions[0]), other_bone);
+        bytes[RECORD + 12] = 3;
+        assert_eq!(parse(&bytes, positions[0]), other_bone);
+        // Extra bytes of the name
+        bytes[16] = 1;
+        assert_ne!(parse(&bytes, positions[0]), other_bone);
+    }
+
+    #[test]
+    fn recursive_types_are_cut() {
+        let (bytes, positions) = types(&[&[
+            (TypeId::Int32, "Value", None, 0),
+            (TypeId::Reference, "Next", Some(0), 0),
+        ]]);
+        let definition = parse(&bytes, positions[0]);
+        assert_eq!(definition.members[1].definition, None);
+        assert_eq!(
+            definition.to_string(),
+            "struct {
+    granny_int32 Value;
+    void *Next; /* recursive */
+};"
+        );
+
+        // Types referencing each other are cut on the one parsed last
+        let (bytes, positions) = types(&[
+            &[(TypeId::Reference, "B", Some(1), 0)],
+            &[(TypeId::Reference, "A", Some(0), 0)],
+        ]);
+        let a = parse(&bytes, positions[0]);
+        let b = parse(&bytes, positions[1]);
+        let b_of_a = a.members[0].definition.clone().unwrap();
+        assert_eq!(b_of_a.members[0].definition, None);
+        let a_of_b = b.members[0].definition.clone().unwrap();
+        assert_eq!(a_of_b.members[0].definition, None);
+        assert_ne!(b_of_a, b);
+        assert_ne!(a_of_b, a);
+    }
+
+    #[test]
+    fn types_are_parsed_once() {
+        let (bytes, positions) = types(&[
+            &[
+                (TypeId::Reference, "First", Some(1), 0),
+                (TypeId::ArrayOfReferences, "Others", Some(1), 0),
+            ],
+            &[(TypeId::Int32, "Value", None, 0)],
+        ]);
+        let definition = parse(&bytes, positions[0]);
+        let [first, others] = &definition.members[..] else {
+            panic!("Type must have two members.");
+        };
+        assert!(Arc::ptr_eq(
+            first.definition.as_ref().unwrap(),
+            others.definition.as_ref().unwrap()
+        ));
+    }
+}
@@ -0,0 +1,167 @@
+/// Widens an IEEE 754 half precision float to `f32`.
+///
+/// Every half value, subnormals, infinities and NaN payloads included, has
+/// an exact `f32` representation, so [`f32_to_f16`] gives back `bits`.
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let sign = u32::from(bits & 0x8000) << 16;
+    let exponent = u32::from((bits >> 10) & 0x1f);
+    let mantissa = u32::from(bits & 0x3ff);
+
+    let bits = match (exponent, mantissa) {
+        (0, 0) => sign,
+        (0, _) => {
+            // Subnormal, normalized by moving its highest set bit to the
+            // implicit bit
+            let shift = mantissa.leading_zeros() - 21;
+            sign | ((113 - shift) << 23) | (((mantissa << shift) & 0x3ff) << 13)
+        }
+        (0x1f, _) => sign | 0x7f80_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 112) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(bits)
+}
+
+/// Narrows an `f32` to an IEEE 754 half precision float, rounding to the
+/// nearest even value.
+///
+/// Values too large for a half become infinities, NaN stays NaN.
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = (bits >> 16) & 0x8000;
+    let exponent = (bits >> 23) & 0xff;
+    let mantissa = bits & 0x7f_ffff;
+
+    let magnitude = match exponent {
+        0xff => {
+            let payload = mantissa >> 13;
+            if mantissa != 0 && payload == 0 {
+                // NaN whose payload would be lost, keep it a quiet NaN
+                0x7e00
+            } else {
+                0x7c00 | payload
+            }
+        }
+        143.. => 0x7c00,
+        113.. => round(((exponent - 112) << 10) | (mantissa >> 13), mantissa, 13),
+        102.. => {
+            let mantissa = mantissa | 0x80_0000;
+            let shift = 126 - exponent;
+            round(mantissa >> shift, mantissa, shift)
+        }
+        _ => 0,
+    };
+
+    let Ok(half) = u16::try_from(sign | magnitude) else {
+        unreachable!("Half float must fit in 16 bits.");
+    };
+    half
+}
+
+/// Rounds `value`, the `mantissa` shifted right by `shift` bits, to the
+/// nearest even value. A carry into the exponent is the correct result.
+fn round(value: u32, mantissa: u32, shift: u32) -> u32 {
+    let remainder = mantissa & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    if remainder > halfway || (remainder == halfway && value & 1 == 1) {
+        value + 1
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{f16_to_f32, f32_to_f16};
+
+    #[test]
+    fn zeros_keep_their_sign() {
+        assert_eq!(f16_to_f32(0x0000).to_bits(), 0.0_f32.to_bits());
+        assert_eq!(f16_to_f32(0x8000).to_bits(), (-0.0_f32).to_bits());
+        assert_eq!(f32_to_f16(0.0), 0x0000);
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+    }
+
+    #[test]
+    fn subnormals() {
+        let smallest = 2_f32.powi(-24);
+        assert_eq!(f16_to_f32(0x0001), smallest);
+        assert_eq!(f16_to_f32(0x03ff), 1023. * smallest);
+        assert_eq!(f16_to_f32(0x8001), -smallest);
+        assert_eq!(f32_to_f16(smallest), 0x0001);
+        assert_eq!(f32_to_f16(1023. * smallest), 0x03ff);
+    }
+
+    #[test]
+    fn normals() {
+        assert_eq!(f16_to_f32(0x0400), 2_f32.powi(-14));
+        assert_eq!(f16_to_f32(0x7bff), 65504.);
+        assert_eq!(f16_to_f32(0x3c00), 1.);
+        assert_eq!(f16_to_f32(0xc000), -2.);
+        assert_eq!(f32_to_f16(2_f32.powi(-14)), 0x0400);
+        assert_eq!(f32_to_f16(65504.), 0x7bff);
+        assert_eq!(f32_to_f16(0.5), 0x3800);
+    }
+
+    #[test]
+    fn infinities_and_nan() {
+        assert_eq!(f16_to_f32(0x7c00), f32::INFINITY);
+        assert_eq!(f16_to_f32(0xfc00), f32::NEG_INFINITY);
+        assert_eq!(f32_to_f16(f32::INFINITY), 0x7c00);
+        assert_eq!(f32_to_f16(f32::NEG_INFINITY), 0xfc00);
+
+        // Payload moves to the top of the f32 mantissa
+        let nan = f16_to_f32(0x7e01);
+        assert!(nan.is_nan());
+        assert_eq!(nan.to_bits(), 0x7fc0_2000);
+        assert_eq!(f32_to_f16(nan), 0x7e01);
+        assert_eq!(f32_to_f16(f16_to_f32(0xfd00)), 0xfd00);
+        // Payload only on bits a half drops
+        assert_eq!(f32_to_f16(f32::from_bits(0x7f80_0001)), 0x7e00);
+    }
+
+    #[test]
+    fn overflow_becomes_infinity() {
+        // Halfway to the next exponent, rounding up to it
+        assert_eq!(f32_to_f16(65520.), 0x7c00);
+        assert_eq!(f32_to_f16(65519.), 0x7bff);
+        assert_eq!(f32_to_f16(1e6), 0x7c00);
+        assert_eq!(f32_to_f16(-1e6), 0xfc00);
+        assert_eq!(f32_to_f16(f32::MAX), 0x7c00);
+    }
+
+    #[test]
+    fn rounds_ties_to_even() {
+        let ulp = 2_f32.powi(-10);
+        // Between 0x3c00 and 0x3c01, 0x3c00 being even
+        assert_eq!(f32_to_f16(1. + ulp / 2.), 0x3c00);
+        // Between 0x3c01 and 0x3c02
+        assert_eq!(f32_to_f16(1. + 3. * ulp / 2.), 0x3c02);
+        assert_eq!(f32_to_f16(1. + ulp / 2. + 2_f32.powi(-20)), 0x3c01);
+        assert_eq!(f32_to_f16(1. + ulp / 2. - 2_f32.powi(-20)), 0x3c00);
+        // Carry from the mantissa into the exponent
+        assert_eq!(f32_to_f16(2. - ulp / 4.), 0x4000);
+
+        // Subnormals round the same way, the smallest halfway to zero
+        let smallest = 2_f32.powi(-24);
+        assert_eq!(f32_to_f16(smallest / 2.), 0x0000);
+        assert_eq!(f32_to_f16(3. * smallest / 2.), 0x0002);
+        assert_eq!(f32_to_f16(smallest / 2. + 2_f32.powi(-40)), 0x0001);
+        assert_eq!(f32_to_f16(smallest / 4.), 0x0000);
+        assert_eq!(f32_to_f16(-smallest / 4.), 0x8000);
+        // Largest subnormal rounding up to the smallest normal
+        assert_eq!(f32_to_f16(2_f32.powi(-14) - smallest / 4.), 0x0400);
+    }
+
+    #[test]
+    fn every_half_round_trips() {
+        for bits in 0..=u16::MAX {
+            assert_eq!(
+                f32_to_f16(f16_to_f32(bits)),
+                bits,
+                "{:#06x} widens to {:?}",
+                bits,
+                f16_to_f32(bits)
+            );
+        }
+    }
+}
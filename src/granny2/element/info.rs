@@ -3,22 +3,29 @@ use std::{
     io::{BufRead, Read, Seek, SeekFrom},
 };
 
-use crate::granny2::transform::Transform;
+use crate::granny2::{format::Format, transform::Transform};
 
-use super::{type_id::TypeId, Data};
+use super::{half::f16_to_f32, type_id::TypeId, Data};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Info {
     pub element_type: TypeId,
     name_offset: u64,
     pub children_offset: u64,
     pub array_size: usize,
     pub extra: [u8; 12],
+    /// Pointer sized field closing the member, kept as read without
+    /// relocating it, which is also how it is written back.
     pub extra_ptr: usize,
 }
 
 impl Info {
-    pub fn parse<T: Read + Seek>(reader: &mut T, types_pos: u64) -> Result<Vec<Self>, InfoError> {
+    pub fn parse<T: Read + Seek>(
+        reader: &mut T,
+        types_pos: u64,
+        format: Format,
+    ) -> Result<Vec<Self>, InfoError> {
         let rewind_pos = reader.stream_position()?;
         reader.seek(SeekFrom::Start(types_pos))?;
 
@@ -36,13 +43,13 @@ impl Info {
 
             let name_offset = {
                 let mut buffer = [0; 8];
-                reader.read_exact(&mut buffer[0..4])?;
+                reader.read_exact(&mut buffer[..format.pointer_size()])?;
                 u64::from_le_bytes(buffer)
             };
 
             let children_offset = {
                 let mut buffer = [0; 8];
-                reader.read_exact(&mut buffer[0..4])?;
+                reader.read_exact(&mut buffer[..format.pointer_size()])?;
                 u64::from_le_bytes(buffer)
             };
 
@@ -68,7 +75,7 @@ impl Info {
 
             let extra_ptr = {
                 let mut buffer = [0; 8];
-                reader.read_exact(&mut buffer[0..4])?;
+                reader.read_exact(&mut buffer[..format.pointer_size()])?;
                 usize::from_le_bytes(buffer)
             };
 
@@ -106,6 +113,77 @@ impl Info {
         }
     }
 
+    /// Size of an [`Info`] record in the file.
+    pub const fn sizeof(format: Format) -> usize {
+        20 + 3 * format.pointer_size()
+    }
+
+    pub fn name_offset(&self) -> u64 {
+        self.name_offset
+    }
+
+    /// Size of the member's data in the object, including inlined members.
+    pub fn size<T: Read + Seek>(&self, reader: &mut T, format: Format) -> Result<usize, InfoError> {
+        let pointer_size = format.pointer_size();
+        let size = match self.element_type {
+            TypeId::Int8 | TypeId::UInt8 | TypeId::Int8Norm | TypeId::UInt8Norm => 1,
+            TypeId::Int16
+            | TypeId::UInt16
+            | TypeId::Int16Norm
+            | TypeId::UInt16Norm
+            | TypeId::Real16 => 2,
+            TypeId::Int32 | TypeId::UInt32 | TypeId::Real32 => 4,
+            TypeId::Transform => 68,
+            TypeId::String | TypeId::Reference | TypeId::EmptyReference => pointer_size,
+            TypeId::ReferenceToArray | TypeId::ArrayOfReferences => 4 + pointer_size,
+            TypeId::VariantReference => 2 * pointer_size,
+            TypeId::ReferenceToVariantArray => 4 + 2 * pointer_size,
+            TypeId::Inline => Self::struct_size(reader, self.children_offset, format)?,
+            TypeId::None | TypeId::Removed => 0,
+        };
+        size.checked_mul(self.array_size)
+            .ok_or(InfoError::InvalidArraySize)
+    }
+
+    /// Size of an object described by the type at `types_pos`.
+    pub fn struct_size<T: Read + Seek>(
+        reader: &mut T,
+        types_pos: u64,
+        format: Format,
+    ) -> Result<usize, InfoError> {
+        Self::struct_size_inside(reader, types_pos, format, &mut vec![])
+    }
+
+    /// Size of an object inlined in objects of the types in `outer`, none
+    /// of which it can inline in turn.
+    fn struct_size_inside<T: Read + Seek>(
+        reader: &mut T,
+        types_pos: u64,
+        format: Format,
+        outer: &mut Vec<u64>,
+    ) -> Result<usize, InfoError> {
+        if outer.contains(&types_pos) {
+            return Err(InfoError::RecursiveInline);
+        }
+        outer.push(types_pos);
+        let mut size = 0_usize;
+        for info in Self::parse(reader, types_pos, format)? {
+            let member_size = match info.element_type {
+                TypeId::Inline => {
+                    Self::struct_size_inside(reader, info.children_offset, format, outer)?
+                        .checked_mul(info.array_size)
+                        .ok_or(InfoError::InvalidArraySize)?
+                }
+                _ => info.size(reader, format)?,
+            };
+            size = size
+                .checked_add(member_size)
+                .ok_or(InfoError::InvalidArraySize)?;
+        }
+        outer.pop();
+        Ok(size)
+    }
+
     pub fn read_name<T: BufRead + Seek>(&self, reader: &mut T) -> Result<Box<str>, std::io::Error> {
         if self.name_offset == 0 {
             Ok(String::new().into_boxed_str())
@@ -117,30 +195,63 @@ impl Info {
     pub fn read_data<T: BufRead + Seek>(
         &self,
         reader: &mut T,
+        format: Format,
     ) -> Result<Vec<Data>, std::io::Error> {
+        if self.array_size > 1 {
+            if let Some(array) = self.read_array(reader)? {
+                return Ok(vec![array]);
+            }
+        }
+
+        let pointer_size = format.pointer_size();
         let data = (0..self.array_size)
             .map(|_| -> Result<Data, std::io::Error> {
                 let data = match self.element_type {
-                    TypeId::Int8 | TypeId::Int8Norm => {
+                    TypeId::Int8 => {
                         let mut buffer = [0];
                         reader.read_exact(&mut buffer)?;
                         Data::Int8(i8::from_le_bytes(buffer))
                     }
-                    TypeId::UInt8 | TypeId::UInt8Norm => {
+                    TypeId::Int8Norm => {
+                        let mut buffer = [0];
+                        reader.read_exact(&mut buffer)?;
+                        Data::Int8Norm(i8::from_le_bytes(buffer))
+                    }
+                    TypeId::UInt8 => {
                         let mut buffer = [0];
                         reader.read_exact(&mut buffer)?;
                         Data::UInt8(u8::from_le_bytes(buffer))
                     }
-                    TypeId::Int16 | TypeId::Int16Norm => {
+                    TypeId::UInt8Norm => {
+                        let mut buffer = [0];
+                        reader.read_exact(&mut buffer)?;
+                        Data::UInt8Norm(u8::from_le_bytes(buffer))
+                    }
+                    TypeId::Int16 => {
                         let mut buffer = [0; 2];
                         reader.read_exact(&mut buffer)?;
                         Data::Int16(i16::from_le_bytes(buffer))
                     }
-                    TypeId::UInt16 | TypeId::UInt16Norm | TypeId::Real16 => {
+                    TypeId::Int16Norm => {
+                        let mut buffer = [0; 2];
+                        reader.read_exact(&mut buffer)?;
+                        Data::Int16Norm(i16::from_le_bytes(buffer))
+                    }
+                    TypeId::UInt16 => {
                         let mut buffer = [0; 2];
                         reader.read_exact(&mut buffer)?;
                         Data::UInt16(u16::from_le_bytes(buffer))
                     }
+                    TypeId::UInt16Norm => {
+                        let mut buffer = [0; 2];
+                        reader.read_exact(&mut buffer)?;
+                        Data::UInt16Norm(u16::from_le_bytes(buffer))
+                    }
+                    TypeId::Real16 => {
+                        let mut buffer = [0; 2];
+                        reader.read_exact(&mut buffer)?;
+                        Data::Real16(f16_to_f32(u16::from_le_bytes(buffer)))
+                    }
                     TypeId::Int32 => {
                         let mut buffer = [0; 4];
                         reader.read_exact(&mut buffer)?;
@@ -158,40 +269,45 @@ impl Info {
                     }
                     TypeId::Transform => Data::Transform(Transform::parse(reader)?),
                     TypeId::String => {
-                        let mut buffer = [0; 4];
-                        reader.read_exact(&mut buffer)?;
-                        let pos = u32::from_le_bytes(buffer);
-                        Data::String(Self::read_name_from_pos(u64::from(pos), reader)?)
+                        let mut buffer = [0; 8];
+                        reader.read_exact(&mut buffer[..pointer_size])?;
+                        let pos = u64::from_le_bytes(buffer);
+                        if pos == 0 {
+                            Data::String(String::new().into_boxed_str())
+                        } else {
+                            Data::String(Self::read_name_from_pos(pos, reader)?)
+                        }
                     }
                     TypeId::Reference | TypeId::EmptyReference => {
                         let mut buffer = [0; 8];
-                        reader.read_exact(&mut buffer[..4])?;
+                        reader.read_exact(&mut buffer[..pointer_size])?;
                         Data::Reference(u64::from_le_bytes(buffer))
                     }
                     TypeId::ReferenceToArray => {
                         let mut buffer = [0; 8];
                         reader.read_exact(&mut buffer[..4])?;
                         let size = u64::from_le_bytes(buffer);
-                        reader.read_exact(&mut buffer[..4])?;
+                        reader.read_exact(&mut buffer[..pointer_size])?;
                         let pos = u64::from_le_bytes(buffer);
 
                         Data::Array(size, pos)
                     }
                     TypeId::VariantReference => {
                         let mut buffer = [0; 8];
-                        reader.read_exact(&mut buffer[..4])?;
+                        reader.read_exact(&mut buffer[..pointer_size])?;
                         let offset = u64::from_le_bytes(buffer);
-                        reader.read_exact(&mut buffer[..4])?;
+                        reader.read_exact(&mut buffer[..pointer_size])?;
                         let pos = u64::from_le_bytes(buffer);
                         Data::Variant(offset, pos)
                     }
                     TypeId::ReferenceToVariantArray => {
                         let mut buffer = [0; 8];
-                        reader.read_exact(&mut buffer[..4])?;
+                        reader.read_exact(&mut buffer[..pointer_size])?;
                         let offset = u64::from_le_bytes(buffer);
+                        buffer = [0; 8];
                         reader.read_exact(&mut buffer[..4])?;
                         let size = u64::from_le_bytes(buffer);
-                        reader.read_exact(&mut buffer[..4])?;
+                        reader.read_exact(&mut buffer[..pointer_size])?;
                         let pos = u64::from_le_bytes(buffer);
                         Data::VariantArray(size, offset, pos)
                     }
@@ -199,14 +315,22 @@ impl Info {
                         let mut buffer = [0; 8];
                         reader.read_exact(&mut buffer[..4])?;
                         let size = u64::from_le_bytes(buffer);
-                        reader.read_exact(&mut buffer[..4])?;
+                        reader.read_exact(&mut buffer[..pointer_size])?;
                         let offset = u64::from_le_bytes(buffer);
 
+                        let Ok(pointer_step) = u64::try_from(pointer_size) else {
+                            unreachable!("Pointer size must be smaller than u64.");
+                        };
+
                         let rewind_pos = reader.stream_position()?;
                         let mut references = vec![];
                         for i in 0..size {
-                            reader.seek(SeekFrom::Start(offset + i * 4))?;
-                            reader.read_exact(&mut buffer[..4])?;
+                            let pos = i
+                                .checked_mul(pointer_step)
+                                .and_then(|step| offset.checked_add(step))
+                                .ok_or_else(out_of_range)?;
+                            reader.seek(SeekFrom::Start(pos))?;
+                            reader.read_exact(&mut buffer[..pointer_size])?;
                             let reference = u64::from_le_bytes(buffer);
                             references.push(reference);
                         }
@@ -222,6 +346,74 @@ impl Info {
         Ok(data)
     }
 
+    /// Reads all values of a numeric member at once, `None` for other types.
+    fn read_array<T: Read>(&self, reader: &mut T) -> Result<Option<Data>, std::io::Error> {
+        let value_size: usize = match self.element_type {
+            TypeId::Int8 | TypeId::UInt8 | TypeId::Int8Norm | TypeId::UInt8Norm => 1,
+            TypeId::Int16
+            | TypeId::UInt16
+            | TypeId::Int16Norm
+            | TypeId::UInt16Norm
+            | TypeId::Real16 => 2,
+            TypeId::Int32 | TypeId::UInt32 | TypeId::Real32 => 4,
+            _ => return Ok(None),
+        };
+
+        // Read in steps, so a bogus array size fails at the end of the data
+        // instead of allocating for it
+        let length = value_size
+            .checked_mul(self.array_size)
+            .ok_or_else(out_of_range)?;
+        let mut buffer = vec![];
+        reader
+            .by_ref()
+            .take(to_u64(length))
+            .read_to_end(&mut buffer)?;
+        if buffer.len() != length {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Array would be read after end of data.",
+            ));
+        }
+
+        let pairs = || buffer.chunks_exact(2).map(|pair| [pair[0], pair[1]]);
+        let quads = || {
+            buffer
+                .chunks_exact(4)
+                .map(|quad| [quad[0], quad[1], quad[2], quad[3]])
+        };
+        let array = match self.element_type {
+            TypeId::Int8 => Data::Int8Array(
+                buffer
+                    .iter()
+                    .map(|byte| i8::from_le_bytes([*byte]))
+                    .collect(),
+            ),
+            TypeId::Int8Norm => Data::Int8NormArray(
+                buffer
+                    .iter()
+                    .map(|byte| i8::from_le_bytes([*byte]))
+                    .collect(),
+            ),
+            TypeId::UInt8 => Data::UInt8Array(buffer),
+            TypeId::UInt8Norm => Data::UInt8NormArray(buffer),
+            TypeId::Int16 => Data::Int16Array(pairs().map(i16::from_le_bytes).collect()),
+            TypeId::Int16Norm => Data::Int16NormArray(pairs().map(i16::from_le_bytes).collect()),
+            TypeId::UInt16 => Data::UInt16Array(pairs().map(u16::from_le_bytes).collect()),
+            TypeId::UInt16Norm => Data::UInt16NormArray(pairs().map(u16::from_le_bytes).collect()),
+            TypeId::Real16 => Data::Real16Array(
+                pairs()
+                    .map(|pair| f16_to_f32(u16::from_le_bytes(pair)))
+                    .collect(),
+            ),
+            TypeId::Int32 => Data::Int32Array(quads().map(i32::from_le_bytes).collect()),
+            TypeId::UInt32 => Data::UInt32Array(quads().map(u32::from_le_bytes).collect()),
+            TypeId::Real32 => Data::Real32Array(quads().map(f32::from_le_bytes).collect()),
+            _ => unreachable!("Only numeric types have a value size."),
+        };
+        Ok(Some(array))
+    }
+
     fn read_name_from_pos<T: BufRead + Seek>(
         pos: u64,
         reader: &mut T,
@@ -237,9 +429,14 @@ impl Info {
             )
         })?;
         let mut buffer = Vec::new();
-        let _ = reader.read_until(0, &mut buffer).unwrap();
+        reader.read_until(0, &mut buffer)?;
         // Pop trailing '\0'
-        assert_eq!(buffer.pop(), Some(0));
+        if buffer.pop() != Some(0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Name is not terminated.",
+            ));
+        }
 
         // Return to previous position
         reader.seek(SeekFrom::Start(rewind_pos))?;
@@ -254,6 +451,7 @@ impl Info {
 pub enum InfoError {
     InvalidArraySize,
     InvalidChildrenOffsetForVariant,
+    RecursiveInline,
     Io,
 }
 
@@ -271,7 +469,19 @@ impl Display for InfoError {
             Self::InvalidChildrenOffsetForVariant => {
                 write!(f, "Children Offset should be zero for Variants")
             }
+            Self::RecursiveInline => write!(f, "Inline member has its own type."),
             Self::Io => write!(f, "Couldn't read info due to Io error."),
         }
     }
 }
+
+fn out_of_range() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "Position is out of range.")
+}
+
+fn to_u64(value: usize) -> u64 {
+    let Ok(value) = u64::try_from(value) else {
+        unreachable!("Size must be smaller than u64.");
+    };
+    value
+}
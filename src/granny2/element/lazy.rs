@@ -0,0 +1,380 @@
+use std::{
+    borrow::Cow,
+    io::{Seek, SeekFrom},
+    sync::Arc,
+};
+
+use crate::granny2::{format::Format, section::SectionData};
+
+use super::{Data, ElementError, Info, TypeDefinition, TypeId};
+
+/// Object on the decompressed section data, whose members are only decoded
+/// when accessed.
+#[derive(Debug, Clone, Copy)]
+pub struct LazyObject<'a> {
+    data: &'a SectionData<'a>,
+    format: Format,
+    types_pos: u64,
+    object_pos: u64,
+}
+
+impl<'a> LazyObject<'a> {
+    /// Object at `object_pos`, described by the type at `types_pos`.
+    ///
+    /// `data` must already have its relocations applied, and be marshalled
+    /// if the file is big endian.
+    pub fn new(data: &'a SectionData<'a>, format: Format, types_pos: u64, object_pos: u64) -> Self {
+        Self {
+            data,
+            format,
+            types_pos,
+            object_pos,
+        }
+    }
+
+    /// Position of the object on the section data.
+    pub fn position(&self) -> u64 {
+        self.object_pos
+    }
+
+    /// Type of the object, with the types it references.
+    pub fn definition(&self) -> Result<Arc<TypeDefinition>, ElementError> {
+        TypeDefinition::parse(&mut self.data.reader(), self.types_pos, self.format)
+    }
+
+    /// Members of the object, in the order of its type.
+    pub fn members(&self) -> Result<Vec<LazyElement<'a>>, ElementError> {
+        let mut reader = self.data.reader();
+        let mut pos = self.object_pos;
+        let mut members = vec![];
+        for info in Info::parse(&mut reader, self.types_pos, self.format)? {
+            let size = info.size(&mut reader, self.format)?;
+            members.push(LazyElement {
+                data: self.data,
+                format: self.format,
+                info,
+                pos,
+            });
+            pos = pos
+                .checked_add(to_u64(size))
+                .ok_or(ElementError::OutOfRange(pos))?;
+        }
+        Ok(members)
+    }
+
+    /// Member named `name`.
+    pub fn child(&self, name: &str) -> Result<LazyElement<'a>, ElementError> {
+        for member in self.members()? {
+            if &*member.name()? == name {
+                return Ok(member);
+            }
+        }
+        Err(ElementError::NotFound(name.into()))
+    }
+}
+
+/// Member of a [`LazyObject`].
+///
+/// Only the member's type is known up front, its name, data and the
+/// objects it references are read from the section data on each access.
+#[derive(Debug, Clone)]
+pub struct LazyElement<'a> {
+    data: &'a SectionData<'a>,
+    format: Format,
+    info: Info,
+    pos: u64,
+}
+
+impl<'a> LazyElement<'a> {
+    pub fn info(&self) -> &Info {
+        &self.info
+    }
+
+    pub fn element_type(&self) -> TypeId {
+        self.info.element_type
+    }
+
+    /// Name of the member, borrowed from the section data unless it is
+    /// not valid UTF-8.
+    pub fn name(&self) -> Result<Cow<'a, str>, ElementError> {
+        match self.info.name_offset() {
+            0 => Ok(Cow::Borrowed("")),
+            pos => self.str_at(pos),
+        }
+    }
+
+    /// Value of a `String` member, borrowed like [`LazyElement::name`].
+    pub fn string(&self) -> Result<Cow<'a, str>, ElementError> {
+        if self.info.element_type != TypeId::String {
+            return Err(ElementError::WrongType(self.info.element_type));
+        }
+        let mut buffer = [0; 8];
+        buffer[..self.format.pointer_size()].copy_from_slice(self.bytes()?);
+        match u64::from_le_bytes(buffer) {
+            0 => Ok(Cow::Borrowed("")),
+            pos => self.str_at(pos),
+        }
+    }
+
+    /// Bytes of the member on its object.
+    pub fn bytes(&self) -> Result<&'a [u8], ElementError> {
+        let size = self.info.size(&mut self.data.reader(), self.format)?;
+        self.slice(self.pos, size)
+    }
+
+    /// Bytes of all items of an array of objects, such as the vertices of
+    /// a vertex data, without decoding them.
+    pub fn array_bytes(&self) -> Result<&'a [u8], ElementError> {
+        let (types_pos, count, pos) = self.array()?;
+        let item_size = Info::struct_size(&mut self.data.reader(), types_pos, self.format)?;
+        let size = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_mul(item_size))
+            .ok_or(ElementError::OutOfRange(pos))?;
+        self.slice(pos, size)
+    }
+
+    /// Values of the member, as on [`Element::data`](super::Element::data).
+    pub fn data(&self) -> Result<Vec<Data>, ElementError> {
+        let mut reader = self.data.reader();
+        reader.seek(SeekFrom::Start(self.pos))?;
+        Ok(self.info.read_data(&mut reader, self.format)?)
+    }
+
+    /// Object held by a reference or inlined, `None` for null references.
+    pub fn object(&self) -> Result<Option<LazyObject<'a>>, ElementError> {
+        let object = match (self.info.element_type, self.data()?.as_slice()) {
+            (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(0)])
+            | (TypeId::VariantReference, [Data::Variant(0, _) | Data::Variant(_, 0)]) => None,
+            (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(pos)]) => {
+                Some(self.object_at(self.info.children_offset, *pos))
+            }
+            (TypeId::VariantReference, [Data::Variant(offset, pos)]) => {
+                Some(self.object_at(*offset, *pos))
+            }
+            (TypeId::Inline, [Data::Empty]) => {
+                Some(self.object_at(self.info.children_offset, self.pos))
+            }
+            (element_type, _) => return Err(ElementError::WrongType(element_type)),
+        };
+        Ok(object)
+    }
+
+    /// Member `name` of the object held by this member.
+    pub fn child(&self, name: &str) -> Result<LazyElement<'a>, ElementError> {
+        self.object()?
+            .ok_or(ElementError::NullReference)?
+            .child(name)
+    }
+
+    /// Number of items on an array of objects or of references.
+    pub fn len(&self) -> Result<usize, ElementError> {
+        let len = match (self.info.element_type, self.data()?.as_slice()) {
+            (TypeId::ReferenceToArray, [Data::Array(size, _)])
+            | (TypeId::ReferenceToVariantArray, [Data::VariantArray(size, _, _)]) => {
+                let Ok(size) = usize::try_from(*size) else {
+                    unreachable!("Array size must be smaller than usize.");
+                };
+                size
+            }
+            (TypeId::ArrayOfReferences, [Data::ArrayOfReferences(references)]) => references.len(),
+            (TypeId::Inline, data) => data.len(),
+            (element_type, _) => return Err(ElementError::WrongType(element_type)),
+        };
+        Ok(len)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, ElementError> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Item `index` of an array of objects or of references.
+    pub fn index(&self, index: usize) -> Result<LazyObject<'a>, ElementError> {
+        let data = self.data()?;
+        let item = match (self.info.element_type, data.as_slice()) {
+            (TypeId::ReferenceToArray, [Data::Array(size, pos)]) => {
+                self.item(self.info.children_offset, (*size, *pos), index)?
+            }
+            (TypeId::ReferenceToVariantArray, [Data::VariantArray(size, offset, pos)]) => {
+                self.item(*offset, (*size, *pos), index)?
+            }
+            (TypeId::ArrayOfReferences, [Data::ArrayOfReferences(references)]) => {
+                match references.get(index) {
+                    Some(0) => return Err(ElementError::NullReference),
+                    Some(pos) => self.object_at(self.info.children_offset, *pos),
+                    None => return Err(ElementError::OutOfBounds(index)),
+                }
+            }
+            (TypeId::Inline, data) => self.item(
+                self.info.children_offset,
+                (to_u64(data.len()), self.pos),
+                index,
+            )?,
+            (element_type, _) => return Err(ElementError::WrongType(element_type)),
+        };
+        Ok(item)
+    }
+
+    /// Item of objects laid out one after the other.
+    fn item(
+        &self,
+        types_pos: u64,
+        (size, pos): (u64, u64),
+        index: usize,
+    ) -> Result<LazyObject<'a>, ElementError> {
+        if to_u64(index) >= size {
+            return Err(ElementError::OutOfBounds(index));
+        }
+        let item_size = Info::struct_size(&mut self.data.reader(), types_pos, self.format)?;
+        let item_pos = index
+            .checked_mul(item_size)
+            .and_then(|offset| pos.checked_add(to_u64(offset)))
+            .ok_or(ElementError::OutOfRange(pos))?;
+        Ok(self.object_at(types_pos, item_pos))
+    }
+
+    /// Type, item count and position of an array of objects.
+    fn array(&self) -> Result<(u64, u64, u64), ElementError> {
+        match self.data()?.as_slice() {
+            [Data::Array(count, pos)] => Ok((self.info.children_offset, *count, *pos)),
+            [Data::VariantArray(count, offset, pos)] => Ok((*offset, *count, *pos)),
+            _ => Err(ElementError::WrongType(self.info.element_type)),
+        }
+    }
+
+    /// `size` bytes at `pos`, failing unless all of them are on the same
+    /// section.
+    fn slice(&self, pos: u64, size: usize) -> Result<&'a [u8], ElementError> {
+        self.data
+            .get(pos, size)
+            .ok_or(ElementError::OutOfRange(pos))
+    }
+
+    /// Null terminated string at `pos`, which must end on its section.
+    fn str_at(&self, pos: u64) -> Result<Cow<'a, str>, ElementError> {
+        let data = self
+            .data
+            .tail(pos)
+            .filter(|data| !data.is_empty())
+            .ok_or(ElementError::OutOfRange(pos))?;
+        let Some(end) = data.iter().position(|byte| *byte == 0) else {
+            return Err(ElementError::UnterminatedString(pos));
+        };
+        Ok(String::from_utf8_lossy(&data[..end]))
+    }
+
+    fn object_at(&self, types_pos: u64, object_pos: u64) -> LazyObject<'a> {
+        LazyObject::new(self.data, self.format, types_pos, object_pos)
+    }
+}
+
+/// Typed views of numeric members, as on [`Element`](super::Element).
+///
+/// Arrays of objects whose members all have the type are viewed as one
+/// slice, such as the indices of a topology. Values are borrowed from the
+/// section data when they are aligned, and copied otherwise.
+impl<'a> LazyElement<'a> {
+    /// Raw values of an `Int8` or `Int8Norm` member.
+    pub fn as_i8_slice(&self) -> Result<Cow<'a, [i8]>, ElementError> {
+        self.values(
+            |element_type| matches!(element_type, TypeId::Int8 | TypeId::Int8Norm),
+            i8::from_le_bytes,
+        )
+    }
+
+    /// Raw values of an `UInt8` or `UInt8Norm` member.
+    pub fn as_u8_slice(&self) -> Result<Cow<'a, [u8]>, ElementError> {
+        self.values(
+            |element_type| matches!(element_type, TypeId::UInt8 | TypeId::UInt8Norm),
+            u8::from_le_bytes,
+        )
+    }
+
+    /// Raw values of an `Int16` or `Int16Norm` member.
+    pub fn as_i16_slice(&self) -> Result<Cow<'a, [i16]>, ElementError> {
+        self.values(
+            |element_type| matches!(element_type, TypeId::Int16 | TypeId::Int16Norm),
+            i16::from_le_bytes,
+        )
+    }
+
+    /// Raw values of an `UInt16` or `UInt16Norm` member.
+    pub fn as_u16_slice(&self) -> Result<Cow<'a, [u16]>, ElementError> {
+        self.values(
+            |element_type| matches!(element_type, TypeId::UInt16 | TypeId::UInt16Norm),
+            u16::from_le_bytes,
+        )
+    }
+
+    pub fn as_i32_slice(&self) -> Result<Cow<'a, [i32]>, ElementError> {
+        self.values(
+            |element_type| element_type == TypeId::Int32,
+            i32::from_le_bytes,
+        )
+    }
+
+    pub fn as_u32_slice(&self) -> Result<Cow<'a, [u32]>, ElementError> {
+        self.values(
+            |element_type| element_type == TypeId::UInt32,
+            u32::from_le_bytes,
+        )
+    }
+
+    /// Values of a `Real32` member. Unlike on [`Element`](super::Element),
+    /// `Real16` members are not widened.
+    pub fn as_f32_slice(&self) -> Result<Cow<'a, [f32]>, ElementError> {
+        self.values(
+            |element_type| element_type == TypeId::Real32,
+            f32::from_le_bytes,
+        )
+    }
+
+    /// Values of the member, or of the array items, if `holds` their type.
+    ///
+    /// `T` must be a number, which is valid for any bytes, and
+    /// `from_le_bytes` must read it from its `N` bytes.
+    fn values<T: Copy, const N: usize>(
+        &self,
+        holds: fn(TypeId) -> bool,
+        from_le_bytes: fn([u8; N]) -> T,
+    ) -> Result<Cow<'a, [T]>, ElementError> {
+        let bytes = if holds(self.info.element_type) {
+            self.bytes()?
+        } else {
+            let (types_pos, _, _) = self.array()?;
+            let members = Info::parse(&mut self.data.reader(), types_pos, self.format)?;
+            if members.is_empty() || !members.iter().all(|member| holds(member.element_type)) {
+                return Err(ElementError::WrongType(self.info.element_type));
+            }
+            self.array_bytes()?
+        };
+
+        // Section data is little endian once marshalled
+        if cfg!(target_endian = "little") {
+            // SAFETY: Any bytes are a valid `T`, and only whole values
+            // that are aligned are borrowed.
+            let (start, values, end) = unsafe { bytes.align_to::<T>() };
+            if start.is_empty() && end.is_empty() {
+                return Ok(Cow::Borrowed(values));
+            }
+        }
+        Ok(Cow::Owned(
+            bytes
+                .chunks_exact(N)
+                .map(|value| {
+                    let Ok(value) = value.try_into() else {
+                        unreachable!("Chunks must have the size of a value.");
+                    };
+                    from_le_bytes(value)
+                })
+                .collect(),
+        ))
+    }
+}
+
+fn to_u64(value: usize) -> u64 {
+    let Ok(value) = u64::try_from(value) else {
+        unreachable!("Position must be smaller than u64.");
+    };
+    value
+}
@@ -1,26 +1,75 @@
 mod data;
+mod definition;
+mod half;
 mod info;
+mod lazy;
+mod query;
+mod schema_change;
 mod type_id;
 
 use std::{
+    collections::{HashMap, HashSet},
     error::Error,
     fmt::Display,
-    io::{BufRead, Seek},
+    io::{BufRead, Cursor, Seek},
+    sync::Arc,
 };
 
+use super::format::Format;
+
+use self::definition::Definitions;
+
 pub use self::{
     data::Data,
+    definition::{MemberDefinition, TypeDefinition},
+    half::{f16_to_f32, f32_to_f16},
     info::{Info, InfoError},
+    lazy::{LazyElement, LazyObject},
+    query::Query,
+    schema_change::{ChangeKind, SchemaChange},
     type_id::TypeId,
 };
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Element {
     pub info: Info,
     pub name: Box<str>,
-    pub children: Vec<Element>,
+    /// Members of the referenced or inlined objects.
+    ///
+    /// Objects referenced from several places are parsed once, every
+    /// reference sharing the same members.
+    pub children: Arc<[Element]>,
     pub size: usize,
     pub data: Vec<Data>,
+    /// Type of the objects in `children`, known even when there are none.
+    ///
+    /// Serialized in full with every element, deserialized elements no
+    /// longer sharing it.
+    pub definition: Option<Arc<TypeDefinition>>,
+    /// Identifies the object referenced by the element, every reference
+    /// to the same object having the same one.
+    ///
+    /// Numbered in parse order, `None` for null references and members
+    /// that don't reference an object on their own.
+    pub object: Option<usize>,
+}
+
+/// Referenced objects parsed so far, identified by their type and position.
+///
+/// Array items are only parsed once anyway, so they aren't kept.
+#[derive(Default)]
+struct Objects {
+    parsed: HashMap<(u64, u64), ParsedObject>,
+    /// Objects and array items whose members are still being parsed, a
+    /// reference to one of them being a cycle.
+    parsing: HashSet<(u64, u64)>,
+    definitions: Definitions,
+}
+
+struct ParsedObject {
+    id: usize,
+    members: Arc<[Element]>,
 }
 
 impl Element {
@@ -28,26 +77,89 @@ impl Element {
         reader: &mut T,
         types_pos: u64,
         object_pos: u64,
+        format: Format,
+    ) -> Result<Vec<Self>, ElementError> {
+        let mut objects = Objects::default();
+        objects.parsing.insert((types_pos, object_pos));
+        Self::parse_members(reader, types_pos, object_pos, format, &mut objects)
+    }
+
+    pub fn parse_single<T: BufRead + Seek>(
+        reader: &mut T,
+        info: Info,
+        format: Format,
+    ) -> Result<Self, ElementError> {
+        Self::parse_member(reader, info, format, &mut Objects::default())
+    }
+
+    /// Definition of the member, as declared on the type of its object.
+    pub fn member_definition(&self) -> MemberDefinition {
+        MemberDefinition::new(self.name.clone(), &self.info, self.definition.clone())
+    }
+
+    /// First element matching `path` among the children, see [`Query`].
+    pub fn get(&self, path: &str) -> Option<&Element> {
+        self.query(path).next()
+    }
+
+    /// Elements matching `path` among the children, see [`Query`].
+    pub fn query(&self, path: &str) -> Query<'_> {
+        Query::new(&self.children, path)
+    }
+
+    fn parse_members<T: BufRead + Seek>(
+        reader: &mut T,
+        types_pos: u64,
+        object_pos: u64,
+        format: Format,
+        objects: &mut Objects,
     ) -> Result<Vec<Self>, ElementError> {
         reader.seek(std::io::SeekFrom::Start(object_pos))?;
         let mut elements = Vec::new();
-        for type_info in Info::parse(reader, types_pos)? {
-            elements.push(Element::parse_single(reader, type_info)?);
+        for type_info in Info::parse(reader, types_pos, format)? {
+            elements.push(Element::parse_member(reader, type_info, format, objects)?);
         }
         Ok(elements)
     }
 
-    pub fn parse_single<T: BufRead + Seek>(
+    fn parse_member<T: BufRead + Seek>(
         reader: &mut T,
         info: Info,
+        format: Format,
+        objects: &mut Objects,
     ) -> Result<Self, ElementError> {
         let name = info.read_name(reader)?;
 
         let size = info.array_size;
 
-        let data = info.read_data(reader)?;
+        let data = info.read_data(reader, format)?;
+
+        let types_pos = match (info.element_type, data.as_slice()) {
+            (TypeId::EmptyReference, _) if info.children_offset == 0 => None,
+            (
+                TypeId::Inline
+                | TypeId::Reference
+                | TypeId::EmptyReference
+                | TypeId::ReferenceToArray
+                | TypeId::ArrayOfReferences,
+                _,
+            ) => Some(info.children_offset),
+            (TypeId::VariantReference, [Data::Variant(offset, _)])
+            | (TypeId::ReferenceToVariantArray, [Data::VariantArray(_, offset, _)])
+                if *offset != 0 =>
+            {
+                Some(*offset)
+            }
+            _ => None,
+        };
+        let definition = types_pos
+            .map(|types_pos| {
+                TypeDefinition::parse_cached(reader, types_pos, format, &mut objects.definitions)
+            })
+            .transpose()?;
 
-        let children = Self::read_children(reader, &info, &data)?;
+        let (object, children) =
+            Self::read_children(reader, &info, &data, &definition, format, objects)?;
 
         Ok(Element {
             info,
@@ -55,20 +167,196 @@ impl Element {
             children,
             size,
             data,
+            definition,
+            object,
         })
     }
 
+    /// Identifier and members of the object at `object_pos`, parsed only
+    /// the first time it is referenced.
+    fn shared_object<T: BufRead + Seek>(
+        reader: &mut T,
+        types_pos: u64,
+        object_pos: u64,
+        format: Format,
+        objects: &mut Objects,
+    ) -> Result<(Option<usize>, Arc<[Element]>), ElementError> {
+        let key = (types_pos, object_pos);
+        if let Some(object) = objects.parsed.get(&key) {
+            return Ok((Some(object.id), object.members.clone()));
+        }
+
+        let members: Arc<[Element]> =
+            Self::parse_object(reader, types_pos, object_pos, format, objects)?.into();
+        // Objects referenced by the members were numbered before this one
+        let id = objects.parsed.len();
+        let object = ParsedObject {
+            id,
+            members: members.clone(),
+        };
+        objects.parsed.insert(key, object);
+        Ok((Some(id), members))
+    }
+
+    /// Members of the object at `object_pos`, failing if they end up
+    /// referencing the object itself.
+    fn parse_object<T: BufRead + Seek>(
+        reader: &mut T,
+        types_pos: u64,
+        object_pos: u64,
+        format: Format,
+        objects: &mut Objects,
+    ) -> Result<Vec<Element>, ElementError> {
+        let key = (types_pos, object_pos);
+        if !objects.parsing.insert(key) {
+            return Err(ElementError::Cycle(object_pos));
+        }
+        let members = Self::parse_members(reader, types_pos, object_pos, format, objects)?;
+        objects.parsing.remove(&key);
+        Ok(members)
+    }
+
+    /// Objects laid out one after the other, parsed as array items.
+    fn objects<T: BufRead + Seek>(
+        reader: &mut T,
+        info: &Info,
+        definition: &Option<Arc<TypeDefinition>>,
+        types_pos: u64,
+        (count, object_pos): (u64, u64),
+        format: Format,
+        objects: &mut Objects,
+    ) -> Result<Arc<[Element]>, ElementError> {
+        let members = Info::parse(reader, types_pos, format)?;
+        if !members.is_empty()
+            && members
+                .iter()
+                .all(|member| member.element_type.is_numeric())
+        {
+            return Self::numeric_objects(
+                reader,
+                info,
+                definition,
+                &members,
+                (count, object_pos),
+                format,
+            );
+        }
+
+        let item_size = Info::struct_size(reader, types_pos, format)?;
+        let Ok(item_size) = u64::try_from(item_size) else {
+            unreachable!("Struct size must be smaller than u64.");
+        };
+
+        let mut children = vec![];
+        for i in 0..count {
+            let pos = object_pos + i * item_size;
+            children.push(Element {
+                info: info.clone(),
+                name: i.to_string().into_boxed_str(),
+                children: Self::parse_object(reader, types_pos, pos, format, objects)?.into(),
+                size: 1,
+                data: vec![],
+                definition: definition.clone(),
+                object: None,
+            });
+        }
+        Ok(children.into())
+    }
+
+    /// Array items whose `members` are all numbers, such as vertices.
+    ///
+    /// The whole array is read at once and the member types and names
+    /// only once, instead of seeking back to them for every item.
+    fn numeric_objects<T: BufRead + Seek>(
+        reader: &mut T,
+        info: &Info,
+        definition: &Option<Arc<TypeDefinition>>,
+        members: &[Info],
+        (count, object_pos): (u64, u64),
+        format: Format,
+    ) -> Result<Arc<[Element]>, ElementError> {
+        let sizes = members
+            .iter()
+            .map(|member| member.size(reader, format))
+            .collect::<Result<Vec<_>, _>>()?;
+        let names = members
+            .iter()
+            .map(|member| member.read_name(reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        let item_size = sizes.iter().sum::<usize>();
+
+        // Counts are checked against the data before allocating for them
+        let end = reader.seek(std::io::SeekFrom::End(0))?;
+        let length = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_mul(item_size))
+            .filter(|length| {
+                u64::try_from(*length)
+                    .ok()
+                    .and_then(|length| object_pos.checked_add(length))
+                    .is_some_and(|last| last <= end)
+            })
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "Array would be read after end of data.",
+                )
+            })?;
+        reader.seek(std::io::SeekFrom::Start(object_pos))?;
+        let mut bytes = vec![0; length];
+        reader.read_exact(&mut bytes)?;
+
+        let mut children = vec![];
+        for (i, item) in bytes.chunks_exact(item_size).enumerate() {
+            let mut item = Cursor::new(item);
+            let item_members = members
+                .iter()
+                .zip(&names)
+                .map(|(member, name)| {
+                    Ok(Element {
+                        info: member.clone(),
+                        name: name.clone(),
+                        children: Arc::from([]),
+                        size: member.array_size,
+                        data: member.read_data(&mut item, format)?,
+                        definition: None,
+                        object: None,
+                    })
+                })
+                .collect::<Result<Vec<_>, std::io::Error>>()?;
+            children.push(Element {
+                info: info.clone(),
+                name: i.to_string().into_boxed_str(),
+                children: item_members.into(),
+                size: 1,
+                data: vec![],
+                definition: definition.clone(),
+                object: None,
+            });
+        }
+        Ok(children.into())
+    }
+
+    /// Children of the member, along with the identifier of the object
+    /// when it references one.
     fn read_children<T: BufRead + Seek>(
         reader: &mut T,
         info: &Info,
         data: &[Data],
-    ) -> Result<Vec<Element>, ElementError> {
-        let rewind_pos = reader.stream_position()?;
+        definition: &Option<Arc<TypeDefinition>>,
+        format: Format,
+        objects: &mut Objects,
+    ) -> Result<(Option<usize>, Arc<[Element]>), ElementError> {
+        let mut rewind_pos = reader.stream_position()?;
 
+        let mut object = None;
         let children = match (info.element_type, data) {
-            (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(0)]) => vec![],
+            (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(0)]) => Arc::from([]),
             (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(ref_pos)]) => {
-                Self::parse(reader, info.children_offset, *ref_pos)?
+                let children;
+                (object, children) =
+                    Self::shared_object(reader, info.children_offset, *ref_pos, format, objects)?;
+                children
             }
             (TypeId::Reference, _) => {
                 unreachable!("Reference should always be paired with Reference.");
@@ -80,97 +368,212 @@ impl Element {
                 let mut children = vec![];
 
                 for (i, reference) in references.iter().enumerate() {
-                    let child = Self::parse(reader, info.children_offset, *reference)?;
+                    let (object, child) = if *reference == 0 {
+                        (None, Arc::from([]))
+                    } else {
+                        Self::shared_object(
+                            reader,
+                            info.children_offset,
+                            *reference,
+                            format,
+                            objects,
+                        )?
+                    };
                     children.push(Element {
                         info: info.clone(),
                         name: i.to_string().into_boxed_str(),
                         children: child,
                         size: 1,
                         data: vec![],
+                        definition: definition.clone(),
+                        object,
                     });
                 }
 
-                children
+                children.into()
             }
             (TypeId::ArrayOfReferences, _) => {
                 unreachable!("ArrayOfReferences should always be paired with ArrayOfReferences.");
             }
-            (TypeId::ReferenceToArray, [Data::Array(0, _)]) => {
-                vec![]
-            }
-            (TypeId::ReferenceToArray, [Data::Array(size, pos)]) => {
-                let mut children = vec![];
-
-                let mut pos = *pos;
-                for i in 0..*size {
-                    let child = Self::parse(reader, info.children_offset, pos)?;
-                    children.push(Element {
-                        info: info.clone(),
-                        name: i.to_string().into_boxed_str(),
-                        children: child,
-                        size: 1,
-                        data: vec![],
-                    });
-                    pos = reader.stream_position()?;
-                }
-
-                children
-            }
+            (TypeId::ReferenceToArray, [Data::Array(0, _)]) => Arc::from([]),
+            (TypeId::ReferenceToArray, [Data::Array(size, pos)]) => Self::objects(
+                reader,
+                info,
+                definition,
+                info.children_offset,
+                (*size, *pos),
+                format,
+                objects,
+            )?,
             (TypeId::ReferenceToArray, _) => {
                 unreachable!("ReferenceToArray should always be paired with Array.");
             }
-            (TypeId::VariantReference, [Data::Variant(0, _)]) => {
-                vec![]
-            }
+            (TypeId::VariantReference, [Data::Variant(0, _)]) => Arc::from([]),
             (TypeId::VariantReference, [Data::Variant(offset, data)]) => {
-                Self::parse(reader, *offset, *data)?
+                let children;
+                (object, children) = Self::shared_object(reader, *offset, *data, format, objects)?;
+                children
             }
             (TypeId::VariantReference, _) => {
                 unreachable!("VariantReference should always be paired with Variant.");
             }
-            (TypeId::ReferenceToVariantArray, [Data::VariantArray(0, _, _)]) => {
-                vec![]
-            }
+            (TypeId::ReferenceToVariantArray, [Data::VariantArray(0, _, _)]) => Arc::from([]),
             (TypeId::ReferenceToVariantArray, [Data::VariantArray(size, offset, data)]) => {
+                Self::objects(
+                    reader,
+                    info,
+                    definition,
+                    *offset,
+                    (*size, *data),
+                    format,
+                    objects,
+                )?
+            }
+            (TypeId::ReferenceToVariantArray, _) => {
+                unreachable!("ReferenceToVariantArray should always be paired with VariantArray.");
+            }
+            (TypeId::Inline, [Data::Empty]) => {
+                let children =
+                    Self::parse_members(reader, info.children_offset, rewind_pos, format, objects)?;
+                // Inlined members are part of this object, so continue after them
+                rewind_pos = reader.stream_position()?;
+                children.into()
+            }
+            (TypeId::Inline, data) if data.iter().all(|data| *data == Data::Empty) => {
                 let mut children = vec![];
 
-                let mut pos = *data;
-                for i in 0..*size {
-                    let child = Self::parse(reader, *offset, pos)?;
-
+                for i in 0..data.len() {
+                    let child = Self::parse_members(
+                        reader,
+                        info.children_offset,
+                        rewind_pos,
+                        format,
+                        objects,
+                    )?;
                     children.push(Element {
                         info: info.clone(),
                         name: i.to_string().into_boxed_str(),
-                        children: child,
+                        children: child.into(),
                         size: 1,
                         data: vec![],
+                        definition: definition.clone(),
+                        object: None,
                     });
-                    pos = reader.stream_position()?;
+                    rewind_pos = reader.stream_position()?;
                 }
 
-                children
-            }
-            (TypeId::ReferenceToVariantArray, _) => {
-                unreachable!("ReferenceToVariantArray should always be paired with VariantArray.");
-            }
-            (TypeId::Inline, [Data::Empty]) => {
-                Self::parse(reader, info.children_offset, rewind_pos)?
+                children.into()
             }
             (TypeId::Inline, _) => {
                 unreachable!("Inline should always be paired with Empty.");
             }
-            _ => vec![],
+            _ => Arc::from([]),
         };
 
         reader.seek(std::io::SeekFrom::Start(rewind_pos))?;
 
-        Ok(children)
+        Ok((object, children))
+    }
+}
+
+/// Typed views of numeric members, covering both single values and arrays.
+impl Element {
+    /// Raw values of an `Int8` or `Int8Norm` member.
+    pub fn as_i8_slice(&self) -> Option<&[i8]> {
+        match self.data.as_slice() {
+            [Data::Int8Array(values) | Data::Int8NormArray(values)] => Some(values),
+            [Data::Int8(value) | Data::Int8Norm(value)] => Some(std::slice::from_ref(value)),
+            _ => None,
+        }
+    }
+
+    /// Raw values of an `UInt8` or `UInt8Norm` member.
+    pub fn as_u8_slice(&self) -> Option<&[u8]> {
+        match self.data.as_slice() {
+            [Data::UInt8Array(values) | Data::UInt8NormArray(values)] => Some(values),
+            [Data::UInt8(value) | Data::UInt8Norm(value)] => Some(std::slice::from_ref(value)),
+            _ => None,
+        }
+    }
+
+    /// Raw values of an `Int16` or `Int16Norm` member.
+    pub fn as_i16_slice(&self) -> Option<&[i16]> {
+        match self.data.as_slice() {
+            [Data::Int16Array(values) | Data::Int16NormArray(values)] => Some(values),
+            [Data::Int16(value) | Data::Int16Norm(value)] => Some(std::slice::from_ref(value)),
+            _ => None,
+        }
+    }
+
+    /// Raw values of an `UInt16` or `UInt16Norm` member.
+    pub fn as_u16_slice(&self) -> Option<&[u16]> {
+        match self.data.as_slice() {
+            [Data::UInt16Array(values) | Data::UInt16NormArray(values)] => Some(values),
+            [Data::UInt16(value) | Data::UInt16Norm(value)] => Some(std::slice::from_ref(value)),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32_slice(&self) -> Option<&[i32]> {
+        match self.data.as_slice() {
+            [Data::Int32Array(values)] => Some(values),
+            [Data::Int32(value)] => Some(std::slice::from_ref(value)),
+            _ => None,
+        }
+    }
+
+    pub fn as_u32_slice(&self) -> Option<&[u32]> {
+        match self.data.as_slice() {
+            [Data::UInt32Array(values)] => Some(values),
+            [Data::UInt32(value)] => Some(std::slice::from_ref(value)),
+            _ => None,
+        }
+    }
+
+    /// Values of a `Real32` or, already widened, of a `Real16` member.
+    pub fn as_f32_slice(&self) -> Option<&[f32]> {
+        match self.data.as_slice() {
+            [Data::Real32Array(values) | Data::Real16Array(values)] => Some(values),
+            [Data::Real32(value) | Data::Real16(value)] => Some(std::slice::from_ref(value)),
+            _ => None,
+        }
+    }
+
+    /// Values of a normalized integer member as floats, dividing by the
+    /// largest value of the integer type.
+    ///
+    /// Unsigned members map to `[0, 1]` and signed ones to `[-1, 1]`, the
+    /// smallest signed value being clamped to `-1`.
+    pub fn normalized(&self) -> Option<Vec<f32>> {
+        let values = match self.info.element_type {
+            TypeId::Int8Norm => normalize(self.as_i8_slice()?, i8::MAX),
+            TypeId::UInt8Norm => normalize(self.as_u8_slice()?, u8::MAX),
+            TypeId::Int16Norm => normalize(self.as_i16_slice()?, i16::MAX),
+            TypeId::UInt16Norm => normalize(self.as_u16_slice()?, u16::MAX),
+            _ => return None,
+        };
+        Some(values)
     }
 }
 
+fn normalize<T: Copy + Into<f32>>(values: &[T], max: T) -> Vec<f32> {
+    let max = max.into();
+    values
+        .iter()
+        .map(|value| ((*value).into() / max).max(-1.))
+        .collect()
+}
+
 #[derive(Debug)]
 pub enum ElementError {
     InvalidType,
+    Cycle(u64),
+    NotFound(Box<str>),
+    WrongType(TypeId),
+    NullReference,
+    OutOfBounds(usize),
+    OutOfRange(u64),
+    UnterminatedString(u64),
     Info,
     Io,
 }
@@ -179,6 +582,21 @@ impl Display for ElementError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::InvalidType => write!(f, "Couldn't create an element from type info."),
+            Self::Cycle(pos) => write!(f, "Object at {:#x} references itself.", pos),
+            Self::NotFound(name) => write!(f, "Object has no member named {}.", name),
+            Self::WrongType(element_type) => {
+                write!(
+                    f,
+                    "Element of type {:?} does not hold objects.",
+                    element_type
+                )
+            }
+            Self::NullReference => write!(f, "Reference is null."),
+            Self::OutOfBounds(index) => write!(f, "Index {} is out of bounds.", index),
+            Self::OutOfRange(pos) => {
+                write!(f, "Position {:#x} is outside of the section data.", pos)
+            }
+            Self::UnterminatedString(pos) => write!(f, "String at {:#x} is not terminated.", pos),
             Self::Info => write!(f, "An error occurred while reading Element's Info."),
             Self::Io => write!(f, "Couldn't parse elements due to Io error."),
         }
@@ -200,3 +618,97 @@ impl From<InfoError> for ElementError {
         Self::Info
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::granny2::format::Format;
+
+    use super::{Element, TypeId};
+
+    /// Single member of `element_type` holding `values`, parsed from a
+    /// little endian 32-bit object.
+    fn member(element_type: TypeId, array_size: u32, values: &[u8]) -> Element {
+        // Member type, then the type ending the list, then the object
+        let mut bytes = vec![0; 64];
+        bytes[..4].copy_from_slice(&u32::from(element_type).to_le_bytes());
+        bytes[12..16].copy_from_slice(&array_size.to_le_bytes());
+        bytes.extend_from_slice(values);
+        let mut elements =
+            Element::parse(&mut Cursor::new(bytes), 0, 64, Format::LITTLE_ENDIAN_32).unwrap();
+        assert_eq!(elements.len(), 1);
+        elements.remove(0)
+    }
+
+    fn bytes_i16(values: &[i16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn bytes_u16(values: &[u16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn signed_values_divide_by_max() {
+        let values = [127_i8, -127, -128, 0, 64].map(|value| value.to_le_bytes()[0]);
+        let element = member(TypeId::Int8Norm, 5, &values);
+        assert_eq!(element.as_i8_slice(), Some(&[127, -127, -128, 0, 64][..]));
+        // The smallest value is clamped instead of going below -1
+        assert_eq!(
+            element.normalized(),
+            Some(vec![1., -1., -1., 0., 64. / 127.])
+        );
+
+        let element = member(
+            TypeId::Int16Norm,
+            4,
+            &bytes_i16(&[32767, -32767, -32768, -16384]),
+        );
+        assert_eq!(
+            element.normalized(),
+            Some(vec![1., -1., -1., -16384. / 32767.])
+        );
+    }
+
+    #[test]
+    fn unsigned_values_divide_by_max() {
+        let element = member(TypeId::UInt8Norm, 4, &[255, 0, 128, 1]);
+        assert_eq!(
+            element.normalized(),
+            Some(vec![1., 0., 128. / 255., 1. / 255.])
+        );
+
+        let element = member(TypeId::UInt16Norm, 3, &bytes_u16(&[65535, 0, 32768]));
+        assert_eq!(element.normalized(), Some(vec![1., 0., 32768. / 65535.]));
+    }
+
+    #[test]
+    fn single_values_are_normalized() {
+        let element = member(TypeId::Int8Norm, 0, &[0x80]);
+        assert_eq!(element.as_i8_slice(), Some(&[-128][..]));
+        assert_eq!(element.normalized(), Some(vec![-1.]));
+
+        let element = member(TypeId::UInt16Norm, 1, &bytes_u16(&[13107]));
+        assert_eq!(element.normalized(), Some(vec![0.2]));
+    }
+
+    #[test]
+    fn other_types_are_not_normalized() {
+        let element = member(TypeId::Int8, 2, &[0x80, 0x7f]);
+        assert_eq!(element.as_i8_slice(), Some(&[-128, 127][..]));
+        assert_eq!(element.normalized(), None);
+
+        let element = member(TypeId::UInt16, 0, &bytes_u16(&[65535]));
+        assert_eq!(element.normalized(), None);
+
+        let element = member(TypeId::Real32, 0, &1_f32.to_le_bytes());
+        assert_eq!(element.normalized(), None);
+    }
+}
@@ -0,0 +1,173 @@
+use std::slice::Iter;
+
+use super::Element;
+
+/// Iterator over the elements matching a path such as
+/// `Meshes/0/PrimaryVertexData/Vertices`.
+///
+/// Segments are separated by `/` and matched against the names of the
+/// elements, where `*` matches any run of characters and `?` any single
+/// character, so `Models/*/Name` matches the name of every model. Empty
+/// segments are skipped, and a path without segments matches nothing.
+///
+/// Items of arrays of objects and of references are named by their index,
+/// and the members of referenced, inlined and variant objects are the
+/// children of the member holding them, so a path goes through them the
+/// same way. Numbers are matched as a whole, `Origin/0` matching nothing
+/// even if `Origin` holds three of them.
+#[derive(Debug, Clone)]
+pub struct Query<'a> {
+    segments: Vec<Box<str>>,
+    /// Elements left to match against each segment.
+    pending: Vec<Iter<'a, Element>>,
+    /// Names of the elements matched so far.
+    path: Vec<&'a str>,
+}
+
+impl<'a> Query<'a> {
+    /// Matches `path` starting from `elements`, such as the root of a file.
+    pub fn new(elements: &'a [Element], path: &str) -> Self {
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(Box::from)
+            .collect::<Vec<_>>();
+        let pending = if segments.is_empty() {
+            vec![]
+        } else {
+            vec![elements.iter()]
+        };
+        Self {
+            segments,
+            pending,
+            path: vec![],
+        }
+    }
+
+    /// Path of the last match, with wildcards replaced by the names they
+    /// matched.
+    pub fn path(&self) -> String {
+        self.path.join("/")
+    }
+}
+
+impl<'a> Iterator for Query<'a> {
+    type Item = &'a Element;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.pending.len().checked_sub(1)?;
+            let Some(element) = self.pending[depth].next() else {
+                self.pending.pop();
+                continue;
+            };
+            if !matches(&self.segments[depth], &element.name) {
+                continue;
+            }
+
+            self.path.truncate(depth);
+            self.path.push(&element.name);
+            if depth + 1 == self.segments.len() {
+                return Some(element);
+            }
+            self.pending.push(element.children.iter());
+        }
+    }
+}
+
+/// Whether `name` matches the glob `pattern`.
+fn matches(pattern: &str, name: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return pattern == name;
+    }
+
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*`, and the name position it was tried at
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+            }
+            Some(c) if *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => {
+                // Let the last `*` take one more character
+                let Some((star, start)) = backtrack else {
+                    return false;
+                };
+                p = star;
+                n = start + 1;
+                backtrack = Some((star, n));
+            }
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn names_without_wildcards_match_exactly() {
+        assert!(matches("Name", "Name"));
+        assert!(!matches("Name", "name"));
+        assert!(!matches("Name", "Names"));
+        assert!(!matches("Names", "Name"));
+        assert!(matches("", ""));
+        assert!(!matches("", "Name"));
+    }
+
+    #[test]
+    fn star_matches_any_run() {
+        assert!(matches("*", "Name"));
+        assert!(matches("*", ""));
+        assert!(matches("**", "Name"));
+        assert!(matches("*Name", "FromFileName"));
+        assert!(matches("*Name", "Name"));
+        assert!(matches("From*", "FromFileName"));
+        assert!(matches("From*Name", "FromFileName"));
+        assert!(matches("From*Name", "FromName"));
+        assert!(!matches("From*Name", "FromFileNames"));
+        assert!(!matches("*Name", "Named"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        assert!(matches("?", "0"));
+        assert!(!matches("?", ""));
+        assert!(!matches("?", "10"));
+        assert!(matches("??", "10"));
+        assert!(matches("Bone?", "Bone3"));
+        assert!(!matches("Bone?", "Bone"));
+        // Characters, not bytes
+        assert!(matches("?", "é"));
+        assert!(matches("?*?", "ab"));
+        assert!(!matches("?*?", "a"));
+    }
+
+    #[test]
+    fn stars_backtrack() {
+        assert!(matches("a*b*c", "abc"));
+        assert!(matches("a*b*c", "aXbYc"));
+        assert!(matches("a*b*c", "abbbc"));
+        // The first `b` and `c` are not the ones matched
+        assert!(matches("a*b*c", "acbcbc"));
+        assert!(matches("a*b*c", "abcbc"));
+        assert!(!matches("a*b*c", "ac"));
+        assert!(!matches("a*b*c", "abcb"));
+        assert!(!matches("a*b*c", "bc"));
+        assert!(matches("*a?c", "aabc"));
+        assert!(matches("*ab", "aab"));
+    }
+}
@@ -0,0 +1,277 @@
+use std::{collections::HashSet, fmt::Display};
+
+use super::{MemberDefinition, TypeDefinition};
+
+/// Difference found on a member of a struct by [`TypeDefinition::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    /// Names of the members leading from the root type to the struct
+    /// holding the member, separated by `/`. Empty for the root type.
+    pub path: Box<str>,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added(MemberDefinition),
+    Removed(MemberDefinition),
+    /// Member whose type or array size changed, before and after.
+    Retyped(MemberDefinition, MemberDefinition),
+}
+
+impl TypeDefinition {
+    /// Members added, removed or retyped on `other` compared to this type,
+    /// and on the types both reference through members of the same name.
+    ///
+    /// Members are matched by name, so reordering them is not a change.
+    /// Each pair of types is compared once, at the first path reaching
+    /// them.
+    pub fn diff(&self, other: &TypeDefinition) -> Vec<SchemaChange> {
+        let mut changes = vec![];
+        diff(self, other, "", &mut HashSet::new(), &mut changes);
+        changes
+    }
+}
+
+fn diff<'a>(
+    before: &'a TypeDefinition,
+    after: &'a TypeDefinition,
+    path: &str,
+    compared: &mut HashSet<(&'a TypeDefinition, &'a TypeDefinition)>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if !compared.insert((before, after)) {
+        return;
+    }
+
+    let find = |members: &[MemberDefinition], name: &str| {
+        members.iter().position(|member| &*member.name == name)
+    };
+    let mut change = |kind| {
+        changes.push(SchemaChange {
+            path: path.into(),
+            kind,
+        })
+    };
+
+    let mut nested = vec![];
+    for member in &before.members {
+        let Some(index) = find(&after.members, &member.name) else {
+            change(ChangeKind::Removed(member.clone()));
+            continue;
+        };
+        let other = &after.members[index];
+        if member.member_type != other.member_type || member.array_size != other.array_size {
+            change(ChangeKind::Retyped(member.clone(), other.clone()));
+        }
+        if let (Some(before), Some(after)) = (&member.definition, &other.definition) {
+            if before != after {
+                nested.push((&member.name, before, after));
+            }
+        }
+    }
+    for member in &after.members {
+        if find(&before.members, &member.name).is_none() {
+            change(ChangeKind::Added(member.clone()));
+        }
+    }
+
+    for (name, before, after) in nested {
+        let path = if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", path, name)
+        };
+        diff(before, after, &path, compared, changes);
+    }
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}: {}", self.path, self.kind)
+        }
+    }
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let member_type = |member: &MemberDefinition| {
+            if member.array_size > 1 {
+                format!("{:?}[{}]", member.member_type, member.array_size)
+            } else {
+                format!("{:?}", member.member_type)
+            }
+        };
+        match self {
+            Self::Added(member) => write!(f, "+ {}: {}", member.name, member_type(member)),
+            Self::Removed(member) => write!(f, "- {}: {}", member.name, member_type(member)),
+            Self::Retyped(before, after) => write!(
+                f,
+                "~ {}: {} -> {}",
+                before.name,
+                member_type(before),
+                member_type(after)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::granny2::element::TypeId;
+
+    use super::{ChangeKind, MemberDefinition, SchemaChange, TypeDefinition};
+
+    fn member(name: &str, member_type: TypeId, array_size: usize) -> MemberDefinition {
+        MemberDefinition {
+            name: name.into(),
+            member_type,
+            array_size,
+            extra: [0; 12],
+            extra_ptr: 0,
+            definition: None,
+        }
+    }
+
+    fn reference(name: &str, definition: &Arc<TypeDefinition>) -> MemberDefinition {
+        MemberDefinition {
+            definition: Some(definition.clone()),
+            ..member(name, TypeId::ReferenceToArray, 0)
+        }
+    }
+
+    fn definition(members: Vec<MemberDefinition>) -> Arc<TypeDefinition> {
+        Arc::new(TypeDefinition { members })
+    }
+
+    fn change(path: &str, kind: ChangeKind) -> SchemaChange {
+        SchemaChange {
+            path: path.into(),
+            kind,
+        }
+    }
+
+    fn bone() -> Arc<TypeDefinition> {
+        definition(vec![
+            member("Name", TypeId::String, 0),
+            member("ParentIndex", TypeId::Int32, 0),
+            member("Origin", TypeId::Real32, 3),
+        ])
+    }
+
+    #[test]
+    fn equal_types_have_no_changes() {
+        let before = definition(vec![reference("Bones", &bone())]);
+        let after = definition(vec![reference("Bones", &bone())]);
+        assert!(before.diff(&after).is_empty());
+    }
+
+    #[test]
+    fn finds_added_removed_and_retyped_members() {
+        let before = bone();
+        let after = definition(vec![
+            member("Name", TypeId::String, 0),
+            member("ParentIndex", TypeId::Int16, 0),
+            member("Origin", TypeId::Real32, 4),
+            member("Flags", TypeId::UInt32, 0),
+        ]);
+        let changes = before.diff(&after);
+        assert_eq!(
+            changes,
+            [
+                change(
+                    "",
+                    ChangeKind::Retyped(
+                        member("ParentIndex", TypeId::Int32, 0),
+                        member("ParentIndex", TypeId::Int16, 0)
+                    )
+                ),
+                change(
+                    "",
+                    ChangeKind::Retyped(
+                        member("Origin", TypeId::Real32, 3),
+                        member("Origin", TypeId::Real32, 4)
+                    )
+                ),
+                change("", ChangeKind::Added(member("Flags", TypeId::UInt32, 0))),
+            ]
+        );
+        assert_eq!(
+            changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "~ ParentIndex: Int32 -> Int16",
+                "~ Origin: Real32[3] -> Real32[4]",
+                "+ Flags: UInt32",
+            ]
+        );
+
+        assert_eq!(
+            after.diff(&before)[2],
+            change("", ChangeKind::Removed(member("Flags", TypeId::UInt32, 0)))
+        );
+    }
+
+    #[test]
+    fn reordering_is_not_a_change() {
+        let mut members = bone().members.clone();
+        members.reverse();
+        assert!(bone().diff(&definition(members)).is_empty());
+    }
+
+    #[test]
+    fn finds_nested_changes() {
+        let mut members = bone().members.clone();
+        members.remove(1);
+        let skeleton = |bone: &Arc<TypeDefinition>| {
+            definition(vec![
+                member("Name", TypeId::String, 0),
+                reference("Bones", bone),
+            ])
+        };
+        let before = definition(vec![reference("Skeletons", &skeleton(&bone()))]);
+        let after = definition(vec![reference(
+            "Skeletons",
+            &skeleton(&definition(members)),
+        )]);
+        let changes = before.diff(&after);
+        assert_eq!(
+            changes,
+            [change(
+                "Skeletons/Bones",
+                ChangeKind::Removed(member("ParentIndex", TypeId::Int32, 0))
+            )]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "Skeletons/Bones: - ParentIndex: Int32"
+        );
+
+        // Members whose type changed are not compared further
+        let before = definition(vec![reference("Bones", &bone())]);
+        let after = definition(vec![member("Bones", TypeId::Int32, 0)]);
+        assert_eq!(before.diff(&after).len(), 1);
+    }
+
+    #[test]
+    fn shared_types_are_compared_once() {
+        let mut members = bone().members.clone();
+        members.pop();
+        let changed = definition(members);
+        let before = definition(vec![
+            reference("Bones", &bone()),
+            reference("Roots", &bone()),
+        ]);
+        let after = definition(vec![
+            reference("Bones", &changed),
+            reference("Roots", &changed),
+        ]);
+        let changes = before.diff(&after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(&*changes[0].path, "Bones");
+    }
+}
@@ -1,4 +1,5 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeId {
     /// No node
     None,
@@ -26,17 +27,17 @@ pub enum TypeId {
     Int8,
     /// 8bit number unsigned
     UInt8,
-    /// TODO: discover what changes between this and int8
+    /// 8bit number signed, normalized to `[-1, 1]` by dividing by 127
     Int8Norm,
-    /// TODO: discover what changes between this and uint8
+    /// 8bit number unsigned, normalized to `[0, 1]` by dividing by 255
     UInt8Norm,
     /// 16bit number signed
     Int16,
     /// 16bit number unsigned
     UInt16,
-    /// TODO: discover what changes between this and int16
+    /// 16bit number signed, normalized to `[-1, 1]` by dividing by 32767
     Int16Norm,
-    /// TODO: discover what changes between this and uint16
+    /// 16bit number unsigned, normalized to `[0, 1]` by dividing by 65535
     UInt16Norm,
     /// 32bit number signed
     Int32,
@@ -48,6 +49,27 @@ pub enum TypeId {
     EmptyReference,
 }
 
+impl TypeId {
+    /// Whether members of this type hold plain numbers.
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Self::Int8
+                | Self::UInt8
+                | Self::Int8Norm
+                | Self::UInt8Norm
+                | Self::Int16
+                | Self::UInt16
+                | Self::Int16Norm
+                | Self::UInt16Norm
+                | Self::Int32
+                | Self::UInt32
+                | Self::Real16
+                | Self::Real32
+        )
+    }
+}
+
 impl TryFrom<u32> for TypeId {
     type Error = u32;
 
@@ -80,3 +102,33 @@ impl TryFrom<u32> for TypeId {
         }
     }
 }
+
+impl From<TypeId> for u32 {
+    fn from(value: TypeId) -> Self {
+        match value {
+            TypeId::None => 0,
+            TypeId::Inline => 1,
+            TypeId::Reference => 2,
+            TypeId::ReferenceToArray => 3,
+            TypeId::ArrayOfReferences => 4,
+            TypeId::VariantReference => 5,
+            TypeId::Removed => 6,
+            TypeId::ReferenceToVariantArray => 7,
+            TypeId::String => 8,
+            TypeId::Transform => 9,
+            TypeId::Real32 => 10,
+            TypeId::Int8 => 11,
+            TypeId::UInt8 => 12,
+            TypeId::Int8Norm => 13,
+            TypeId::UInt8Norm => 14,
+            TypeId::Int16 => 15,
+            TypeId::UInt16 => 16,
+            TypeId::Int16Norm => 17,
+            TypeId::UInt16Norm => 18,
+            TypeId::Int32 => 19,
+            TypeId::UInt32 => 20,
+            TypeId::Real16 => 21,
+            TypeId::EmptyReference => 22,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use serde_json::{json, Value};
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+/// Binary buffer of a glTF file, with the views and accessors pointing
+/// into it.
+#[derive(Debug, Default)]
+pub struct Buffer {
+    pub data: Vec<u8>,
+    pub views: Vec<Value>,
+    pub accessors: Vec<Value>,
+}
+
+impl Buffer {
+    /// Adds a vertex attribute, `kind` being the glTF accessor type.
+    pub fn push_attribute<const N: usize>(&mut self, values: &[[f32; N]], kind: &str) -> usize {
+        let bytes = values
+            .iter()
+            .flatten()
+            .flat_map(|value| value.to_le_bytes())
+            .collect::<Vec<_>>();
+        let view = self.push_view(&bytes, Some(ARRAY_BUFFER));
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": kind,
+        }))
+    }
+
+    /// Adds vertex positions along with the bounds glTF requires for
+    /// them.
+    pub fn push_positions(&mut self, positions: &[[f32; 3]]) -> usize {
+        let accessor = self.push_attribute(positions, "VEC3");
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for position in positions {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(position[axis]);
+                max[axis] = max[axis].max(position[axis]);
+            }
+        }
+        self.accessors[accessor]["min"] = json!(min);
+        self.accessors[accessor]["max"] = json!(max);
+        accessor
+    }
+
+    pub fn push_joints(&mut self, joints: &[[u16; 4]]) -> usize {
+        let bytes = joints
+            .iter()
+            .flatten()
+            .flat_map(|joint| joint.to_le_bytes())
+            .collect::<Vec<_>>();
+        let view = self.push_view(&bytes, Some(ARRAY_BUFFER));
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_SHORT,
+            "count": joints.len(),
+            "type": "VEC4",
+        }))
+    }
+
+    pub fn push_indices(&mut self, indices: &[u32]) -> usize {
+        let bytes = indices
+            .iter()
+            .flat_map(|index| index.to_le_bytes())
+            .collect::<Vec<_>>();
+        let view = self.push_view(&bytes, Some(ELEMENT_ARRAY_BUFFER));
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }))
+    }
+
+    /// Adds data that isn't bound as vertices, such as matrices or
+    /// animation samples.
+    pub fn push_data<const N: usize>(&mut self, values: &[[f32; N]], kind: &str) -> usize {
+        let bytes = values
+            .iter()
+            .flatten()
+            .flat_map(|value| value.to_le_bytes())
+            .collect::<Vec<_>>();
+        let view = self.push_view(&bytes, None);
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": kind,
+        }))
+    }
+
+    /// Adds animation sample times, whose bounds glTF requires.
+    pub fn push_times(&mut self, times: &[f32]) -> usize {
+        let values = times.iter().map(|time| [*time]).collect::<Vec<_>>();
+        let accessor = self.push_data(&values, "SCALAR");
+        let min = times.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = times.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        self.accessors[accessor]["min"] = json!([min]);
+        self.accessors[accessor]["max"] = json!([max]);
+        accessor
+    }
+
+    fn push_view(&mut self, bytes: &[u8], target: Option<u32>) -> usize {
+        // Every accessor on this buffer has 4 byte components at most
+        self.data.resize(self.data.len().next_multiple_of(4), 0);
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.data.len(),
+            "byteLength": bytes.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.data.extend_from_slice(bytes);
+        self.views.push(view);
+        self.views.len() - 1
+    }
+
+    fn push_accessor(&mut self, accessor: Value) -> usize {
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+}
@@ -0,0 +1,561 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::granny2::{
+    file_info::{Animation, FileInfo, Mesh, Skeleton},
+    transform::Transform,
+};
+
+use super::{buffer::Buffer, Gltf, GltfError};
+
+/// Rate animations are resampled at when they don't store a time step.
+const DEFAULT_TIME_STEP: f32 = 1. / 30.;
+/// Most frames an animation is resampled into, longer animations being
+/// resampled at a coarser rate.
+const MAX_FRAMES: u16 = u16::MAX;
+
+/// Index of a glTF mesh and whether it has joints, `None` when the mesh
+/// has no vertices.
+type BuiltMesh = Option<(usize, bool)>;
+
+/// Collects the glTF objects while walking a [`FileInfo`].
+pub struct Builder<'a> {
+    file_info: &'a FileInfo<'a>,
+    buffer: Buffer,
+    nodes: Vec<Value>,
+    meshes: Vec<Value>,
+    skins: Vec<Value>,
+    animations: Vec<Value>,
+    /// glTF mesh built for a mesh and the skeleton it is skinned to.
+    built_meshes: HashMap<(usize, Option<usize>), BuiltMesh>,
+    /// Node of every bone of each model's skeleton.
+    bone_nodes: Vec<Vec<usize>>,
+}
+
+impl<'a> Builder<'a> {
+    pub fn new(file_info: &'a FileInfo<'a>) -> Self {
+        Self {
+            file_info,
+            buffer: Buffer::default(),
+            nodes: Vec::new(),
+            meshes: Vec::new(),
+            skins: Vec::new(),
+            animations: Vec::new(),
+            built_meshes: HashMap::new(),
+            bone_nodes: vec![Vec::new(); file_info.models.len()],
+        }
+    }
+
+    pub fn build(mut self) -> Result<Gltf, GltfError> {
+        let file_info = self.file_info;
+        let mut scene = Vec::new();
+        let mut bound = vec![false; file_info.meshes.len()];
+
+        for (model_index, model) in file_info.models.iter().enumerate() {
+            let root = self.push_node(json!({ "name": model.name }), Some(model.initial_placement));
+            scene.push(root);
+
+            let mut skin = None;
+            if let Some(skeleton) = model.skeleton {
+                let bones = self.push_skeleton(&file_info.skeletons[skeleton], root);
+                skin = Some(self.push_skin(&file_info.skeletons[skeleton], &bones));
+                self.bone_nodes[model_index] = bones;
+            }
+
+            for mesh_index in model.mesh_bindings.iter().flatten() {
+                bound[*mesh_index] = true;
+                let Some((mesh, skinned)) = self.mesh(*mesh_index, model.skeleton)? else {
+                    continue;
+                };
+                let mut node = json!({
+                    "name": file_info.meshes[*mesh_index].name,
+                    "mesh": mesh,
+                });
+                if let (true, Some(skin)) = (skinned, skin) {
+                    node["skin"] = json!(skin);
+                }
+                let node = self.push_node(node, None);
+                self.add_child(root, node);
+            }
+        }
+
+        // Meshes no model uses are still exported, unskinned
+        for (mesh_index, mesh) in file_info.meshes.iter().enumerate() {
+            if bound[mesh_index] {
+                continue;
+            }
+            if let Some((gltf_mesh, _)) = self.mesh(mesh_index, None)? {
+                scene.push(self.push_node(json!({ "name": mesh.name, "mesh": gltf_mesh }), None));
+            }
+        }
+
+        for animation in &file_info.animations {
+            self.push_animation(animation)?;
+        }
+
+        let mut json = Map::new();
+        json.insert(
+            "asset".into(),
+            json!({ "version": "2.0", "generator": "granny2" }),
+        );
+        json.insert("scene".into(), json!(0));
+        json.insert("scenes".into(), json!([{ "nodes": scene }]));
+        self.insert_textures(&mut json);
+        Self::insert(&mut json, "nodes", self.nodes);
+        Self::insert(&mut json, "meshes", self.meshes);
+        Self::insert(&mut json, "skins", self.skins);
+        Self::insert(&mut json, "animations", self.animations);
+        Self::insert(&mut json, "accessors", self.buffer.accessors);
+        Self::insert(&mut json, "bufferViews", self.buffer.views);
+        if !self.buffer.data.is_empty() {
+            json.insert(
+                "buffers".into(),
+                json!([{ "byteLength": self.buffer.data.len() }]),
+            );
+        }
+
+        Ok(Gltf {
+            json: Value::Object(json),
+            buffer: self.buffer.data,
+        })
+    }
+
+    /// Adds materials, with the textures and images they use.
+    fn insert_textures(&self, json: &mut Map<String, Value>) {
+        let file_info = self.file_info;
+
+        let images = file_info
+            .textures
+            .iter()
+            .map(|texture| {
+                let uri = texture
+                    .from_file_name
+                    .replace('\\', "/")
+                    .replace(' ', "%20");
+                json!({ "uri": uri })
+            })
+            .collect::<Vec<_>>();
+        let textures = (0..images.len())
+            .map(|image| json!({ "source": image }))
+            .collect::<Vec<_>>();
+        let materials = file_info
+            .materials
+            .iter()
+            .map(|material| {
+                let mut gltf_material = json!({ "name": material.name });
+                if let Some(texture) = file_info.material_texture(material) {
+                    gltf_material["pbrMetallicRoughness"] =
+                        json!({ "baseColorTexture": { "index": texture } });
+                }
+                gltf_material
+            })
+            .collect::<Vec<_>>();
+
+        Self::insert(json, "images", images);
+        Self::insert(json, "textures", textures);
+        Self::insert(json, "materials", materials);
+    }
+
+    /// Adds a node for every bone, returning their indices.
+    fn push_skeleton(&mut self, skeleton: &Skeleton, root: usize) -> Vec<usize> {
+        let mut bones: Vec<usize> = Vec::with_capacity(skeleton.bones.len());
+        for bone in &skeleton.bones {
+            let node = self.push_node(json!({ "name": bone.name }), Some(bone.local_transform));
+            let parent = bone.parent_index.map_or(root, |parent| bones[parent]);
+            self.add_child(parent, node);
+            bones.push(node);
+        }
+        bones
+    }
+
+    fn push_skin(&mut self, skeleton: &Skeleton, bones: &[usize]) -> usize {
+        let inverse_bind_matrices = skeleton
+            .bones
+            .iter()
+            .map(|bone| bone.inverse_world_4x4.0)
+            .collect::<Vec<_>>();
+        let accessor = self.buffer.push_data(&inverse_bind_matrices, "MAT4");
+        self.skins.push(json!({
+            "name": skeleton.name,
+            "joints": bones,
+            "inverseBindMatrices": accessor,
+        }));
+        self.skins.len() - 1
+    }
+
+    /// glTF mesh of `mesh_index`, built the first time it's needed for a
+    /// skeleton. Meshes without vertices are left out.
+    fn mesh(&mut self, mesh_index: usize, skeleton: Option<usize>) -> Result<BuiltMesh, GltfError> {
+        if let Some(built) = self.built_meshes.get(&(mesh_index, skeleton)) {
+            return Ok(*built);
+        }
+
+        let mesh = &self.file_info.meshes[mesh_index];
+        let built = self.push_mesh(mesh, skeleton.map(|index| &self.file_info.skeletons[index]))?;
+        self.built_meshes.insert((mesh_index, skeleton), built);
+        Ok(built)
+    }
+
+    fn push_mesh(
+        &mut self,
+        mesh: &Mesh,
+        skeleton: Option<&Skeleton>,
+    ) -> Result<BuiltMesh, GltfError> {
+        let geometry = mesh.geometry()?;
+        let count = geometry.positions.len();
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let mut attributes = Map::new();
+        attributes.insert(
+            "POSITION".into(),
+            json!(self.buffer.push_positions(&geometry.positions)),
+        );
+        if let Some(normals) = geometry
+            .normals
+            .as_ref()
+            .filter(|normals| normals.len() == count)
+        {
+            attributes.insert(
+                "NORMAL".into(),
+                json!(self.buffer.push_attribute(normals, "VEC3")),
+            );
+            if let Some(tangents) = geometry
+                .tangents
+                .as_ref()
+                .filter(|tangents| tangents.len() == count)
+            {
+                // glTF stores the handedness of the binormal on the tangent
+                let tangents = tangents
+                    .iter()
+                    .enumerate()
+                    .map(|(vertex, [x, y, z])| {
+                        let handedness = geometry
+                            .binormals
+                            .as_ref()
+                            .and_then(|binormals| binormals.get(vertex))
+                            .map_or(1., |binormal| {
+                                let [nx, ny, nz] = normals[vertex];
+                                let cross = [ny * z - nz * y, nz * x - nx * z, nx * y - ny * x];
+                                let dot = cross[0] * binormal[0]
+                                    + cross[1] * binormal[1]
+                                    + cross[2] * binormal[2];
+                                if dot < 0. {
+                                    -1.
+                                } else {
+                                    1.
+                                }
+                            });
+                        [*x, *y, *z, handedness]
+                    })
+                    .collect::<Vec<_>>();
+                attributes.insert(
+                    "TANGENT".into(),
+                    json!(self.buffer.push_attribute(&tangents, "VEC4")),
+                );
+            }
+        }
+        for (channel, coordinates) in geometry.texture_coordinates.iter().enumerate() {
+            if coordinates.len() == count {
+                attributes.insert(
+                    format!("TEXCOORD_{}", channel),
+                    json!(self.buffer.push_attribute(coordinates, "VEC2")),
+                );
+            }
+        }
+
+        let mut skinned = false;
+        if let (Some(skeleton), Some(weights), Some(indices)) =
+            (skeleton, &geometry.bone_weights, &geometry.bone_indices)
+        {
+            let bindings = mesh.bone_binding_indices(skeleton);
+            let mut joints = Vec::with_capacity(count);
+            let mut normalized = Vec::with_capacity(count);
+            for (weights, indices) in weights.iter().zip(indices) {
+                let mut vertex_joints = [0; 4];
+                let mut vertex_weights = [0.; 4];
+                for i in 0..4 {
+                    let joint = usize::try_from(indices[i])
+                        .ok()
+                        .and_then(|binding| bindings.get(binding).copied().flatten())
+                        .and_then(|bone| u16::try_from(bone).ok());
+                    if let Some(joint) = joint {
+                        vertex_joints[i] = joint;
+                        vertex_weights[i] = weights[i];
+                    }
+                }
+                let total = vertex_weights.iter().sum::<f32>();
+                if total > 0. {
+                    vertex_weights
+                        .iter_mut()
+                        .for_each(|weight| *weight /= total);
+                }
+                joints.push(vertex_joints);
+                normalized.push(vertex_weights);
+            }
+            if joints.len() == count {
+                attributes.insert("JOINTS_0".into(), json!(self.buffer.push_joints(&joints)));
+                attributes.insert(
+                    "WEIGHTS_0".into(),
+                    json!(self.buffer.push_attribute(&normalized, "VEC4")),
+                );
+                skinned = true;
+            }
+        }
+
+        let attributes = Value::Object(attributes);
+        let mut primitives = Vec::new();
+        if geometry.groups.is_empty() {
+            let mut primitive = json!({ "attributes": attributes });
+            if !geometry.indices.is_empty() {
+                primitive["indices"] = json!(self.buffer.push_indices(&geometry.indices));
+            }
+            primitives.push(primitive);
+        }
+        for group in &geometry.groups {
+            let (Ok(first), Ok(count)) = (
+                usize::try_from(group.tri_first),
+                usize::try_from(group.tri_count),
+            ) else {
+                continue;
+            };
+            let Some(indices) = geometry.indices.get(first * 3..(first + count) * 3) else {
+                continue;
+            };
+            if indices.is_empty() {
+                continue;
+            }
+            let mut primitive = json!({
+                "attributes": attributes,
+                "indices": self.buffer.push_indices(indices),
+            });
+            let material = usize::try_from(group.material_index)
+                .ok()
+                .and_then(|binding| mesh.material_bindings.get(binding).copied().flatten());
+            if let Some(material) = material {
+                primitive["material"] = json!(material);
+            }
+            primitives.push(primitive);
+        }
+
+        self.meshes
+            .push(json!({ "name": mesh.name, "primitives": primitives }));
+        Ok(Some((self.meshes.len() - 1, skinned)))
+    }
+
+    /// Resamples every track bound to a bone into glTF channels.
+    ///
+    /// Track groups are bound to the model of the same name, or to the
+    /// only model with a skeleton, and tracks to bones by name.
+    fn push_animation(&mut self, animation: &Animation) -> Result<(), GltfError> {
+        let file_info = self.file_info;
+        let Some(times) = Self::sample_times(animation.duration, animation.time_step) else {
+            return Err(GltfError::InvalidDuration(animation.name.into()));
+        };
+
+        let mut samplers = Vec::new();
+        let mut channels = Vec::new();
+        let mut input = None;
+
+        let skinned_models = file_info
+            .models
+            .iter()
+            .enumerate()
+            .filter(|(_, model)| model.skeleton.is_some())
+            .collect::<Vec<_>>();
+        for track_group in animation
+            .track_groups
+            .iter()
+            .flatten()
+            .map(|index| &file_info.track_groups[*index])
+        {
+            let model = file_info
+                .models
+                .iter()
+                .enumerate()
+                .find(|(_, model)| model.name == track_group.name)
+                .or(match skinned_models.as_slice() {
+                    [model] => Some(*model),
+                    _ => None,
+                });
+            let Some((model_index, Some(skeleton))) =
+                model.map(|(index, model)| (index, model.skeleton))
+            else {
+                continue;
+            };
+            let skeleton = &file_info.skeletons[skeleton];
+
+            let poses = times
+                .iter()
+                .map(|time| animation.sample_track_group(track_group, *time))
+                .collect::<Vec<_>>();
+            let input = *input.get_or_insert_with(|| self.buffer.push_times(&times));
+
+            for (track_index, track) in track_group.transform_tracks.iter().enumerate() {
+                let Some(bone) = skeleton.bone_index(track.name) else {
+                    continue;
+                };
+                let node = self.bone_nodes[model_index][bone];
+                let transforms = poses
+                    .iter()
+                    .map(|pose| &pose[track_index])
+                    .collect::<Vec<_>>();
+
+                let translations = transforms
+                    .iter()
+                    .map(|transform| transform.translation)
+                    .collect::<Vec<_>>();
+                let rotations = transforms
+                    .iter()
+                    .map(|transform| transform.rotation)
+                    .collect::<Vec<_>>();
+                let scales = transforms
+                    .iter()
+                    .map(|transform| Self::scale(transform))
+                    .collect::<Vec<_>>();
+
+                let outputs = [
+                    ("translation", self.buffer.push_data(&translations, "VEC3")),
+                    ("rotation", self.buffer.push_data(&rotations, "VEC4")),
+                    ("scale", self.buffer.push_data(&scales, "VEC3")),
+                ];
+                for (path, output) in outputs {
+                    channels.push(json!({
+                        "sampler": samplers.len(),
+                        "target": { "node": node, "path": path },
+                    }));
+                    samplers.push(json!({
+                        "input": input,
+                        "output": output,
+                        "interpolation": "LINEAR",
+                    }));
+                }
+            }
+        }
+
+        if !channels.is_empty() {
+            self.animations.push(json!({
+                "name": animation.name,
+                "samplers": samplers,
+                "channels": channels,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Times an animation is sampled at, every `time_step` from the start
+    /// up to and including `duration`, or `None` if `duration` isn't finite.
+    fn sample_times(duration: f32, time_step: f32) -> Option<Vec<f32>> {
+        if !duration.is_finite() {
+            return None;
+        }
+        let duration = duration.max(0.);
+        let time_step = if time_step.is_finite() && time_step > 0. {
+            time_step
+        } else {
+            DEFAULT_TIME_STEP
+        };
+
+        // Clamped and never NaN, so the conversion is exact
+        let steps = (duration / time_step).ceil().min(f32::from(MAX_FRAMES - 1)) as u16;
+        let time_step = if f32::from(steps) * time_step < duration {
+            duration / f32::from(steps)
+        } else {
+            time_step
+        };
+
+        let times = (0..steps)
+            .map(|step| f32::from(step) * time_step)
+            .filter(|time| *time < duration)
+            .chain([duration])
+            .collect();
+        Some(times)
+    }
+
+    fn push_node(&mut self, mut node: Value, transform: Option<&Transform>) -> usize {
+        if let Some(transform) = transform {
+            if transform.flags & Transform::HAS_POSITION != 0 {
+                node["translation"] = json!(transform.translation);
+            }
+            if transform.flags & Transform::HAS_ORIENTATION != 0 {
+                node["rotation"] = json!(transform.rotation);
+            }
+            if transform.flags & Transform::HAS_SCALE_SHEAR != 0 {
+                node["scale"] = json!(Self::scale(transform));
+            }
+        }
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn add_child(&mut self, parent: usize, child: usize) {
+        match self.nodes[parent]
+            .get_mut("children")
+            .and_then(Value::as_array_mut)
+        {
+            Some(children) => children.push(json!(child)),
+            None => self.nodes[parent]["children"] = json!([child]),
+        }
+    }
+
+    /// Scale on the diagonal of the scale/shear, glTF has no shear.
+    fn scale(transform: &Transform) -> [f32; 3] {
+        let scale_shear = transform.scale_shear;
+        [scale_shear[0], scale_shear[4], scale_shear[8]]
+    }
+
+    /// Adds `values` under `name`, glTF doesn't allow empty lists.
+    fn insert(json: &mut Map<String, Value>, name: &str, values: Vec<Value>) {
+        if !values.is_empty() {
+            json.insert(name.into(), Value::Array(values));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Builder, DEFAULT_TIME_STEP, MAX_FRAMES};
+
+    #[test]
+    fn sample_times_end_on_duration() {
+        assert_eq!(
+            Builder::sample_times(1., 0.25).unwrap(),
+            [0., 0.25, 0.5, 0.75, 1.]
+        );
+        assert_eq!(
+            Builder::sample_times(0.6, 0.25).unwrap(),
+            [0., 0.25, 0.5, 0.6]
+        );
+        assert_eq!(Builder::sample_times(0., 0.25).unwrap(), [0.]);
+        assert_eq!(Builder::sample_times(-1., 0.25).unwrap(), [0.]);
+    }
+
+    #[test]
+    fn sample_times_default_step() {
+        for time_step in [0., -1., f32::NAN, f32::INFINITY] {
+            let times = Builder::sample_times(1., time_step).unwrap();
+            assert_eq!(times.len(), 31);
+            assert_eq!(times[1], DEFAULT_TIME_STEP);
+        }
+    }
+
+    #[test]
+    fn sample_times_reject_non_finite_duration() {
+        for duration in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(Builder::sample_times(duration, 0.25).is_none());
+        }
+    }
+
+    #[test]
+    fn sample_times_cap_frames() {
+        // Past 2^24 frames an f32 frame counter stops increasing
+        for duration in [1e9, f32::MAX] {
+            let times = Builder::sample_times(duration, 1. / 30.).unwrap();
+            assert_eq!(times.len(), usize::from(MAX_FRAMES));
+            assert_eq!(times.last(), Some(&duration));
+            assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+}
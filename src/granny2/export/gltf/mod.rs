@@ -0,0 +1,151 @@
+mod buffer;
+mod builder;
+
+use std::{error::Error, fmt::Display, io::Write};
+
+use serde_json::{json, Value};
+
+use crate::granny2::file_info::{FileInfo, FileInfoError};
+
+use self::builder::Builder;
+
+const GLB_MAGIC: u32 = 0x4654_6c67;
+const GLB_VERSION: u32 = 2;
+const JSON_CHUNK: u32 = 0x4e4f_534a;
+const BIN_CHUNK: u32 = 0x004e_4942;
+
+/// glTF 2.0 asset holding the models, meshes and animations of a file.
+///
+/// Coordinates are kept as the file stores them, no conversion is done
+/// from the art tool's axes or units.
+#[derive(Debug)]
+pub struct Gltf {
+    pub json: Value,
+    /// Binary buffer referenced by the accessors on `json`.
+    pub buffer: Vec<u8>,
+}
+
+impl Gltf {
+    pub fn new(file_info: &FileInfo) -> Result<Self, GltfError> {
+        Builder::new(file_info).build()
+    }
+
+    /// Writes a `.gltf` file, with the buffer embedded as a data URI.
+    pub fn write_gltf<T: Write>(&self, writer: &mut T) -> Result<(), GltfError> {
+        let mut json = self.json.clone();
+        if let Some(buffer) = json
+            .get_mut("buffers")
+            .and_then(|buffers| buffers.get_mut(0))
+        {
+            buffer["uri"] = json!(format!(
+                "data:application/octet-stream;base64,{}",
+                Self::base64(&self.buffer)
+            ));
+        }
+        serde_json::to_writer_pretty(&mut *writer, &json)?;
+        Ok(())
+    }
+
+    /// Writes a binary `.glb` file.
+    pub fn write_glb<T: Write>(&self, writer: &mut T) -> Result<(), GltfError> {
+        let mut json = serde_json::to_vec(&self.json)?;
+        json.resize(json.len().next_multiple_of(4), b' ');
+        let mut buffer = self.buffer.clone();
+        buffer.resize(buffer.len().next_multiple_of(4), 0);
+
+        let json_length = u32::try_from(json.len()).map_err(|_| GltfError::TooLarge)?;
+        let buffer_length = u32::try_from(buffer.len()).map_err(|_| GltfError::TooLarge)?;
+        let mut length = 12 + 8 + json_length;
+        if !buffer.is_empty() {
+            length = length
+                .checked_add(8 + buffer_length)
+                .ok_or(GltfError::TooLarge)?;
+        }
+
+        writer.write_all(&GLB_MAGIC.to_le_bytes())?;
+        writer.write_all(&GLB_VERSION.to_le_bytes())?;
+        writer.write_all(&length.to_le_bytes())?;
+
+        writer.write_all(&json_length.to_le_bytes())?;
+        writer.write_all(&JSON_CHUNK.to_le_bytes())?;
+        writer.write_all(&json)?;
+
+        if !buffer.is_empty() {
+            writer.write_all(&buffer_length.to_le_bytes())?;
+            writer.write_all(&BIN_CHUNK.to_le_bytes())?;
+            writer.write_all(&buffer)?;
+        }
+        Ok(())
+    }
+
+    fn base64(data: &[u8]) -> String {
+        const ALPHABET: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+        for chunk in data.chunks(3) {
+            let bytes = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+            let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    let index = (bits >> (18 - 6 * i)) & 0x3f;
+                    let Ok(index) = usize::try_from(index) else {
+                        unreachable!("Base64 index must be smaller than usize.");
+                    };
+                    encoded.push(char::from(ALPHABET[index]));
+                } else {
+                    encoded.push('=');
+                }
+            }
+        }
+        encoded
+    }
+}
+
+#[derive(Debug)]
+pub enum GltfError {
+    FileInfo,
+    TooLarge,
+    InvalidDuration(Box<str>),
+    Io,
+}
+
+impl Display for GltfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileInfo => write!(f, "Failed to read file info."),
+            Self::TooLarge => write!(f, "glTF data is too large for a binary file."),
+            Self::InvalidDuration(name) => {
+                write!(f, "Animation {} has a duration that isn't finite.", name)
+            }
+            Self::Io => write!(f, "Failed to write glTF."),
+        }
+    }
+}
+
+impl Error for GltfError {}
+
+impl From<FileInfoError> for GltfError {
+    fn from(value: FileInfoError) -> Self {
+        log::error!("{}", value);
+        Self::FileInfo
+    }
+}
+
+impl From<serde_json::Error> for GltfError {
+    fn from(value: serde_json::Error) -> Self {
+        log::error!("{}", value);
+        Self::Io
+    }
+}
+
+impl From<std::io::Error> for GltfError {
+    fn from(value: std::io::Error) -> Self {
+        log::error!("{}", value);
+        Self::Io
+    }
+}
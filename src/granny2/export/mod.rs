@@ -0,0 +1,5 @@
+//! Conversion of parsed files to formats other tools can open.
+
+#[cfg(feature = "gltf")]
+pub mod gltf;
+pub mod obj;
@@ -0,0 +1,195 @@
+use std::{collections::HashSet, error::Error, fmt::Display, io::Write};
+
+use crate::granny2::file_info::{FileInfo, FileInfoError, Material};
+
+/// Writes the geometry of every mesh as Wavefront OBJ, with a `usemtl`
+/// for each triangle group, and their materials as MTL.
+///
+/// Vertices are written on the rest pose, as stored on the file.
+/// `mtl_name` is the path of the MTL file referenced by the OBJ file.
+pub fn write_obj<O: Write, M: Write>(
+    file_info: &FileInfo,
+    obj: &mut O,
+    mtl: &mut M,
+    mtl_name: &str,
+) -> Result<(), ObjError> {
+    let material_names = material_names(&file_info.materials);
+    write_mtl(file_info, mtl, &material_names)?;
+
+    writeln!(obj, "mtllib {}", mtl_name)?;
+
+    // OBJ indices start at 1 and keep counting across objects
+    let mut positions = 1;
+    let mut coordinates = 1;
+    let mut normals = 1;
+    for mesh in &file_info.meshes {
+        let geometry = mesh.geometry()?;
+        let count = geometry.positions.len();
+
+        writeln!(obj, "o {}", sanitize(mesh.name))?;
+        for [x, y, z] in &geometry.positions {
+            writeln!(obj, "v {} {} {}", x, y, z)?;
+        }
+        let mesh_coordinates = geometry
+            .texture_coordinates
+            .first()
+            .filter(|channel| channel.len() == count);
+        for [u, v] in mesh_coordinates.into_iter().flatten() {
+            writeln!(obj, "vt {} {}", u, v)?;
+        }
+        let mesh_normals = geometry
+            .normals
+            .as_ref()
+            .filter(|normals| normals.len() == count);
+        for [x, y, z] in mesh_normals.into_iter().flatten() {
+            writeln!(obj, "vn {} {} {}", x, y, z)?;
+        }
+
+        let face_vertex = |index: u32| {
+            let Ok(index) = usize::try_from(index) else {
+                unreachable!("Vertex index must be smaller than usize.");
+            };
+            match (mesh_coordinates.is_some(), mesh_normals.is_some()) {
+                (true, true) => format!(
+                    "{}/{}/{}",
+                    positions + index,
+                    coordinates + index,
+                    normals + index
+                ),
+                (true, false) => format!("{}/{}", positions + index, coordinates + index),
+                (false, true) => format!("{}//{}", positions + index, normals + index),
+                (false, false) => format!("{}", positions + index),
+            }
+        };
+        let write_faces = |obj: &mut O, indices: &[u32]| -> Result<(), ObjError> {
+            for triangle in indices.chunks_exact(3) {
+                writeln!(
+                    obj,
+                    "f {} {} {}",
+                    face_vertex(triangle[0]),
+                    face_vertex(triangle[1]),
+                    face_vertex(triangle[2])
+                )?;
+            }
+            Ok(())
+        };
+
+        if geometry.groups.is_empty() {
+            write_faces(obj, &geometry.indices)?;
+        }
+        for group in &geometry.groups {
+            let (Ok(first), Ok(count)) = (
+                usize::try_from(group.tri_first),
+                usize::try_from(group.tri_count),
+            ) else {
+                continue;
+            };
+            let Some(indices) = geometry.indices.get(first * 3..(first + count) * 3) else {
+                continue;
+            };
+            let material = usize::try_from(group.material_index)
+                .ok()
+                .and_then(|binding| mesh.material_bindings.get(binding).copied().flatten());
+            match material {
+                Some(material) => writeln!(obj, "usemtl {}", material_names[material])?,
+                None => writeln!(obj, "usemtl default")?,
+            }
+            write_faces(obj, indices)?;
+        }
+
+        positions += count;
+        if mesh_coordinates.is_some() {
+            coordinates += count;
+        }
+        if mesh_normals.is_some() {
+            normals += count;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_mtl<M: Write>(
+    file_info: &FileInfo,
+    mtl: &mut M,
+    material_names: &[String],
+) -> Result<(), ObjError> {
+    // Used by groups without a material
+    writeln!(mtl, "newmtl default")?;
+    writeln!(mtl, "Kd 1 1 1")?;
+    writeln!(mtl)?;
+
+    for (material, name) in file_info.materials.iter().zip(material_names) {
+        writeln!(mtl, "newmtl {}", name)?;
+        writeln!(mtl, "Kd 1 1 1")?;
+
+        if let Some(texture) = file_info.material_texture(material) {
+            writeln!(
+                mtl,
+                "map_Kd {}",
+                file_info.textures[texture]
+                    .from_file_name
+                    .replace('\\', "/")
+            )?;
+        }
+        writeln!(mtl)?;
+    }
+    Ok(())
+}
+
+/// Names every material can be referenced by, made unique as OBJ
+/// identifies materials by name.
+fn material_names(materials: &[Material]) -> Vec<String> {
+    let mut used = HashSet::from(["default".to_owned()]);
+    materials
+        .iter()
+        .enumerate()
+        .map(|(index, material)| {
+            let mut name = sanitize(material.name);
+            if !used.insert(name.clone()) {
+                name = format!("{}_{}", name, index);
+                used.insert(name.clone());
+            }
+            name
+        })
+        .collect()
+}
+
+/// Replaces whitespace, which would end a name on OBJ and MTL files.
+fn sanitize(name: &str) -> String {
+    if name.is_empty() {
+        return "unnamed".into();
+    }
+    name.replace(char::is_whitespace, "_")
+}
+
+#[derive(Debug)]
+pub enum ObjError {
+    FileInfo,
+    Io,
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileInfo => write!(f, "Failed to read file info."),
+            Self::Io => write!(f, "Failed to write OBJ."),
+        }
+    }
+}
+
+impl Error for ObjError {}
+
+impl From<FileInfoError> for ObjError {
+    fn from(value: FileInfoError) -> Self {
+        log::error!("{}", value);
+        Self::FileInfo
+    }
+}
+
+impl From<std::io::Error> for ObjError {
+    fn from(value: std::io::Error) -> Self {
+        log::error!("{}", value);
+        Self::Io
+    }
+}
@@ -0,0 +1,186 @@
+use crate::granny2::{element::Element, transform::Transform};
+
+use super::{members, FileInfoError, TrackGroup};
+
+#[derive(Debug)]
+pub struct Animation<'a> {
+    pub name: &'a str,
+    pub duration: f32,
+    pub time_step: f32,
+    pub oversampling: f32,
+    /// Indices on [`super::FileInfo::track_groups`].
+    pub track_groups: Vec<Option<usize>>,
+    /// Times the animation plays, 0 loops forever.
+    pub default_loop_count: i32,
+    pub members: &'a [Element],
+}
+
+impl<'a> Animation<'a> {
+    pub fn parse(members: &'a [Element], track_groups: &[u64]) -> Result<Self, FileInfoError> {
+        let track_groups = members::references(members, "TrackGroups")?
+            .iter()
+            .map(|(address, _)| members::index_of(track_groups, Some(*address)))
+            .collect();
+
+        Ok(Self {
+            name: members::string(members, "Name")?,
+            duration: members::real32(members, "Duration")?,
+            time_step: members::real32(members, "TimeStep")?,
+            oversampling: members::real32(members, "Oversampling")?,
+            track_groups,
+            default_loop_count: members::int32(members, "DefaultLoopCount")?,
+            members,
+        })
+    }
+
+    /// Local transforms of every track of `track_group` at `time`, in
+    /// seconds since the animation started.
+    ///
+    /// Time wraps around `duration` while the animation loops, and holds
+    /// the last pose once every loop has played.
+    pub fn sample_track_group(&self, track_group: &TrackGroup, time: f32) -> Vec<Transform> {
+        let looping = self.default_loop_count != 1;
+        let time = self.local_time(time);
+        track_group
+            .transform_tracks
+            .iter()
+            .map(|track| track.sample(time, self.time_step, looping))
+            .collect()
+    }
+
+    /// Time inside a single loop of the animation.
+    pub fn local_time(&self, time: f32) -> f32 {
+        if self.duration <= 0. {
+            return 0.;
+        }
+        let time = time.max(0.);
+        match u16::try_from(self.default_loop_count) {
+            Ok(0) => time.rem_euclid(self.duration),
+            Ok(loops) if time < self.duration * f32::from(loops) => time.rem_euclid(self.duration),
+            _ => self.duration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Animation;
+    use crate::granny2::{
+        file_info::{Curve, CurveFormat, TrackGroup, TransformTrack},
+        transform::Transform,
+    };
+
+    fn animation(default_loop_count: i32) -> Animation<'static> {
+        Animation {
+            name: "Walk",
+            duration: 2.,
+            time_step: 0.5,
+            oversampling: 1.,
+            track_groups: vec![],
+            default_loop_count,
+            members: &[],
+        }
+    }
+
+    fn identity(dimension: usize) -> Curve {
+        Curve {
+            format: CurveFormat::DaIdentity,
+            degree: 0,
+            dimension,
+            knots: vec![0.],
+            controls: vec![],
+        }
+    }
+
+    fn track(position_curve: Curve) -> TransformTrack<'static> {
+        TransformTrack {
+            name: "root",
+            flags: 0,
+            orientation_curve: identity(4),
+            position_curve,
+            scale_shear_curve: identity(9),
+            members: &[],
+        }
+    }
+
+    fn track_group() -> TrackGroup<'static> {
+        // Moves 2 along x and back, once in seconds and once in keyframes
+        // of half a second
+        let seconds = Curve {
+            format: CurveFormat::DaK32fC32f,
+            degree: 1,
+            dimension: 3,
+            knots: vec![0., 1., 2.],
+            controls: vec![0., 0., 0., 2., 0., 0., 0., 0., 0.],
+        };
+        let keyframes = Curve {
+            format: CurveFormat::DaKeyframes32f,
+            knots: vec![0., 1., 2., 3., 4.],
+            controls: [0_u8, 1, 2, 1, 0]
+                .into_iter()
+                .flat_map(|x| [f32::from(x), 0., 0.])
+                .collect(),
+            ..seconds.clone()
+        };
+        TrackGroup {
+            name: "Walk",
+            transform_tracks: vec![track(seconds), track(keyframes)],
+            initial_placement: &Transform::IDENTITY,
+            members: &[],
+        }
+    }
+
+    #[test]
+    fn local_time_loops_forever() {
+        let animation = animation(0);
+        assert_eq!(animation.local_time(0.5), 0.5);
+        assert_eq!(animation.local_time(5.), 1.);
+        assert_eq!(animation.local_time(-1.), 0.);
+    }
+
+    #[test]
+    fn local_time_holds_after_the_last_loop() {
+        let twice = animation(2);
+        assert_eq!(twice.local_time(3.), 1.);
+        assert_eq!(twice.local_time(4.), 2.);
+        assert_eq!(twice.local_time(5.), 2.);
+
+        let once = animation(1);
+        assert_eq!(once.local_time(1.5), 1.5);
+        assert_eq!(once.local_time(2.5), 2.);
+    }
+
+    #[test]
+    fn local_time_of_empty_animation() {
+        let animation = Animation {
+            duration: 0.,
+            ..animation(0)
+        };
+        assert_eq!(animation.local_time(1.), 0.);
+    }
+
+    #[test]
+    fn samples_every_track() {
+        let track_group = track_group();
+        let transforms = animation(1).sample_track_group(&track_group, 1.5);
+        assert_eq!(transforms.len(), 2);
+        for transform in transforms {
+            assert_eq!(transform.flags, Transform::HAS_POSITION);
+            assert_eq!(transform.translation, [1., 0., 0.]);
+            assert_eq!(transform.rotation, Transform::IDENTITY.rotation);
+        }
+    }
+
+    #[test]
+    fn samples_in_local_time() {
+        let track_group = track_group();
+        // Second loop of a looping animation
+        for transform in animation(0).sample_track_group(&track_group, 2.5) {
+            assert_eq!(transform.translation, [1., 0., 0.]);
+        }
+        // Last pose once the animation ended
+        for transform in animation(1).sample_track_group(&track_group, 2.5) {
+            assert_eq!(transform.translation, [0., 0., 0.]);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::granny2::element::Element;
+
+use super::{members, FileInfoError};
+
+/// Information about the tool the file was created with.
+#[derive(Debug)]
+pub struct ArtToolInfo<'a> {
+    pub from_art_tool_name: &'a str,
+    pub art_tool_major_revision: i32,
+    pub art_tool_minor_revision: i32,
+    pub units_per_meter: f32,
+    pub origin: [f32; 3],
+    pub right_vector: [f32; 3],
+    pub up_vector: [f32; 3],
+    pub back_vector: [f32; 3],
+}
+
+impl<'a> ArtToolInfo<'a> {
+    pub fn parse(members: &'a [Element]) -> Result<Self, FileInfoError> {
+        Ok(Self {
+            from_art_tool_name: members::string(members, "FromArtToolName")?,
+            art_tool_major_revision: members::int32(members, "ArtToolMajorRevision")?,
+            art_tool_minor_revision: members::int32(members, "ArtToolMinorRevision")?,
+            units_per_meter: members::real32(members, "UnitsPerMeter")?,
+            origin: members::real32_array(members, "Origin")?,
+            right_vector: members::real32_array(members, "RightVector")?,
+            up_vector: members::real32_array(members, "UpVector")?,
+            back_vector: members::real32_array(members, "BackVector")?,
+        })
+    }
+}
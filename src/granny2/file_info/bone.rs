@@ -0,0 +1,37 @@
+use crate::granny2::{
+    element::Element,
+    transform::{Matrix, Transform},
+};
+
+use super::{members, FileInfoError};
+
+#[derive(Debug)]
+pub struct Bone<'a> {
+    pub name: &'a str,
+    /// Index of the parent on [`super::Skeleton::bones`], `None` for roots.
+    pub parent_index: Option<usize>,
+    pub local_transform: &'a Transform,
+    /// Inverse of the world transform of the bone on the rest pose.
+    pub inverse_world_4x4: Matrix,
+    pub lod_error: f32,
+}
+
+impl<'a> Bone<'a> {
+    /// Parses the bone at `index`, whose parent must come before it.
+    pub fn parse(members: &'a [Element], index: usize) -> Result<Self, FileInfoError> {
+        let parent = members::int32(members, "ParentIndex")?;
+        let parent_index = match usize::try_from(parent) {
+            Ok(parent_index) if parent_index < index => Some(parent_index),
+            Err(_) if parent == -1 => None,
+            _ => return Err(FileInfoError::InvalidParentIndex(index, parent)),
+        };
+
+        Ok(Self {
+            name: members::string(members, "Name")?,
+            parent_index,
+            local_transform: members::transform(members, "LocalTransform")?,
+            inverse_world_4x4: Matrix(members::real32_array(members, "InverseWorld4x4")?),
+            lod_error: members::real32(members, "LODError")?,
+        })
+    }
+}
@@ -0,0 +1,625 @@
+use std::f32::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+use crate::granny2::element::Element;
+
+use super::{members, CurveFormat, FileInfoError};
+
+/// Ranges of the three smallest components of a packed quaternion,
+/// selected 4 bits at a time by `ScaleOffsetTableEntries`.
+const QUATERNION_SCALES: [f32; 16] = [
+    SQRT_2,
+    FRAC_1_SQRT_2,
+    FRAC_1_SQRT_2 / 2.,
+    FRAC_1_SQRT_2 / 2.,
+    FRAC_1_SQRT_2 / 2.,
+    FRAC_1_SQRT_2 / 4.,
+    FRAC_1_SQRT_2 / 4.,
+    FRAC_1_SQRT_2 / 4.,
+    -SQRT_2,
+    -FRAC_1_SQRT_2,
+    -FRAC_1_SQRT_2 / 2.,
+    -FRAC_1_SQRT_2 / 2.,
+    -FRAC_1_SQRT_2 / 2.,
+    -FRAC_1_SQRT_2 / 4.,
+    -FRAC_1_SQRT_2 / 4.,
+    -FRAC_1_SQRT_2 / 4.,
+];
+const QUATERNION_OFFSETS: [f32; 16] = [
+    -FRAC_1_SQRT_2,
+    -FRAC_1_SQRT_2 / 2.,
+    -FRAC_1_SQRT_2 * 0.75,
+    -FRAC_1_SQRT_2 / 4.,
+    FRAC_1_SQRT_2 / 4.,
+    -FRAC_1_SQRT_2 / 4.,
+    -FRAC_1_SQRT_2 / 8.,
+    0.,
+    FRAC_1_SQRT_2,
+    FRAC_1_SQRT_2 / 2.,
+    FRAC_1_SQRT_2 * 0.75,
+    FRAC_1_SQRT_2 / 4.,
+    -FRAC_1_SQRT_2 / 4.,
+    FRAC_1_SQRT_2 / 4.,
+    FRAC_1_SQRT_2 / 8.,
+    -0.,
+];
+
+/// Animation curve with its knots and controls decoded to floats.
+///
+/// Quantized formats are expanded, so a curve always holds `dimension`
+/// floats for every knot. Scale/shear curves stored with fewer channels
+/// are expanded to a full 3x3 matrix, and positions stored with a single
+/// channel to 3 components.
+#[derive(Debug, Clone)]
+pub struct Curve {
+    pub format: CurveFormat,
+    pub degree: u8,
+    /// Number of floats on each control.
+    pub dimension: usize,
+    pub knots: Vec<f32>,
+    pub controls: Vec<f32>,
+}
+
+impl Curve {
+    /// Decodes the members of a `Curve2`.
+    pub fn parse(curve: &[Element]) -> Result<Self, FileInfoError> {
+        let data = members::variant(curve, "CurveData")?;
+        let (format, degree) = Self::header(data)?;
+
+        let mut curve = Self {
+            format,
+            degree,
+            dimension: 0,
+            knots: Vec::new(),
+            controls: Vec::new(),
+        };
+
+        match format {
+            CurveFormat::DaKeyframes32f => {
+                let Ok(dimension) = usize::try_from(members::int16(data, "Dimension")?) else {
+                    return Err(FileInfoError::InvalidCurveData(format));
+                };
+                curve.dimension = dimension;
+                curve.controls = members::numbers(data, "Controls")?;
+                let count = curve.controls.len().checked_div(dimension).unwrap_or(0);
+                curve.knots = std::iter::successors(Some(0.), |knot| Some(knot + 1.))
+                    .take(count)
+                    .collect();
+            }
+            CurveFormat::DaK32fC32f => {
+                curve.knots = members::numbers(data, "Knots")?;
+                curve.controls = members::numbers(data, "Controls")?;
+                curve.dimension = curve
+                    .controls
+                    .len()
+                    .checked_div(curve.knots.len())
+                    .unwrap_or(0);
+            }
+            CurveFormat::DaIdentity => {
+                let Ok(dimension) = usize::try_from(members::int16(data, "Dimension")?) else {
+                    return Err(FileInfoError::InvalidCurveData(format));
+                };
+                curve.set_constant(Self::identity(dimension));
+            }
+            CurveFormat::DaConstant32f => {
+                curve.set_constant(members::numbers(data, "Controls")?);
+            }
+            CurveFormat::D3Constant32f => {
+                curve.set_constant(members::real32_array::<3>(data, "Controls")?.to_vec());
+            }
+            CurveFormat::D4Constant32f => {
+                curve.set_constant(members::real32_array::<4>(data, "Controls")?.to_vec());
+            }
+            CurveFormat::DaK16uC16u | CurveFormat::DaK8uC8u => {
+                let scale_offsets = members::numbers(data, "ControlScaleOffsets")?;
+                let (scales, offsets) = scale_offsets.split_at(scale_offsets.len() / 2);
+                curve.decode_quantized(data, scales.len(), |channels| {
+                    channels
+                        .iter()
+                        .zip(scales)
+                        .zip(offsets)
+                        .map(|((value, scale), offset)| value * scale + offset)
+                        .collect()
+                })?;
+            }
+            CurveFormat::D4nK16uC15u => curve.decode_quaternions(data, 15)?,
+            CurveFormat::D4nK8uC7u => curve.decode_quaternions(data, 7)?,
+            CurveFormat::D3K16uC16u | CurveFormat::D3K8uC8u => {
+                let scales = members::real32_array::<3>(data, "ControlScales")?;
+                let offsets = members::real32_array::<3>(data, "ControlOffsets")?;
+                curve.decode_quantized(data, 3, |channels| {
+                    (0..3)
+                        .map(|i| channels[i] * scales[i] + offsets[i])
+                        .collect()
+                })?;
+            }
+            CurveFormat::D9I1K16uC16u | CurveFormat::D9I1K8uC8u => {
+                let scale = members::real32(data, "ControlScale")?;
+                let offset = members::real32(data, "ControlOffset")?;
+                curve.decode_quantized(data, 1, |channels| {
+                    let value = channels[0] * scale + offset;
+                    Self::diagonal([value; 3])
+                })?;
+            }
+            CurveFormat::D9I3K16uC16u | CurveFormat::D9I3K8uC8u => {
+                let scales = members::real32_array::<3>(data, "ControlScales")?;
+                let offsets = members::real32_array::<3>(data, "ControlOffsets")?;
+                curve.decode_quantized(data, 3, |channels| {
+                    Self::diagonal([0, 1, 2].map(|i| channels[i] * scales[i] + offsets[i]))
+                })?;
+            }
+            CurveFormat::D3I1K32fC32f | CurveFormat::D3I1K16uC16u | CurveFormat::D3I1K8uC8u => {
+                let scales = members::real32_array::<3>(data, "ControlScales")?;
+                let offsets = members::real32_array::<3>(data, "ControlOffsets")?;
+                curve.decode_quantized(data, 1, |channels| {
+                    (0..3)
+                        .map(|i| channels[0] * scales[i] + offsets[i])
+                        .collect()
+                })?;
+            }
+        }
+
+        Ok(curve)
+    }
+
+    /// Floats of the control at `knot`, `None` past the last control.
+    pub fn control(&self, knot: usize) -> Option<&[f32]> {
+        let start = knot.checked_mul(self.dimension)?;
+        self.controls.get(start..start.checked_add(self.dimension)?)
+    }
+
+    /// Evaluates the curve at `time` as a B-spline of its degree.
+    ///
+    /// Past its ends a looping curve wraps around, while any other curve
+    /// holds its first and last controls.
+    pub fn evaluate(&self, time: f32, looping: bool) -> Vec<f32> {
+        self.evaluate_spline(time, looping, false)
+    }
+
+    /// Evaluates an orientation curve as a normalized quaternion.
+    ///
+    /// Controls are flipped onto the hemisphere of their neighbors before
+    /// blending, so the rotation always takes the short way around.
+    pub fn evaluate_quaternion(&self, time: f32, looping: bool) -> [f32; 4] {
+        let [x, y, z, w] = self.evaluate_spline(time, looping, true)[..] else {
+            return [0., 0., 0., 1.];
+        };
+        let length = (x * x + y * y + z * z + w * w).sqrt();
+        if length > 0. {
+            [x / length, y / length, z / length, w / length]
+        } else {
+            [0., 0., 0., 1.]
+        }
+    }
+
+    fn evaluate_spline(&self, time: f32, looping: bool, quaternion: bool) -> Vec<f32> {
+        let count = self.knots.len();
+        if count == 0 || self.controls.len() < count * self.dimension {
+            return Self::identity(self.dimension);
+        }
+        let first = self.knots[0];
+        let last = self.knots[count - 1];
+        if count == 1 || last <= first {
+            return self.control_or_identity(0);
+        }
+
+        let time = if looping {
+            first + (time - first).rem_euclid(last - first)
+        } else {
+            time.clamp(first, last)
+        };
+        // Step curves hold each control from its knot on, the last one included
+        let passed = self.knots.partition_point(|knot| *knot <= time);
+        let degree = usize::from(self.degree);
+        if degree == 0 {
+            return self.control_or_identity(passed.clamp(1, count) - 1);
+        }
+        let span = passed.clamp(1, count - 1);
+
+        // de Boor's algorithm over the controls `span - degree..=span`,
+        // indices are shifted by `degree` to stay positive
+        let Some(mut points) = (0..=degree)
+            .map(|i| Some(self.extended_control(span + i, degree, looping)?.to_vec()))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Self::identity(self.dimension);
+        };
+        if quaternion {
+            for i in 1..points.len() {
+                let dot = points[i - 1]
+                    .iter()
+                    .zip(&points[i])
+                    .map(|(a, b)| a * b)
+                    .sum::<f32>();
+                if dot < 0. {
+                    points[i].iter_mut().for_each(|value| *value = -*value);
+                }
+            }
+        }
+
+        for level in 1..=degree {
+            for i in (level..=degree).rev() {
+                let start = self.extended_knot(i + span - 1, degree, looping);
+                let end = self.extended_knot(i + span + degree - level, degree, looping);
+                let alpha = if end > start {
+                    (time - start) / (end - start)
+                } else {
+                    0.
+                };
+                let (before, after) = points.split_at_mut(i);
+                for (value, previous) in after[0].iter_mut().zip(&before[i - 1]) {
+                    *value = previous * (1. - alpha) + *value * alpha;
+                }
+            }
+        }
+
+        points.swap_remove(degree)
+    }
+
+    /// Copy of the control at `knot`, or the identity when it is missing.
+    fn control_or_identity(&self, knot: usize) -> Vec<f32> {
+        self.control(knot)
+            .map_or_else(|| Self::identity(self.dimension), <[f32]>::to_vec)
+    }
+
+    /// Control `index - offset`, wrapping around on looping curves and
+    /// clamping to the ends on the others.
+    fn extended_control(&self, index: usize, offset: usize, looping: bool) -> Option<&[f32]> {
+        let last = self.knots.len() - 1;
+        if looping {
+            let (_, knot) = self.wrap(index, offset);
+            self.control(knot)
+        } else {
+            self.control(index.saturating_sub(offset).min(last))
+        }
+    }
+
+    /// Knot `index - offset`, repeating the knots on looping curves and
+    /// mirroring them around the ends on the others.
+    fn extended_knot(&self, index: usize, offset: usize, looping: bool) -> f32 {
+        let last = self.knots.len() - 1;
+        let first_knot = self.knots[0];
+        let last_knot = self.knots[last];
+        if looping {
+            let (periods, knot) = self.wrap(index, offset);
+            self.knots[knot] + periods * (last_knot - first_knot)
+        } else if index < offset {
+            2. * first_knot - self.knots[(offset - index).min(last)]
+        } else if index - offset > last {
+            2. * last_knot - self.knots[last - (index - offset - last).min(last)]
+        } else {
+            self.knots[index - offset]
+        }
+    }
+
+    /// Splits `index - offset` into a number of periods of a looping curve
+    /// and a knot inside the period.
+    ///
+    /// The last knot of a looping curve is the first knot of the next
+    /// period, so a period spans all but one knot.
+    fn wrap(&self, index: usize, offset: usize) -> (f32, usize) {
+        let period = self.knots.len() - 1;
+        let borrowed = offset.div_ceil(period);
+        let shifted = index + borrowed * period - offset;
+
+        let Ok(periods) = u16::try_from(shifted / period) else {
+            unreachable!("Periods must be smaller than u16.");
+        };
+        let Ok(borrowed) = u16::try_from(borrowed) else {
+            unreachable!("Periods must be smaller than u16.");
+        };
+        (f32::from(periods) - f32::from(borrowed), shifted % period)
+    }
+
+    fn header(data: &[Element]) -> Result<(CurveFormat, u8), FileInfoError> {
+        let header = data
+            .iter()
+            .find(|member| member.name.starts_with("CurveDataHeader"))
+            .ok_or_else(|| FileInfoError::MissingMember("CurveDataHeader".into()))?;
+        let fields = members::inline(data, &header.name)?;
+        let degree = members::uint8(fields, "Degree")?;
+
+        let format = match CurveFormat::from_header_name(&header.name) {
+            Some(format) => format,
+            None => CurveFormat::try_from(members::uint8(fields, "Format")?)
+                .map_err(|_| FileInfoError::UnknownCurveFormat(header.name.clone()))?,
+        };
+        Ok((format, degree))
+    }
+
+    fn set_constant(&mut self, control: Vec<f32>) {
+        self.dimension = control.len();
+        self.knots = vec![0.];
+        self.controls = control;
+    }
+
+    /// Decodes `KnotsControls`, which holds every knot followed by
+    /// `channels` values per knot, mapping each control with `decode`.
+    fn decode_quantized(
+        &mut self,
+        data: &[Element],
+        channels: usize,
+        decode: impl Fn(&[f32]) -> Vec<f32>,
+    ) -> Result<(), FileInfoError> {
+        if channels == 0 {
+            return Ok(());
+        }
+
+        let knots_controls = members::numbers(data, "KnotsControls")?;
+        let count = knots_controls.len() / (channels + 1);
+        let (knots, controls) = knots_controls.split_at(count);
+
+        let one_over_knot_scale = match self.format {
+            CurveFormat::D3I1K32fC32f => 1.,
+            _ => {
+                let truncated = members::uint16(data, "OneOverKnotScaleTrunc")?;
+                f32::from_bits(u32::from(truncated) << 16)
+            }
+        };
+        self.knots = knots
+            .iter()
+            .map(|knot| knot / one_over_knot_scale)
+            .collect();
+
+        for control in controls.chunks_exact(channels).take(count) {
+            let control = decode(control);
+            self.dimension = control.len();
+            self.controls.extend(control);
+        }
+        Ok(())
+    }
+
+    /// Decodes quaternions stored as their three smallest components,
+    /// with `bits` bits each.
+    ///
+    /// The top bits of the second and third values select which component
+    /// was dropped, and the top bit of the first gives its sign.
+    fn decode_quaternions(&mut self, data: &[Element], bits: u32) -> Result<(), FileInfoError> {
+        let selector = members::uint16(data, "ScaleOffsetTableEntries")?;
+        let one_over_knot_scale = members::real32(data, "OneOverKnotScale")?;
+        let knots_controls = members::integers(data, "KnotsControls")?;
+
+        let mask: u16 = (1 << bits) - 1;
+        let max = f32::from(mask);
+        let mut scales = [0.; 4];
+        let mut offsets = [0.; 4];
+        for (i, (scale, offset)) in scales.iter_mut().zip(&mut offsets).enumerate() {
+            let entry = usize::from((selector >> (4 * i)) & 0xf);
+            *scale = QUATERNION_SCALES[entry] / max;
+            *offset = QUATERNION_OFFSETS[entry];
+        }
+
+        let to_float = |value: u32| {
+            u16::try_from(value)
+                .map(f32::from)
+                .map_err(|_| FileInfoError::InvalidCurveData(self.format))
+        };
+
+        let count = knots_controls.len() / 4;
+        let (knots, controls) = knots_controls.split_at(count);
+        self.knots = knots
+            .iter()
+            .map(|knot| Ok(to_float(*knot)? / one_over_knot_scale))
+            .collect::<Result<_, _>>()?;
+
+        self.dimension = 4;
+        for control in controls.chunks_exact(3).take(count) {
+            let [a, b, c] = [control[0], control[1], control[2]];
+            let dropped = usize::from(b >> bits & 1 == 1) << 1 | usize::from(c >> bits & 1 == 1);
+
+            let mut quaternion = [0.; 4];
+            let mut sum = 0.;
+            for (i, value) in [a, b, c].into_iter().enumerate() {
+                let component = (dropped + 1 + i) & 3;
+                let value =
+                    to_float(value & u32::from(mask))? * scales[component] + offsets[component];
+                quaternion[component] = value;
+                sum += value * value;
+            }
+            let largest = (1. - sum).max(0.).sqrt();
+            quaternion[dropped] = if a >> bits & 1 == 1 {
+                -largest
+            } else {
+                largest
+            };
+            self.controls.extend(quaternion);
+        }
+        Ok(())
+    }
+
+    /// Control of an identity curve, zero for positions, the identity
+    /// quaternion for 4 dimensions and the identity matrix for 9.
+    fn identity(dimension: usize) -> Vec<f32> {
+        match dimension {
+            4 => vec![0., 0., 0., 1.],
+            9 => Self::diagonal([1.; 3]),
+            _ => vec![0.; dimension],
+        }
+    }
+
+    fn diagonal(values: [f32; 3]) -> Vec<f32> {
+        let mut matrix = vec![0.; 9];
+        for (i, value) in values.into_iter().enumerate() {
+            matrix[i * 4] = value;
+        }
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    use super::{Curve, CurveFormat};
+
+    /// One dimensional curve with a knot per control.
+    fn curve(degree: u8, knots: &[f32], controls: &[f32]) -> Curve {
+        Curve {
+            format: CurveFormat::DaK32fC32f,
+            degree,
+            dimension: 1,
+            knots: knots.to_vec(),
+            controls: controls.to_vec(),
+        }
+    }
+
+    fn assert_samples(curve: &Curve, looping: bool, samples: &[(f32, f32)]) {
+        for (time, expected) in samples {
+            let found = curve.evaluate(*time, looping);
+            assert!(
+                (found[0] - expected).abs() < 1e-5,
+                "At {}: {:?} != {}",
+                time,
+                found,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn control_past_the_end_is_none() {
+        let curve = Curve {
+            dimension: 2,
+            ..curve(1, &[0., 1.], &[1., 2., 3., 4.])
+        };
+        assert_eq!(curve.control(1), Some(&[3., 4.][..]));
+        assert_eq!(curve.control(2), None);
+        assert_eq!(curve.control(usize::MAX), None);
+    }
+
+    #[test]
+    fn step_curve_holds_every_control() {
+        let curve = curve(0, &[0., 1., 2.], &[5., 6., 7.]);
+        let samples = [(0., 5.), (0.5, 5.), (1., 6.), (1.5, 6.), (2., 7.)];
+        assert_samples(&curve, false, &samples);
+        // Before and after the knots
+        assert_samples(&curve, false, &[(-1., 5.), (3., 7.)]);
+        // The last knot starts the next loop
+        assert_samples(&curve, true, &[(2., 5.), (2.5, 5.), (-0.5, 6.)]);
+    }
+
+    #[test]
+    fn linear_curve_interpolates() {
+        let curve = curve(1, &[0., 1., 3.], &[0., 2., 4.]);
+        let samples = [(0., 0.), (0.5, 1.), (1., 2.), (2., 3.), (3., 4.)];
+        assert_samples(&curve, false, &samples);
+        assert_samples(&curve, false, &[(-1., 0.), (4., 4.)]);
+    }
+
+    #[test]
+    fn looping_linear_curve_wraps_to_its_first_control() {
+        // The last control belongs to the last knot, which is the first knot
+        // of the next loop
+        let curve = curve(1, &[0., 1., 2.], &[0., 2., 9.]);
+        let samples = [
+            (0., 0.),
+            (0.5, 1.),
+            (1., 2.),
+            (1.5, 1.),
+            (2.5, 1.),
+            (-0.5, 1.),
+        ];
+        assert_samples(&curve, true, &samples);
+    }
+
+    #[test]
+    fn quadratic_curve_blends_three_controls() {
+        // Uniform quadratic basis: 1/2 at the knots around a control and 3/4
+        // halfway between them
+        let curve = curve(2, &[0., 1., 2., 3., 4.], &[0., 0., 4., 0., 0.]);
+        let samples = [(1., 0.), (2., 2.), (2.5, 3.), (3., 2.), (1.5, 0.5)];
+        assert_samples(&curve, false, &samples);
+    }
+
+    #[test]
+    fn clamped_quadratic_curve_mirrors_its_knots() {
+        // Mirroring makes the first knot a peak of the first control, the
+        // last one an average of the last two
+        let curve = curve(2, &[0., 1., 2., 3., 4.], &[4., 0., 1., 0., 2.]);
+        let samples = [(0., 4.), (0.5, 3.5), (2., 0.5), (4., 1.)];
+        assert_samples(&curve, false, &samples);
+        assert_samples(&curve, false, &[(-1., 4.), (5., 1.)]);
+    }
+
+    #[test]
+    fn looping_quadratic_curve_wraps_its_knots() {
+        let curve = curve(2, &[0., 1., 2., 3.], &[4., 0., 0., 9.]);
+        let samples = [(0., 2.), (0.5, 3.), (1., 2.), (1.5, 0.5), (2., 0.)];
+        assert_samples(&curve, true, &samples);
+        // A loop later and earlier
+        assert_samples(&curve, true, &[(3.5, 3.), (-0.5, 0.5), (-2.5, 3.)]);
+    }
+
+    #[test]
+    fn cubic_curve_blends_four_controls() {
+        // Uniform cubic basis: 1/6, 4/6, 1/6 at the knots, 1/48 and 23/48
+        // halfway between them
+        let curve = curve(
+            3,
+            &[0., 1., 2., 3., 4., 5., 6.],
+            &[0., 0., 0., 48., 0., 0., 0.],
+        );
+        let samples = [
+            (2., 0.),
+            (2.5, 1.),
+            (3., 8.),
+            (3.5, 23.),
+            (4., 32.),
+            (5., 8.),
+        ];
+        assert_samples(&curve, false, &samples);
+    }
+
+    #[test]
+    fn looping_cubic_curve_wraps_its_controls() {
+        let curve = curve(3, &[0., 1., 2., 3.], &[6., 0., 0., 9.]);
+        let samples = [
+            (0., 1.),
+            (1., 4.),
+            (2., 1.),
+            (3., 1.),
+            (0.5, 2.875),
+            (3.5, 2.875),
+        ];
+        assert_samples(&curve, true, &samples);
+    }
+
+    #[test]
+    fn single_knot_curve_is_constant() {
+        let curve = curve(3, &[0.5], &[7.]);
+        for looping in [false, true] {
+            assert_samples(&curve, looping, &[(-1., 7.), (0.5, 7.), (10., 7.)]);
+        }
+    }
+
+    #[test]
+    fn missing_controls_give_the_identity() {
+        let curve = Curve {
+            dimension: 4,
+            ..curve(1, &[0., 1.], &[0., 0., 0., 1., 0.])
+        };
+        assert_eq!(curve.evaluate(0.5, false), [0., 0., 0., 1.]);
+    }
+
+    #[test]
+    fn quaternions_take_the_short_way() {
+        // The second control is the negated quarter turn around z
+        let curve = Curve {
+            dimension: 4,
+            ..curve(
+                1,
+                &[0., 1.],
+                &[0., 0., 0., 1., 0., 0., -FRAC_1_SQRT_2, -FRAC_1_SQRT_2],
+            )
+        };
+        let eighth_turn = [0., 0., 0.382_683_43, 0.923_879_5];
+        let found = curve.evaluate_quaternion(0.5, false);
+        assert!(
+            found
+                .iter()
+                .zip(eighth_turn)
+                .all(|(found, expected)| (found - expected).abs() < 1e-5),
+            "{:?}",
+            found
+        );
+    }
+}
@@ -0,0 +1,88 @@
+/// Storage formats of `Curve2` data, in the order of their format ids.
+///
+/// The name of each variant matches the suffix of the
+/// `CurveDataHeader_*` member found on the curve's data type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveFormat {
+    DaKeyframes32f,
+    DaK32fC32f,
+    DaIdentity,
+    DaConstant32f,
+    D3Constant32f,
+    D4Constant32f,
+    DaK16uC16u,
+    DaK8uC8u,
+    D4nK16uC15u,
+    D4nK8uC7u,
+    D3K16uC16u,
+    D3K8uC8u,
+    D9I1K16uC16u,
+    D9I3K16uC16u,
+    D9I1K8uC8u,
+    D9I3K8uC8u,
+    D3I1K32fC32f,
+    D3I1K16uC16u,
+    D3I1K8uC8u,
+}
+
+impl CurveFormat {
+    const ALL: [Self; 19] = [
+        Self::DaKeyframes32f,
+        Self::DaK32fC32f,
+        Self::DaIdentity,
+        Self::DaConstant32f,
+        Self::D3Constant32f,
+        Self::D4Constant32f,
+        Self::DaK16uC16u,
+        Self::DaK8uC8u,
+        Self::D4nK16uC15u,
+        Self::D4nK8uC7u,
+        Self::D3K16uC16u,
+        Self::D3K8uC8u,
+        Self::D9I1K16uC16u,
+        Self::D9I3K16uC16u,
+        Self::D9I1K8uC8u,
+        Self::D9I3K8uC8u,
+        Self::D3I1K32fC32f,
+        Self::D3I1K16uC16u,
+        Self::D3I1K8uC8u,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DaKeyframes32f => "DaKeyframes32f",
+            Self::DaK32fC32f => "DaK32fC32f",
+            Self::DaIdentity => "DaIdentity",
+            Self::DaConstant32f => "DaConstant32f",
+            Self::D3Constant32f => "D3Constant32f",
+            Self::D4Constant32f => "D4Constant32f",
+            Self::DaK16uC16u => "DaK16uC16u",
+            Self::DaK8uC8u => "DaK8uC8u",
+            Self::D4nK16uC15u => "D4nK16uC15u",
+            Self::D4nK8uC7u => "D4nK8uC7u",
+            Self::D3K16uC16u => "D3K16uC16u",
+            Self::D3K8uC8u => "D3K8uC8u",
+            Self::D9I1K16uC16u => "D9I1K16uC16u",
+            Self::D9I3K16uC16u => "D9I3K16uC16u",
+            Self::D9I1K8uC8u => "D9I1K8uC8u",
+            Self::D9I3K8uC8u => "D9I3K8uC8u",
+            Self::D3I1K32fC32f => "D3I1K32fC32f",
+            Self::D3I1K16uC16u => "D3I1K16uC16u",
+            Self::D3I1K8uC8u => "D3I1K8uC8u",
+        }
+    }
+
+    /// Finds the format named by a `CurveDataHeader_*` member.
+    pub fn from_header_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("CurveDataHeader_")?;
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+}
+
+impl TryFrom<u8> for CurveFormat {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::ALL.get(usize::from(value)).copied().ok_or(value)
+    }
+}
@@ -0,0 +1,25 @@
+use crate::granny2::element::Element;
+
+use super::{members, FileInfoError};
+
+/// Information about the exporter that wrote the file.
+#[derive(Debug)]
+pub struct ExporterInfo<'a> {
+    pub exporter_name: &'a str,
+    pub exporter_major_revision: i32,
+    pub exporter_minor_revision: i32,
+    pub exporter_customization: i32,
+    pub exporter_build_number: i32,
+}
+
+impl<'a> ExporterInfo<'a> {
+    pub fn parse(members: &'a [Element]) -> Result<Self, FileInfoError> {
+        Ok(Self {
+            exporter_name: members::string(members, "ExporterName")?,
+            exporter_major_revision: members::int32(members, "ExporterMajorRevision")?,
+            exporter_minor_revision: members::int32(members, "ExporterMinorRevision")?,
+            exporter_customization: members::int32(members, "ExporterCustomization")?,
+            exporter_build_number: members::int32(members, "ExporterBuildNumber")?,
+        })
+    }
+}
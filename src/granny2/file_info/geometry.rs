@@ -0,0 +1,113 @@
+use crate::granny2::element::{Element, TypeId};
+
+use super::{members, FileInfoError, TriMaterialGroup, TriTopology};
+
+/// Vertices and triangles of a mesh, one entry per vertex on every
+/// attribute.
+///
+/// Attributes are found by name on the vertex type, so any layout the
+/// exporter chose can be read. Components missing from a vertex are
+/// filled with zeros.
+#[derive(Debug, Default)]
+pub struct Geometry {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Option<Vec<[f32; 3]>>,
+    pub tangents: Option<Vec<[f32; 3]>>,
+    pub binormals: Option<Vec<[f32; 3]>>,
+    /// One list per texture coordinate channel, in channel order.
+    pub texture_coordinates: Vec<Vec<[f32; 2]>>,
+    pub bone_weights: Option<Vec<[f32; 4]>>,
+    /// Indices on the mesh's bone bindings.
+    pub bone_indices: Option<Vec<[u32; 4]>>,
+    /// Three vertex indices per triangle.
+    pub indices: Vec<u32>,
+    pub groups: Vec<TriMaterialGroup>,
+}
+
+impl Geometry {
+    pub fn parse(mesh: &[Element]) -> Result<Self, FileInfoError> {
+        let mut geometry = Self::default();
+
+        if let Some((_, vertex_data)) = members::reference(mesh, "PrimaryVertexData")? {
+            let vertices = members::variant_array(vertex_data, "Vertices")?;
+            geometry.read_vertices(vertices)?;
+        }
+
+        if let Some((_, topology)) = members::reference(mesh, "PrimaryTopology")? {
+            geometry.groups = TriTopology::parse(topology)?.groups;
+
+            let indices = members::array(topology, "Indices")?;
+            let indices = if indices.is_empty() {
+                members::array(topology, "Indices16")?
+            } else {
+                indices
+            };
+            for index in indices {
+                let [member] = &index.children[..] else {
+                    return Err(FileInfoError::WrongType(
+                        index.name.clone(),
+                        index.info.element_type,
+                    ));
+                };
+                geometry.indices.extend(members::unsigned(member)?);
+            }
+        }
+
+        Ok(geometry)
+    }
+
+    fn read_vertices(&mut self, vertices: &[Element]) -> Result<(), FileInfoError> {
+        for vertex in vertices {
+            for member in vertex.children.iter() {
+                match &*member.name {
+                    "Position" => self.positions.push(Self::components(member)?),
+                    "Normal" => Self::push(&mut self.normals, Self::components(member)?),
+                    "Tangent" => Self::push(&mut self.tangents, Self::components(member)?),
+                    "Binormal" => Self::push(&mut self.binormals, Self::components(member)?),
+                    "BoneWeights" => {
+                        let mut weights = Self::components(member)?;
+                        // Weights stored as plain bytes still add up to 255
+                        if member.info.element_type == TypeId::UInt8 {
+                            weights.iter_mut().for_each(|weight| *weight /= 255.);
+                        }
+                        Self::push(&mut self.bone_weights, weights);
+                    }
+                    "BoneIndices" => {
+                        let values = members::unsigned(member)?;
+                        let mut indices = [0; 4];
+                        indices
+                            .iter_mut()
+                            .zip(values)
+                            .for_each(|(index, value)| *index = value);
+                        Self::push(&mut self.bone_indices, indices);
+                    }
+                    name => {
+                        if let Some(channel) = name
+                            .strip_prefix("TextureCoordinates")
+                            .and_then(|channel| channel.parse::<usize>().ok())
+                        {
+                            if self.texture_coordinates.len() <= channel {
+                                self.texture_coordinates.resize(channel + 1, vec![]);
+                            }
+                            self.texture_coordinates[channel].push(Self::components(member)?);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn components<const N: usize>(member: &Element) -> Result<[f32; N], FileInfoError> {
+        let mut components = [0.; N];
+        components
+            .iter_mut()
+            .zip(members::floats(member)?)
+            .for_each(|(component, value)| *component = value);
+        Ok(components)
+    }
+
+    fn push<T>(attribute: &mut Option<Vec<T>>, value: T) {
+        attribute.get_or_insert_with(Vec::new).push(value);
+    }
+}
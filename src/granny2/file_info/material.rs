@@ -0,0 +1,48 @@
+use crate::granny2::element::Element;
+
+use super::{members, FileInfoError};
+
+#[derive(Debug)]
+pub struct Material<'a> {
+    pub name: &'a str,
+    pub maps: Vec<MaterialMap<'a>>,
+    /// Index on [`super::FileInfo::textures`].
+    pub texture: Option<usize>,
+    pub members: &'a [Element],
+}
+
+/// Material used for a purpose, such as a diffuse or normal map.
+#[derive(Debug)]
+pub struct MaterialMap<'a> {
+    pub usage: &'a str,
+    /// Index on [`super::FileInfo::materials`].
+    pub material: Option<usize>,
+}
+
+impl<'a> Material<'a> {
+    pub fn parse(
+        members: &'a [Element],
+        textures: &[u64],
+        materials: &[u64],
+    ) -> Result<Self, FileInfoError> {
+        let maps = members::array(members, "Maps")?
+            .iter()
+            .map(|map| -> Result<MaterialMap, FileInfoError> {
+                let material = members::reference(&map.children, "Material")?;
+                Ok(MaterialMap {
+                    usage: members::string(&map.children, "Usage")?,
+                    material: members::index_of(materials, material.map(|(address, _)| address)),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let texture = members::reference(members, "Texture")?;
+
+        Ok(Self {
+            name: members::string(members, "Name")?,
+            maps,
+            texture: members::index_of(textures, texture.map(|(address, _)| address)),
+            members,
+        })
+    }
+}
@@ -0,0 +1,240 @@
+use crate::granny2::{
+    element::{Data, Element, TypeId},
+    transform::Transform,
+};
+
+use super::FileInfoError;
+
+/// Finds the member called `name`.
+pub fn find<'a>(members: &'a [Element], name: &str) -> Result<&'a Element, FileInfoError> {
+    members
+        .iter()
+        .find(|member| &*member.name == name)
+        .ok_or_else(|| FileInfoError::MissingMember(name.into()))
+}
+
+fn wrong_type(member: &Element) -> FileInfoError {
+    FileInfoError::WrongType(member.name.clone(), member.info.element_type)
+}
+
+pub fn string<'a>(members: &'a [Element], name: &str) -> Result<&'a str, FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.data.as_slice()) {
+        (TypeId::String, [Data::String(string)]) => Ok(string),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+pub fn int32(members: &[Element], name: &str) -> Result<i32, FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.data.as_slice()) {
+        (TypeId::Int32, [Data::Int32(value)]) => Ok(*value),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+pub fn int16(members: &[Element], name: &str) -> Result<i16, FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.data.as_slice()) {
+        (TypeId::Int16, [Data::Int16(value)]) => Ok(*value),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+pub fn uint16(members: &[Element], name: &str) -> Result<u16, FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.data.as_slice()) {
+        (TypeId::UInt16, [Data::UInt16(value)]) => Ok(*value),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+pub fn uint8(members: &[Element], name: &str) -> Result<u8, FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.data.as_slice()) {
+        (TypeId::UInt8, [Data::UInt8(value)]) => Ok(*value),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+pub fn real32(members: &[Element], name: &str) -> Result<f32, FileInfoError> {
+    let [value] = real32_array(members, name)?;
+    Ok(value)
+}
+
+pub fn real32_array<const N: usize>(
+    members: &[Element],
+    name: &str,
+) -> Result<[f32; N], FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.as_f32_slice()) {
+        (TypeId::Real32, Some(values)) => values.try_into().map_err(|_| wrong_type(member)),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+pub fn transform<'a>(members: &'a [Element], name: &str) -> Result<&'a Transform, FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.data.as_slice()) {
+        (TypeId::Transform, [Data::Transform(transform)]) => Ok(transform),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+/// Members of the struct stored inline on `name`.
+pub fn inline<'a>(members: &'a [Element], name: &str) -> Result<&'a [Element], FileInfoError> {
+    let member = find(members, name)?;
+    match member.info.element_type {
+        TypeId::Inline => Ok(&member.children),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+/// Address and members of the object referenced by `name`, if not null.
+pub fn reference<'a>(
+    members: &'a [Element],
+    name: &str,
+) -> Result<Option<(u64, &'a [Element])>, FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.data.as_slice()) {
+        (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(0)]) => Ok(None),
+        (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(address)]) => {
+            Ok(Some((*address, &member.children)))
+        }
+        _ => Err(wrong_type(member)),
+    }
+}
+
+/// Items of the array referenced by `name`.
+pub fn array<'a>(members: &'a [Element], name: &str) -> Result<&'a [Element], FileInfoError> {
+    let member = find(members, name)?;
+    match member.info.element_type {
+        TypeId::ReferenceToArray => Ok(&member.children),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+/// Strings on the array referenced by `name`, whose items hold a single
+/// string.
+pub fn strings<'a>(members: &'a [Element], name: &str) -> Result<Vec<&'a str>, FileInfoError> {
+    array(members, name)?
+        .iter()
+        .map(|item| match &item.children[..] {
+            [member] => string(&item.children, &member.name),
+            _ => Err(wrong_type(item)),
+        })
+        .collect()
+}
+
+/// Numbers on the array referenced by `name`, whose items hold a single
+/// numeric member.
+pub fn numbers(members: &[Element], name: &str) -> Result<Vec<f32>, FileInfoError> {
+    let mut numbers = Vec::new();
+    for item in array(members, name)? {
+        let [member] = &item.children[..] else {
+            return Err(wrong_type(item));
+        };
+        numbers.extend(floats(member)?);
+    }
+    Ok(numbers)
+}
+
+/// Values on the array referenced by `name`, whose items hold a single
+/// unsigned integer member.
+pub fn integers(members: &[Element], name: &str) -> Result<Vec<u32>, FileInfoError> {
+    let mut integers = Vec::new();
+    for item in array(members, name)? {
+        let [member] = &item.children[..] else {
+            return Err(wrong_type(item));
+        };
+        integers.extend(unsigned(member)?);
+    }
+    Ok(integers)
+}
+
+/// Address and members of every object referenced by `name`, skipping
+/// null references.
+pub fn references<'a>(
+    members: &'a [Element],
+    name: &str,
+) -> Result<Vec<(u64, &'a [Element])>, FileInfoError> {
+    let member = find(members, name)?;
+    match (member.info.element_type, member.data.as_slice()) {
+        (TypeId::ArrayOfReferences, [Data::ArrayOfReferences(addresses)]) => Ok(addresses
+            .iter()
+            .zip(member.children.iter())
+            .filter(|(address, _)| **address != 0)
+            .map(|(address, item)| (*address, &item.children[..]))
+            .collect()),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+/// Members of the object referenced by the variant `name`.
+pub fn variant<'a>(members: &'a [Element], name: &str) -> Result<&'a [Element], FileInfoError> {
+    let member = find(members, name)?;
+    match member.info.element_type {
+        TypeId::VariantReference => Ok(&member.children),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+/// Items of the variant array referenced by `name`.
+pub fn variant_array<'a>(
+    members: &'a [Element],
+    name: &str,
+) -> Result<&'a [Element], FileInfoError> {
+    let member = find(members, name)?;
+    match member.info.element_type {
+        TypeId::ReferenceToVariantArray => Ok(&member.children),
+        _ => Err(wrong_type(member)),
+    }
+}
+
+/// Numeric values of a member converted to floats, normalizing the
+/// normalized integer types.
+pub fn floats(member: &Element) -> Result<Vec<f32>, FileInfoError> {
+    if let Some(values) = member.normalized() {
+        return Ok(values);
+    }
+    let values = match member.info.element_type {
+        TypeId::Int8 => member.as_i8_slice().map(widened),
+        TypeId::UInt8 => member.as_u8_slice().map(widened),
+        TypeId::Int16 => member.as_i16_slice().map(widened),
+        TypeId::UInt16 => member.as_u16_slice().map(widened),
+        TypeId::Real16 | TypeId::Real32 => member.as_f32_slice().map(<[f32]>::to_vec),
+        _ => None,
+    };
+    values.ok_or_else(|| wrong_type(member))
+}
+
+fn widened<T: Copy + Into<f32>>(values: &[T]) -> Vec<f32> {
+    values.iter().map(|value| (*value).into()).collect()
+}
+
+/// Values of an unsigned integer member.
+pub fn unsigned(member: &Element) -> Result<Vec<u32>, FileInfoError> {
+    if let Some(values) = member.as_u8_slice() {
+        return Ok(values.iter().copied().map(u32::from).collect());
+    }
+    if let Some(values) = member.as_u16_slice() {
+        return Ok(values.iter().copied().map(u32::from).collect());
+    }
+    if let Some(values) = member.as_u32_slice() {
+        return Ok(values.to_vec());
+    }
+    let signed = member
+        .as_i16_slice()
+        .map(|values| values.iter().copied().map(i32::from).collect())
+        .or_else(|| member.as_i32_slice().map(<[i32]>::to_vec))
+        .ok_or_else(|| wrong_type(member))?;
+    signed
+        .into_iter()
+        .map(|value| u32::try_from(value).map_err(|_| wrong_type(member)))
+        .collect()
+}
+
+/// Position of the object at `address` on a list of addresses.
+pub fn index_of(addresses: &[u64], address: Option<u64>) -> Option<usize> {
+    address.and_then(|address| addresses.iter().position(|known| *known == address))
+}
@@ -0,0 +1,72 @@
+use crate::granny2::element::Element;
+
+use super::{members, FileInfoError, Geometry, Skeleton};
+
+#[derive(Debug)]
+pub struct Mesh<'a> {
+    pub name: &'a str,
+    /// Index on [`super::FileInfo::vertex_datas`].
+    pub primary_vertex_data: Option<usize>,
+    /// Index on [`super::FileInfo::tri_topologies`].
+    pub primary_topology: Option<usize>,
+    /// Indices on [`super::FileInfo::materials`].
+    pub material_bindings: Vec<Option<usize>>,
+    /// Names of the bones the mesh is bound to.
+    pub bone_bindings: Vec<&'a str>,
+    pub members: &'a [Element],
+}
+
+impl<'a> Mesh<'a> {
+    pub fn parse(
+        members: &'a [Element],
+        vertex_datas: &[u64],
+        tri_topologies: &[u64],
+        materials: &[u64],
+    ) -> Result<Self, FileInfoError> {
+        let primary_vertex_data = members::reference(members, "PrimaryVertexData")?;
+        let primary_topology = members::reference(members, "PrimaryTopology")?;
+
+        let material_bindings = members::array(members, "MaterialBindings")?
+            .iter()
+            .map(|binding| {
+                members::reference(&binding.children, "Material").map(|material| {
+                    members::index_of(materials, material.map(|(address, _)| address))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bone_bindings = members::array(members, "BoneBindings")?
+            .iter()
+            .map(|binding| members::string(&binding.children, "BoneName"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            name: members::string(members, "Name")?,
+            primary_vertex_data: members::index_of(
+                vertex_datas,
+                primary_vertex_data.map(|(address, _)| address),
+            ),
+            primary_topology: members::index_of(
+                tri_topologies,
+                primary_topology.map(|(address, _)| address),
+            ),
+            material_bindings,
+            bone_bindings,
+            members,
+        })
+    }
+
+    /// Vertices and triangles of the mesh's primary vertex data and
+    /// topology.
+    pub fn geometry(&self) -> Result<Geometry, FileInfoError> {
+        Geometry::parse(self.members)
+    }
+
+    /// Index on `skeleton` of each bone binding, matched by name.
+    pub fn bone_binding_indices(&self, skeleton: &Skeleton) -> Vec<Option<usize>> {
+        self.bone_bindings
+            .iter()
+            .map(|name| skeleton.bone_index(name))
+            .collect()
+    }
+}
@@ -0,0 +1,187 @@
+mod animation;
+mod art_tool_info;
+mod bone;
+mod curve;
+mod curve_format;
+mod exporter_info;
+mod geometry;
+mod material;
+mod members;
+mod mesh;
+mod model;
+mod skeleton;
+mod texture;
+mod track_group;
+mod transform_track;
+mod tri_topology;
+mod vertex_data;
+
+use std::{error::Error, fmt::Display};
+
+pub use self::{
+    animation::Animation,
+    art_tool_info::ArtToolInfo,
+    bone::Bone,
+    curve::Curve,
+    curve_format::CurveFormat,
+    exporter_info::ExporterInfo,
+    geometry::Geometry,
+    material::{Material, MaterialMap},
+    mesh::Mesh,
+    model::Model,
+    skeleton::Skeleton,
+    texture::Texture,
+    track_group::TrackGroup,
+    transform_track::TransformTrack,
+    tri_topology::{TriMaterialGroup, TriTopology},
+    vertex_data::VertexData,
+};
+
+use super::element::{Element, TypeId};
+
+/// Typed view of the root object of a file.
+///
+/// References between objects are resolved to indices on the lists of
+/// this struct.
+#[derive(Debug)]
+pub struct FileInfo<'a> {
+    pub art_tool_info: Option<ArtToolInfo<'a>>,
+    pub exporter_info: Option<ExporterInfo<'a>>,
+    pub from_file_name: &'a str,
+    pub textures: Vec<Texture<'a>>,
+    pub materials: Vec<Material<'a>>,
+    pub skeletons: Vec<Skeleton<'a>>,
+    pub vertex_datas: Vec<VertexData<'a>>,
+    pub tri_topologies: Vec<TriTopology<'a>>,
+    pub meshes: Vec<Mesh<'a>>,
+    pub models: Vec<Model<'a>>,
+    pub track_groups: Vec<TrackGroup<'a>>,
+    pub animations: Vec<Animation<'a>>,
+    pub extended_data: &'a [Element],
+}
+
+impl<'a> FileInfo<'a> {
+    pub fn parse(root: &'a [Element]) -> Result<Self, FileInfoError> {
+        let art_tool_info = members::reference(root, "ArtToolInfo")?
+            .map(|(_, members)| ArtToolInfo::parse(members))
+            .transpose()?;
+        let exporter_info = members::reference(root, "ExporterInfo")?
+            .map(|(_, members)| ExporterInfo::parse(members))
+            .transpose()?;
+
+        let textures = members::references(root, "Textures")?;
+        let materials = members::references(root, "Materials")?;
+        let skeletons = members::references(root, "Skeletons")?;
+        let vertex_datas = members::references(root, "VertexDatas")?;
+        let tri_topologies = members::references(root, "TriTopologies")?;
+        let meshes = members::references(root, "Meshes")?;
+        let models = members::references(root, "Models")?;
+        let track_groups = members::references(root, "TrackGroups")?;
+        let animations = members::references(root, "Animations")?;
+
+        let texture_addresses = Self::addresses(&textures);
+        let material_addresses = Self::addresses(&materials);
+        let skeleton_addresses = Self::addresses(&skeletons);
+        let vertex_data_addresses = Self::addresses(&vertex_datas);
+        let tri_topology_addresses = Self::addresses(&tri_topologies);
+        let mesh_addresses = Self::addresses(&meshes);
+        let track_group_addresses = Self::addresses(&track_groups);
+
+        Ok(Self {
+            art_tool_info,
+            exporter_info,
+            from_file_name: members::string(root, "FromFileName")?,
+            textures: textures
+                .iter()
+                .map(|(_, members)| Texture::parse(members))
+                .collect::<Result<_, _>>()?,
+            materials: materials
+                .iter()
+                .map(|(_, members)| {
+                    Material::parse(members, &texture_addresses, &material_addresses)
+                })
+                .collect::<Result<_, _>>()?,
+            skeletons: skeletons
+                .iter()
+                .map(|(_, members)| Skeleton::parse(members))
+                .collect::<Result<_, _>>()?,
+            vertex_datas: vertex_datas
+                .iter()
+                .map(|(_, members)| VertexData::parse(members))
+                .collect::<Result<_, _>>()?,
+            tri_topologies: tri_topologies
+                .iter()
+                .map(|(_, members)| TriTopology::parse(members))
+                .collect::<Result<_, _>>()?,
+            meshes: meshes
+                .iter()
+                .map(|(_, members)| {
+                    Mesh::parse(
+                        members,
+                        &vertex_data_addresses,
+                        &tri_topology_addresses,
+                        &material_addresses,
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+            models: models
+                .iter()
+                .map(|(_, members)| Model::parse(members, &skeleton_addresses, &mesh_addresses))
+                .collect::<Result<_, _>>()?,
+            track_groups: track_groups
+                .iter()
+                .map(|(_, members)| TrackGroup::parse(members))
+                .collect::<Result<_, _>>()?,
+            animations: animations
+                .iter()
+                .map(|(_, members)| Animation::parse(members, &track_group_addresses))
+                .collect::<Result<_, _>>()?,
+            extended_data: members::variant(root, "ExtendedData")?,
+        })
+    }
+
+    /// Texture of `material`, or of the first of its maps that has one,
+    /// as exporters usually keep textures on the maps.
+    pub fn material_texture(&self, material: &Material) -> Option<usize> {
+        material.texture.or_else(|| {
+            material
+                .maps
+                .iter()
+                .filter_map(|map| map.material)
+                .find_map(|map| self.materials[map].texture)
+        })
+    }
+
+    fn addresses(objects: &[(u64, &[Element])]) -> Vec<u64> {
+        objects.iter().map(|(address, _)| *address).collect()
+    }
+}
+
+#[derive(Debug)]
+pub enum FileInfoError {
+    MissingMember(Box<str>),
+    WrongType(Box<str>, TypeId),
+    InvalidParentIndex(usize, i32),
+    UnknownCurveFormat(Box<str>),
+    InvalidCurveData(CurveFormat),
+}
+
+impl Display for FileInfoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingMember(name) => write!(f, "Member {} is missing.", name),
+            Self::WrongType(name, element_type) => {
+                write!(f, "Member {} has unexpected type {:?}.", name, element_type)
+            }
+            Self::InvalidParentIndex(bone, parent) => {
+                write!(f, "Bone {} has invalid parent index {}.", bone, parent)
+            }
+            Self::UnknownCurveFormat(header) => write!(f, "Unknown curve format {}.", header),
+            Self::InvalidCurveData(format) => {
+                write!(f, "Curve data does not match format {}.", format.name())
+            }
+        }
+    }
+}
+
+impl Error for FileInfoError {}
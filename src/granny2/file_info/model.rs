@@ -0,0 +1,40 @@
+use crate::granny2::{element::Element, transform::Transform};
+
+use super::{members, FileInfoError};
+
+#[derive(Debug)]
+pub struct Model<'a> {
+    pub name: &'a str,
+    /// Index on [`super::FileInfo::skeletons`].
+    pub skeleton: Option<usize>,
+    pub initial_placement: &'a Transform,
+    /// Indices on [`super::FileInfo::meshes`].
+    pub mesh_bindings: Vec<Option<usize>>,
+    pub members: &'a [Element],
+}
+
+impl<'a> Model<'a> {
+    pub fn parse(
+        members: &'a [Element],
+        skeletons: &[u64],
+        meshes: &[u64],
+    ) -> Result<Self, FileInfoError> {
+        let skeleton = members::reference(members, "Skeleton")?;
+
+        let mesh_bindings = members::array(members, "MeshBindings")?
+            .iter()
+            .map(|binding| {
+                members::reference(&binding.children, "Mesh")
+                    .map(|mesh| members::index_of(meshes, mesh.map(|(address, _)| address)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            name: members::string(members, "Name")?,
+            skeleton: members::index_of(skeletons, skeleton.map(|(address, _)| address)),
+            initial_placement: members::transform(members, "InitialPlacement")?,
+            mesh_bindings,
+            members,
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use crate::granny2::{
+    element::Element,
+    transform::{Matrix, Transform},
+};
+
+use super::{members, Bone, FileInfoError};
+
+#[derive(Debug)]
+pub struct Skeleton<'a> {
+    pub name: &'a str,
+    /// Bones, with parents always before their children.
+    pub bones: Vec<Bone<'a>>,
+    pub members: &'a [Element],
+}
+
+impl<'a> Skeleton<'a> {
+    pub fn parse(members: &'a [Element]) -> Result<Self, FileInfoError> {
+        let bones = members::array(members, "Bones")?
+            .iter()
+            .enumerate()
+            .map(|(index, bone)| Bone::parse(&bone.children, index))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            name: members::string(members, "Name")?,
+            bones,
+            members,
+        })
+    }
+
+    pub fn bone_index(&self, name: &str) -> Option<usize> {
+        self.bones.iter().position(|bone| bone.name == name)
+    }
+
+    /// World matrices of every bone on the rest pose.
+    pub fn world_matrices(&self) -> Vec<Matrix> {
+        self.compose(|index| self.bones[index].local_transform.matrix())
+    }
+
+    /// World matrices of every bone posed by `local_transforms`, given in
+    /// the same order as the bones.
+    ///
+    /// Bones without a transform keep their rest pose.
+    pub fn posed_world_matrices(&self, local_transforms: &[Transform]) -> Vec<Matrix> {
+        self.compose(|index| {
+            local_transforms
+                .get(index)
+                .unwrap_or(self.bones[index].local_transform)
+                .matrix()
+        })
+    }
+
+    /// Matrices that take vertices from the rest pose to the pose given by
+    /// `world_matrices`, as used for skinning.
+    pub fn skinning_matrices(&self, world_matrices: &[Matrix]) -> Vec<Matrix> {
+        self.bones
+            .iter()
+            .zip(world_matrices)
+            .map(|(bone, world)| *world * bone.inverse_world_4x4)
+            .collect()
+    }
+
+    fn compose(&self, local: impl Fn(usize) -> Matrix) -> Vec<Matrix> {
+        let mut world: Vec<Matrix> = Vec::with_capacity(self.bones.len());
+        for (index, bone) in self.bones.iter().enumerate() {
+            let matrix = match bone.parent_index {
+                Some(parent) => world[parent] * local(index),
+                None => local(index),
+            };
+            world.push(matrix);
+        }
+        world
+    }
+}
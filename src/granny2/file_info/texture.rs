@@ -0,0 +1,28 @@
+use crate::granny2::element::Element;
+
+use super::{members, FileInfoError};
+
+#[derive(Debug)]
+pub struct Texture<'a> {
+    pub from_file_name: &'a str,
+    pub texture_type: i32,
+    pub width: i32,
+    pub height: i32,
+    pub encoding: i32,
+    pub sub_format: i32,
+    pub members: &'a [Element],
+}
+
+impl<'a> Texture<'a> {
+    pub fn parse(members: &'a [Element]) -> Result<Self, FileInfoError> {
+        Ok(Self {
+            from_file_name: members::string(members, "FromFileName")?,
+            texture_type: members::int32(members, "TextureType")?,
+            width: members::int32(members, "Width")?,
+            height: members::int32(members, "Height")?,
+            encoding: members::int32(members, "Encoding")?,
+            sub_format: members::int32(members, "SubFormat")?,
+            members,
+        })
+    }
+}
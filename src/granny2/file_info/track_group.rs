@@ -0,0 +1,25 @@
+use crate::granny2::{element::Element, transform::Transform};
+
+use super::{members, FileInfoError, TransformTrack};
+
+#[derive(Debug)]
+pub struct TrackGroup<'a> {
+    pub name: &'a str,
+    pub transform_tracks: Vec<TransformTrack<'a>>,
+    pub initial_placement: &'a Transform,
+    pub members: &'a [Element],
+}
+
+impl<'a> TrackGroup<'a> {
+    pub fn parse(members: &'a [Element]) -> Result<Self, FileInfoError> {
+        Ok(Self {
+            name: members::string(members, "Name")?,
+            transform_tracks: members::array(members, "TransformTracks")?
+                .iter()
+                .map(|track| TransformTrack::parse(&track.children))
+                .collect::<Result<_, _>>()?,
+            initial_placement: members::transform(members, "InitialPlacement")?,
+            members,
+        })
+    }
+}
@@ -0,0 +1,66 @@
+use crate::granny2::{element::Element, transform::Transform};
+
+use super::{members, Curve, CurveFormat, FileInfoError};
+
+#[derive(Debug)]
+pub struct TransformTrack<'a> {
+    pub name: &'a str,
+    pub flags: i32,
+    pub orientation_curve: Curve,
+    pub position_curve: Curve,
+    pub scale_shear_curve: Curve,
+    pub members: &'a [Element],
+}
+
+impl<'a> TransformTrack<'a> {
+    pub fn parse(members: &'a [Element]) -> Result<Self, FileInfoError> {
+        Ok(Self {
+            name: members::string(members, "Name")?,
+            flags: members::int32(members, "Flags")?,
+            orientation_curve: Curve::parse(members::inline(members, "OrientationCurve")?)?,
+            position_curve: Curve::parse(members::inline(members, "PositionCurve")?)?,
+            scale_shear_curve: Curve::parse(members::inline(members, "ScaleShearCurve")?)?,
+            members,
+        })
+    }
+
+    /// Local transform of the track at `time`, in seconds.
+    ///
+    /// Keyframe curves have a knot every `time_step`, the other formats
+    /// already store their knots in seconds.
+    pub fn sample(&self, time: f32, time_step: f32, looping: bool) -> Transform {
+        let mut transform = Transform::IDENTITY;
+
+        if self.position_curve.format != CurveFormat::DaIdentity {
+            let time = Self::curve_time(&self.position_curve, time, time_step);
+            if let Ok(translation) = self.position_curve.evaluate(time, looping).try_into() {
+                transform.translation = translation;
+                transform.flags |= Transform::HAS_POSITION;
+            }
+        }
+        if self.orientation_curve.format != CurveFormat::DaIdentity
+            && self.orientation_curve.dimension == 4
+        {
+            let time = Self::curve_time(&self.orientation_curve, time, time_step);
+            transform.rotation = self.orientation_curve.evaluate_quaternion(time, looping);
+            transform.flags |= Transform::HAS_ORIENTATION;
+        }
+        if self.scale_shear_curve.format != CurveFormat::DaIdentity {
+            let time = Self::curve_time(&self.scale_shear_curve, time, time_step);
+            if let Ok(scale_shear) = self.scale_shear_curve.evaluate(time, looping).try_into() {
+                transform.scale_shear = scale_shear;
+                transform.flags |= Transform::HAS_SCALE_SHEAR;
+            }
+        }
+
+        transform
+    }
+
+    fn curve_time(curve: &Curve, time: f32, time_step: f32) -> f32 {
+        if curve.format == CurveFormat::DaKeyframes32f && time_step > 0. {
+            time / time_step
+        } else {
+            time
+        }
+    }
+}
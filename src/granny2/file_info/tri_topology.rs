@@ -0,0 +1,35 @@
+use crate::granny2::element::Element;
+
+use super::{members, FileInfoError};
+
+#[derive(Debug)]
+pub struct TriTopology<'a> {
+    pub groups: Vec<TriMaterialGroup>,
+    pub members: &'a [Element],
+}
+
+/// Range of triangles drawn with the same material.
+#[derive(Debug, Clone, Copy)]
+pub struct TriMaterialGroup {
+    /// Index on the mesh's material bindings.
+    pub material_index: i32,
+    pub tri_first: i32,
+    pub tri_count: i32,
+}
+
+impl<'a> TriTopology<'a> {
+    pub fn parse(members: &'a [Element]) -> Result<Self, FileInfoError> {
+        let groups = members::array(members, "Groups")?
+            .iter()
+            .map(|group| -> Result<TriMaterialGroup, FileInfoError> {
+                Ok(TriMaterialGroup {
+                    material_index: members::int32(&group.children, "MaterialIndex")?,
+                    tri_first: members::int32(&group.children, "TriFirst")?,
+                    tri_count: members::int32(&group.children, "TriCount")?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { groups, members })
+    }
+}
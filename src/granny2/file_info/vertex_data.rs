@@ -0,0 +1,21 @@
+use crate::granny2::element::Element;
+
+use super::{members, FileInfoError};
+
+#[derive(Debug)]
+pub struct VertexData<'a> {
+    /// Vertices, whose members are described by the vertex type.
+    pub vertices: &'a [Element],
+    pub vertex_component_names: Vec<&'a str>,
+    pub members: &'a [Element],
+}
+
+impl<'a> VertexData<'a> {
+    pub fn parse(members: &'a [Element]) -> Result<Self, FileInfoError> {
+        Ok(Self {
+            vertices: members::variant_array(members, "Vertices")?,
+            vertex_component_names: members::strings(members, "VertexComponentNames")?,
+            members,
+        })
+    }
+}
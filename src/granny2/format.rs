@@ -0,0 +1,175 @@
+use std::io::{Read, Write};
+
+/// Known magic signatures and the format each one describes.
+///
+/// Every combination of endianness and pointer width has an old and a new
+/// revision, and 32-bit little endian files have an extra, older, revision.
+const MAGICS: [([u8; 16], Format); 9] = [
+    (
+        [
+            184, 103, 176, 202, 248, 109, 177, 15, 132, 114, 140, 126, 94, 25, 0, 30,
+        ],
+        Format::LITTLE_ENDIAN_32,
+    ),
+    (
+        [
+            41, 222, 108, 192, 186, 164, 83, 43, 37, 245, 183, 165, 246, 102, 226, 238,
+        ],
+        Format::LITTLE_ENDIAN_32,
+    ),
+    (
+        [
+            41, 117, 49, 130, 186, 2, 17, 119, 37, 58, 96, 47, 246, 106, 140, 46,
+        ],
+        Format::LITTLE_ENDIAN_32,
+    ),
+    (
+        [
+            14, 17, 149, 181, 106, 165, 181, 75, 235, 40, 40, 80, 37, 120, 179, 4,
+        ],
+        Format::BIG_ENDIAN_32,
+    ),
+    (
+        [
+            14, 116, 162, 10, 106, 235, 235, 100, 235, 78, 30, 171, 37, 145, 219, 143,
+        ],
+        Format::BIG_ENDIAN_32,
+    ),
+    (
+        [
+            229, 155, 73, 94, 111, 99, 31, 20, 30, 19, 235, 169, 144, 190, 237, 196,
+        ],
+        Format::LITTLE_ENDIAN_64,
+    ),
+    (
+        [
+            229, 47, 74, 225, 111, 194, 138, 238, 30, 210, 180, 76, 144, 215, 85, 175,
+        ],
+        Format::LITTLE_ENDIAN_64,
+    ),
+    (
+        [
+            49, 149, 212, 227, 32, 220, 79, 98, 204, 54, 208, 58, 177, 130, 255, 137,
+        ],
+        Format::BIG_ENDIAN_64,
+    ),
+    (
+        [
+            49, 194, 78, 124, 32, 64, 163, 37, 204, 225, 194, 122, 177, 50, 73, 243,
+        ],
+        Format::BIG_ENDIAN_64,
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn u16_from_bytes(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Self::Little => u16::from_le_bytes(bytes),
+            Self::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    pub fn u32_from_bytes(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    pub fn u32_to_bytes(self, value: u32) -> [u8; 4] {
+        match self {
+            Self::Little => value.to_le_bytes(),
+            Self::Big => value.to_be_bytes(),
+        }
+    }
+
+    /// Reads a 32 bit value and widens it to `usize`.
+    pub fn read_usize<T: Read>(self, reader: &mut T) -> Result<usize, std::io::Error> {
+        let mut buffer = [0; 8];
+        match self {
+            Self::Little => {
+                reader.read_exact(&mut buffer[0..4])?;
+                Ok(usize::from_le_bytes(buffer))
+            }
+            Self::Big => {
+                reader.read_exact(&mut buffer[4..8])?;
+                Ok(usize::from_be_bytes(buffer))
+            }
+        }
+    }
+
+    /// Writes a `usize` as a 32 bit value.
+    pub fn write_usize<T: Write>(self, writer: &mut T, value: usize) -> Result<(), std::io::Error> {
+        let Ok(value) = u32::try_from(value) else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Value does not fit in 32 bits.",
+            ));
+        };
+        writer.write_all(&self.u32_to_bytes(value))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointerWidth {
+    Bits32,
+    Bits64,
+}
+
+/// Layout of the data stored in a file, as described by its magic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Format {
+    pub endianness: Endianness,
+    pub pointer_width: PointerWidth,
+}
+
+impl Format {
+    pub const LITTLE_ENDIAN_32: Self = Self {
+        endianness: Endianness::Little,
+        pointer_width: PointerWidth::Bits32,
+    };
+    pub const LITTLE_ENDIAN_64: Self = Self {
+        endianness: Endianness::Little,
+        pointer_width: PointerWidth::Bits64,
+    };
+    pub const BIG_ENDIAN_32: Self = Self {
+        endianness: Endianness::Big,
+        pointer_width: PointerWidth::Bits32,
+    };
+    pub const BIG_ENDIAN_64: Self = Self {
+        endianness: Endianness::Big,
+        pointer_width: PointerWidth::Bits64,
+    };
+
+    pub fn from_magic(magic: &[u8; 16]) -> Option<Self> {
+        MAGICS
+            .iter()
+            .find(|(known, _)| known == magic)
+            .map(|(_, format)| *format)
+    }
+
+    /// Magic used when writing files in this format.
+    pub fn magic(&self) -> [u8; 16] {
+        let Some((magic, _)) = MAGICS.iter().find(|(_, format)| format == self) else {
+            unreachable!("Every format has a magic.");
+        };
+        *magic
+    }
+
+    /// Size in bytes of pointers stored in the file.
+    pub const fn pointer_size(&self) -> usize {
+        match self.pointer_width {
+            PointerWidth::Bits32 => 4,
+            PointerWidth::Bits64 => 8,
+        }
+    }
+}
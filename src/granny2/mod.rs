@@ -1,20 +1,27 @@
+pub mod checksum;
 pub mod compression;
 pub mod element;
+pub mod export;
+pub mod file_info;
+pub mod format;
 pub mod reference;
 pub mod section;
 pub mod transform;
+pub mod writer;
 
-use std::{fmt::Display, io::Read};
+use std::{
+    fmt::Display,
+    io::{Read, Write},
+};
 
+use format::Format;
 use reference::Reference;
 
-const HEADER_MAGIC: [u8; 16] = [
-    184, 103, 176, 202, 248, 109, 177, 15, 132, 114, 140, 126, 94, 25, 0, 30,
-];
-
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     pub magic: [u8; 16],
+    pub format: Format,
     pub header_size: u32,
     pub compression_type: u32,
     pub extra_bytes: [u8; 8],
@@ -38,20 +45,21 @@ impl Header {
             buffer
         };
 
-        if magic != HEADER_MAGIC {
+        let Some(format) = Format::from_magic(&magic) else {
             return Err(HeaderError::MagicMismatch);
-        }
+        };
+        let endianness = format.endianness;
 
         let header_size = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let compression_type = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let extra_bytes = {
@@ -63,56 +71,36 @@ impl Header {
         let version = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let file_size = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let checksum = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let section_offset = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let section_count = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
-        };
-
-        let root_node_type = {
-            let mut section = [0; 8];
-            reader.read_exact(&mut section[0..4])?;
-            let mut offset = [0; 8];
-            reader.read_exact(&mut offset[0..4])?;
-
-            Reference {
-                section: usize::from_le_bytes(section),
-                offset: usize::from_le_bytes(offset),
-            }
+            endianness.u32_from_bytes(buffer)
         };
 
-        let root_node_object = {
-            let mut section = [0; 8];
-            reader.read_exact(&mut section[0..4])?;
-            let mut offset = [0; 8];
-            reader.read_exact(&mut offset[0..4])?;
+        let root_node_type = Reference::parse(reader, endianness)?;
 
-            Reference {
-                section: usize::from_le_bytes(section),
-                offset: usize::from_le_bytes(offset),
-            }
-        };
+        let root_node_object = Reference::parse(reader, endianness)?;
 
         let user_tag = {
             let mut buffer = [0; 4];
@@ -131,6 +119,7 @@ impl Header {
 
         let header = Self {
             magic,
+            format,
             header_size,
             compression_type,
             extra_bytes,
@@ -149,6 +138,29 @@ impl Header {
     }
 }
 
+impl Header {
+    pub fn write<T: Write>(&self, writer: &mut T) -> Result<(), std::io::Error> {
+        let endianness = self.format.endianness;
+        writer.write_all(&self.magic)?;
+        writer.write_all(&endianness.u32_to_bytes(self.header_size))?;
+        writer.write_all(&endianness.u32_to_bytes(self.compression_type))?;
+        writer.write_all(&self.extra_bytes)?;
+        for value in [
+            self.version,
+            self.file_size,
+            self.checksum,
+            self.section_offset,
+            self.section_count,
+        ] {
+            writer.write_all(&endianness.u32_to_bytes(value))?;
+        }
+        self.root_node_type.write(writer, endianness)?;
+        self.root_node_object.write(writer, endianness)?;
+        writer.write_all(&self.user_tag)?;
+        writer.write_all(&self.user_data)
+    }
+}
+
 #[derive(Debug)]
 pub enum HeaderError {
     OutOfBoundsRead(usize),
@@ -1,5 +1,28 @@
+use std::io::{Read, Write};
+
+use super::format::Endianness;
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Reference {
     pub section: usize,
     pub offset: usize,
 }
+
+impl Reference {
+    pub fn parse<T: Read>(reader: &mut T, endianness: Endianness) -> Result<Self, std::io::Error> {
+        let section = endianness.read_usize(reader)?;
+        let offset = endianness.read_usize(reader)?;
+
+        Ok(Self { section, offset })
+    }
+
+    pub fn write<T: Write>(
+        &self,
+        writer: &mut T,
+        endianness: Endianness,
+    ) -> Result<(), std::io::Error> {
+        endianness.write_usize(writer, self.section)?;
+        endianness.write_usize(writer, self.offset)
+    }
+}
@@ -1,11 +1,16 @@
 use std::{error::Error, fmt::Display};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CompressionMode {
     None,
     Oodle0,
     Oodle1,
+    /// First BitKnit revision, out of scope: reading or writing a section
+    /// compressed with it fails with
+    /// [`SectionError::UnsupportedCompression`](super::SectionError::UnsupportedCompression).
     Bitknit1,
+    /// Read only, writing fails as for [`Bitknit1`](Self::Bitknit1).
     Bitknit2,
 }
 
@@ -24,6 +29,18 @@ impl TryFrom<u32> for CompressionMode {
     }
 }
 
+impl From<CompressionMode> for u32 {
+    fn from(value: CompressionMode) -> Self {
+        match value {
+            CompressionMode::None => 0,
+            CompressionMode::Oodle0 => 1,
+            CompressionMode::Oodle1 => 2,
+            CompressionMode::Bitknit1 => 3,
+            CompressionMode::Bitknit2 => 4,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct CompressionModeError(pub u32);
 
@@ -0,0 +1,182 @@
+use std::{
+    borrow::Cow,
+    io::{BufRead, Read, Seek, SeekFrom},
+};
+
+/// Data of every section of a file, decompressed, relocated and
+/// marshalled.
+///
+/// Sections are laid out back to back, positions counting from the start
+/// of the first one as relocated pointers do. Sections that needed none of
+/// those steps are borrowed from the file instead of copied.
+#[derive(Debug, Clone, Default)]
+pub struct SectionData<'a> {
+    sections: Vec<Cow<'a, [u8]>>,
+    /// Position of every section.
+    starts: Vec<u64>,
+}
+
+impl<'a> SectionData<'a> {
+    pub fn new(sections: Vec<Cow<'a, [u8]>>) -> Self {
+        let starts = sections
+            .iter()
+            .scan(0, |end, section| {
+                let start = *end;
+                *end += to_u64(section.len());
+                Some(start)
+            })
+            .collect();
+        Self { sections, starts }
+    }
+
+    /// Data of each section, borrowed from the file when it needed no
+    /// decompression, relocation nor marshalling.
+    pub fn sections(&self) -> &[Cow<'a, [u8]>] {
+        &self.sections
+    }
+
+    /// Copies the sections borrowed from the file.
+    pub fn into_owned(self) -> SectionData<'static> {
+        SectionData {
+            sections: self
+                .sections
+                .into_iter()
+                .map(|section| Cow::Owned(section.into_owned()))
+                .collect(),
+            starts: self.starts,
+        }
+    }
+
+    /// Size of all sections.
+    pub fn len(&self) -> u64 {
+        self.starts
+            .last()
+            .zip(self.sections.last())
+            .map_or(0, |(start, section)| start + to_u64(section.len()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `size` bytes at `pos`, `None` unless all of them are on the same
+    /// section.
+    pub fn get(&self, pos: u64, size: usize) -> Option<&[u8]> {
+        self.tail(pos)?.get(..size)
+    }
+
+    /// Bytes from `pos` to the end of its section.
+    pub fn tail(&self, pos: u64) -> Option<&[u8]> {
+        // Empty sections start where the next one does, which is skipped to
+        let section = self
+            .starts
+            .partition_point(|start| *start <= pos)
+            .checked_sub(1)?;
+        let offset = usize::try_from(pos - self.starts[section]).ok()?;
+        self.sections[section].get(offset..)
+    }
+
+    /// Reader going through all sections as if they were one buffer.
+    pub fn reader(&self) -> SectionReader<'_> {
+        SectionReader { data: self, pos: 0 }
+    }
+}
+
+/// Reader over [`SectionData`], reads going on from the end of a section
+/// to the start of the next one.
+#[derive(Debug, Clone)]
+pub struct SectionReader<'a> {
+    data: &'a SectionData<'a>,
+    pos: u64,
+}
+
+impl Read for SectionReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let size = available.len().min(buf.len());
+        buf[..size].copy_from_slice(&available[..size]);
+        self.consume(size);
+        Ok(size)
+    }
+}
+
+impl BufRead for SectionReader<'_> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.data.tail(self.pos).unwrap_or_default())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = self.pos.saturating_add(to_u64(amt));
+    }
+}
+
+impl Seek for SectionReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(offset) => self.data.len().checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+        self.pos = pos.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Seeked before the start of the section data.",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
+
+fn to_u64(value: usize) -> u64 {
+    let Ok(value) = u64::try_from(value) else {
+        unreachable!("Size must be smaller than u64.");
+    };
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        io::{BufRead, Read, Seek, SeekFrom},
+    };
+
+    use super::SectionData;
+
+    fn data() -> SectionData<'static> {
+        SectionData::new(vec![
+            Cow::Borrowed(b"abc"),
+            Cow::Borrowed(b""),
+            Cow::Owned(b"de\0".to_vec()),
+        ])
+    }
+
+    #[test]
+    fn slices_stay_on_one_section() {
+        let data = data();
+        assert_eq!(data.len(), 6);
+        assert_eq!(data.get(1, 2), Some(&b"bc"[..]));
+        assert_eq!(data.get(3, 3), Some(&b"de\0"[..]));
+        assert_eq!(data.get(2, 2), None);
+        assert_eq!(data.get(6, 0), Some(&b""[..]));
+        assert_eq!(data.get(7, 0), None);
+        assert_eq!(data.tail(4), Some(&b"e\0"[..]));
+    }
+
+    #[test]
+    fn reads_across_sections() {
+        let data = data();
+        let mut reader = data.reader();
+        reader.seek(SeekFrom::Start(1)).unwrap();
+        let mut buffer = vec![];
+        reader.read_until(0, &mut buffer).unwrap();
+        assert_eq!(buffer, b"bcde\0");
+
+        let mut buffer = [0; 4];
+        reader.seek(SeekFrom::End(-5)).unwrap();
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(&buffer, b"bcde");
+        assert!(reader.read_exact(&mut buffer).is_err());
+        assert!(reader.seek(SeekFrom::Current(-7)).is_err());
+    }
+}
@@ -1,7 +1,342 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt::Display,
+    io::{Cursor, Read, Write},
+};
+
+use crate::granny2::{
+    element::{Info, InfoError, TypeId},
+    format::{Endianness, Format},
+};
+
+/// Describes `count` objects at `src_offset` whose type is at
+/// `dst_offset` in section `dst_section`.
 #[derive(Debug)]
 pub struct Marshalling {
-    count: usize,
-    src_offset: usize,
-    dst_section: usize,
-    dst_offset: usize,
+    pub count: usize,
+    pub src_offset: usize,
+    pub dst_section: usize,
+    pub dst_offset: usize,
+}
+
+impl Marshalling {
+    pub fn parse<T: Read>(reader: &mut T, endianness: Endianness) -> Result<Self, std::io::Error> {
+        let count = endianness.read_usize(reader)?;
+        let src_offset = endianness.read_usize(reader)?;
+        let dst_section = endianness.read_usize(reader)?;
+        let dst_offset = endianness.read_usize(reader)?;
+
+        Ok(Self {
+            count,
+            src_offset,
+            dst_section,
+            dst_offset,
+        })
+    }
+
+    pub fn write<T: Write>(
+        &self,
+        writer: &mut T,
+        endianness: Endianness,
+    ) -> Result<(), std::io::Error> {
+        endianness.write_usize(writer, self.count)?;
+        endianness.write_usize(writer, self.src_offset)?;
+        endianness.write_usize(writer, self.dst_section)?;
+        endianness.write_usize(writer, self.dst_offset)
+    }
+
+    pub const fn sizeof() -> usize {
+        16
+    }
+}
+
+/// Restores the byte order of big endian section data.
+///
+/// Granny byte swaps big endian sections as if they only held 32 bit
+/// values, which is wrong for anything smaller (and anything that is not
+/// aligned to 4 bytes). The marshaller walks the type tree and rewrites
+/// every member from the original bytes according to its type. Pointers
+/// that were relocated are left alone.
+pub struct Marshaller<'a> {
+    data: &'a mut [u8],
+    original: &'a [u8],
+    relocated: &'a HashSet<usize>,
+    format: Format,
+    visited_types: HashSet<u64>,
+    visited_objects: HashSet<(u64, u64)>,
+}
+
+impl<'a> Marshaller<'a> {
+    pub fn new(
+        data: &'a mut [u8],
+        original: &'a [u8],
+        relocated: &'a HashSet<usize>,
+        format: Format,
+    ) -> Self {
+        Self {
+            data,
+            original,
+            relocated,
+            format,
+            visited_types: HashSet::new(),
+            visited_objects: HashSet::new(),
+        }
+    }
+
+    /// Swaps a section as a sequence of 32 bit values, like Granny does.
+    pub fn reverse_32(section: &mut [u8]) {
+        section
+            .chunks_exact_mut(4)
+            .for_each(|chunk| chunk.reverse());
+    }
+
+    /// Marshalls `count` consecutive objects of the type at `types_pos`.
+    pub fn marshall(
+        &mut self,
+        types_pos: u64,
+        object_pos: u64,
+        count: usize,
+    ) -> Result<(), MarshallingError> {
+        let size = self.struct_size(types_pos)?;
+        let mut pos = object_pos;
+        for _ in 0..count {
+            self.marshall_object(types_pos, pos)?;
+            pos += Self::to_u64(size);
+        }
+        Ok(())
+    }
+
+    fn marshall_object(&mut self, types_pos: u64, object_pos: u64) -> Result<(), MarshallingError> {
+        if !self.visited_objects.insert((types_pos, object_pos)) {
+            return Ok(());
+        }
+
+        let infos = self.marshall_type(types_pos)?;
+
+        let pointer_size = self.format.pointer_size();
+        let mut pos = Self::to_usize(object_pos);
+        for info in infos {
+            let size = info.size(&mut Cursor::new(&*self.data), self.format)?;
+            match info.element_type {
+                TypeId::Int8 | TypeId::UInt8 | TypeId::Int8Norm | TypeId::UInt8Norm => {
+                    self.restore(pos, size, 1)?;
+                }
+                TypeId::Int16
+                | TypeId::UInt16
+                | TypeId::Int16Norm
+                | TypeId::UInt16Norm
+                | TypeId::Real16 => {
+                    self.restore(pos, size, 2)?;
+                }
+                TypeId::Int32 | TypeId::UInt32 | TypeId::Real32 | TypeId::Transform => {
+                    self.restore(pos, size, 4)?;
+                }
+                TypeId::String => {
+                    let target = self.pointer(pos)?;
+                    self.restore_string(target)?;
+                }
+                TypeId::Reference | TypeId::EmptyReference => {
+                    let target = self.pointer(pos)?;
+                    if target != 0 {
+                        self.marshall_object(info.children_offset, target)?;
+                    }
+                }
+                TypeId::ReferenceToArray => {
+                    let count = self.count(pos)?;
+                    let target = self.pointer(pos + 4)?;
+                    if target != 0 {
+                        self.marshall(info.children_offset, target, count)?;
+                    }
+                }
+                TypeId::ArrayOfReferences => {
+                    let count = self.count(pos)?;
+                    let target = self.pointer(pos + 4)?;
+                    if target != 0 {
+                        for i in 0..count {
+                            let reference =
+                                self.pointer(Self::to_usize(target) + i * pointer_size)?;
+                            if reference != 0 {
+                                self.marshall_object(info.children_offset, reference)?;
+                            }
+                        }
+                    }
+                }
+                TypeId::VariantReference => {
+                    let variant_type = self.pointer(pos)?;
+                    let target = self.pointer(pos + pointer_size)?;
+                    if variant_type != 0 && target != 0 {
+                        self.marshall_object(variant_type, target)?;
+                    }
+                }
+                TypeId::ReferenceToVariantArray => {
+                    let variant_type = self.pointer(pos)?;
+                    let count = self.count(pos + pointer_size)?;
+                    let target = self.pointer(pos + pointer_size + 4)?;
+                    if variant_type != 0 && target != 0 {
+                        self.marshall(variant_type, target, count)?;
+                    }
+                }
+                TypeId::Inline => {
+                    self.marshall(info.children_offset, Self::to_u64(pos), info.array_size)?;
+                }
+                TypeId::None | TypeId::Removed => (),
+            }
+            pos += size;
+        }
+
+        Ok(())
+    }
+
+    /// Restores the [`Info`] records of a type and the types it references.
+    fn marshall_type(&mut self, types_pos: u64) -> Result<Vec<Info>, MarshallingError> {
+        if self.visited_types.insert(types_pos) {
+            let pointer_size = self.format.pointer_size();
+            let mut pos = Self::to_usize(types_pos);
+            loop {
+                self.restore(pos, 4, 4)?;
+                let element_type = {
+                    let mut buffer = [0; 4];
+                    buffer.copy_from_slice(self.slice(pos, 4)?);
+                    u32::from_le_bytes(buffer)
+                };
+                if !matches!(TypeId::try_from(element_type), Ok(other) if other != TypeId::None) {
+                    break;
+                }
+
+                let name = self.pointer(pos + 4)?;
+                self.restore_string(name)?;
+                self.pointer(pos + 4 + pointer_size)?;
+                self.restore(pos + 4 + 2 * pointer_size, 16, 4)?;
+                self.pointer(pos + 20 + 2 * pointer_size)?;
+
+                pos += Info::sizeof(self.format);
+            }
+
+            let infos = Info::parse(&mut Cursor::new(&*self.data), types_pos, self.format)?;
+            for info in &infos {
+                if info.children_offset != 0 {
+                    self.marshall_type(info.children_offset)?;
+                }
+            }
+            Ok(infos)
+        } else {
+            Ok(Info::parse(
+                &mut Cursor::new(&*self.data),
+                types_pos,
+                self.format,
+            )?)
+        }
+    }
+
+    fn struct_size(&mut self, types_pos: u64) -> Result<usize, MarshallingError> {
+        self.marshall_type(types_pos)?;
+        Ok(Info::struct_size(
+            &mut Cursor::new(&*self.data),
+            types_pos,
+            self.format,
+        )?)
+    }
+
+    /// Rewrites `size` bytes at `pos` from the original data, swapping
+    /// every `width` bytes.
+    fn restore(&mut self, pos: usize, size: usize, width: usize) -> Result<(), MarshallingError> {
+        let Some(original) = self.original.get(pos..(pos + size)) else {
+            return Err(MarshallingError::OutOfBounds(pos));
+        };
+        let Some(data) = self.data.get_mut(pos..(pos + size)) else {
+            return Err(MarshallingError::OutOfBounds(pos));
+        };
+        data.copy_from_slice(original);
+        data.chunks_exact_mut(width)
+            .for_each(|chunk| chunk.reverse());
+        Ok(())
+    }
+
+    fn restore_string(&mut self, pos: u64) -> Result<(), MarshallingError> {
+        if pos == 0 {
+            return Ok(());
+        }
+        let pos = Self::to_usize(pos);
+        let Some(length) = self
+            .original
+            .get(pos..)
+            .and_then(|string| string.iter().position(|byte| *byte == 0))
+        else {
+            return Err(MarshallingError::OutOfBounds(pos));
+        };
+        // Includes the trailing '\0'
+        self.restore(pos, length + 1, 1)
+    }
+
+    fn count(&mut self, pos: usize) -> Result<usize, MarshallingError> {
+        self.restore(pos, 4, 4)?;
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(self.slice(pos, 4)?);
+        let Ok(count) = usize::try_from(u32::from_le_bytes(buffer)) else {
+            unreachable!("Count must be smaller than usize.");
+        };
+        Ok(count)
+    }
+
+    /// Reads a pointer, restoring it first if it was not relocated.
+    fn pointer(&mut self, pos: usize) -> Result<u64, MarshallingError> {
+        let pointer_size = self.format.pointer_size();
+        if !self.relocated.contains(&pos) {
+            self.restore(pos, pointer_size, pointer_size)?;
+        }
+        let mut buffer = [0; 8];
+        buffer[..pointer_size].copy_from_slice(self.slice(pos, pointer_size)?);
+        Ok(u64::from_le_bytes(buffer))
+    }
+
+    fn slice(&self, pos: usize, size: usize) -> Result<&[u8], MarshallingError> {
+        self.data
+            .get(pos..(pos + size))
+            .ok_or(MarshallingError::OutOfBounds(pos))
+    }
+
+    fn to_usize(pos: u64) -> usize {
+        let Ok(pos) = usize::try_from(pos) else {
+            unreachable!("Position must be smaller than usize.");
+        };
+        pos
+    }
+
+    fn to_u64(pos: usize) -> u64 {
+        let Ok(pos) = u64::try_from(pos) else {
+            unreachable!("Position must be smaller than u64.");
+        };
+        pos
+    }
+}
+
+#[derive(Debug)]
+pub enum MarshallingError {
+    OutOfBounds(usize),
+    Info,
+}
+
+impl Display for MarshallingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfBounds(pos) => {
+                write!(
+                    f,
+                    "Marshalling would access data outside of sections at {}.",
+                    pos
+                )
+            }
+            Self::Info => write!(f, "Couldn't marshall data due to error reading Info."),
+        }
+    }
+}
+
+impl Error for MarshallingError {}
+
+impl From<InfoError> for MarshallingError {
+    fn from(value: InfoError) -> Self {
+        log::error!("{}", value);
+        Self::Info
+    }
 }
@@ -1,28 +1,40 @@
-use std::io::Read;
+use std::io::{Read, Write};
+
+use crate::granny2::format::Endianness;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MarshallingHeader {
     pub offset: u32,
     pub count: u32,
 }
 
 impl MarshallingHeader {
-    pub fn parse<T: Read>(reader: &mut T) -> Result<Self, std::io::Error> {
+    pub fn parse<T: Read>(reader: &mut T, endianness: Endianness) -> Result<Self, std::io::Error> {
         log::trace!("Parsing marshalling header");
         let offset = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let count = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let marshalling_header = Self { offset, count };
 
         Ok(marshalling_header)
     }
+
+    pub fn write<T: Write>(
+        &self,
+        writer: &mut T,
+        endianness: Endianness,
+    ) -> Result<(), std::io::Error> {
+        writer.write_all(&endianness.u32_to_bytes(self.offset))?;
+        writer.write_all(&endianness.u32_to_bytes(self.count))
+    }
 }
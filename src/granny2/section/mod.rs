@@ -1,24 +1,32 @@
 mod compression_mode;
+mod data;
 mod marshalling;
 mod marshalling_header;
 mod relocation;
 mod relocation_header;
 
 use std::{
+    borrow::Cow,
     fmt::Display,
-    io::{Read, Seek},
+    io::{Cursor, Read, Seek, Write},
 };
 
 pub use self::{
     compression_mode::{CompressionMode, CompressionModeError},
+    data::{SectionData, SectionReader},
+    marshalling::{Marshaller, Marshalling, MarshallingError},
     marshalling_header::MarshallingHeader,
     relocation::Relocation,
     relocation_header::RelocationHeader,
 };
 
-use super::compression::{Oodle, OodleError};
+use super::{
+    compression::{Bitknit, BitknitError, Oodle, OodleError},
+    format::Endianness,
+};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Section {
     pub compression_mode: CompressionMode,
     pub section_offset: u32,
@@ -32,30 +40,33 @@ pub struct Section {
 }
 
 impl Section {
-    pub fn parse<T: Read>(mut reader: &mut T) -> Result<Self, SectionError> {
+    pub fn parse<T: Read>(
+        mut reader: &mut T,
+        endianness: Endianness,
+    ) -> Result<Self, SectionError> {
         log::trace!("Parsing section header");
         let compression_mode = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer).try_into()?
+            endianness.u32_from_bytes(buffer).try_into()?
         };
 
         let section_offset = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let compressed_size = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let decompressed_size = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         if compression_mode == CompressionMode::None && compressed_size != decompressed_size {
@@ -68,24 +79,26 @@ impl Section {
         let alignment_size = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let stop_0 = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
         let stop_1 = {
             let mut buffer = [0; 4];
             reader.read_exact(&mut buffer)?;
-            u32::from_le_bytes(buffer)
+            endianness.u32_from_bytes(buffer)
         };
 
-        let relocation_header = relocation_header::RelocationHeader::parse(&mut reader)?;
+        let relocation_header =
+            relocation_header::RelocationHeader::parse(&mut reader, endianness)?;
 
-        let marshalling_header = marshalling_header::MarshallingHeader::parse(&mut reader)?;
+        let marshalling_header =
+            marshalling_header::MarshallingHeader::parse(&mut reader, endianness)?;
 
         let header = Self {
             compression_mode,
@@ -102,6 +115,31 @@ impl Section {
         Ok(header)
     }
 
+    pub fn write<T: Write>(
+        &self,
+        writer: &mut T,
+        endianness: Endianness,
+    ) -> Result<(), std::io::Error> {
+        for value in [
+            u32::from(self.compression_mode),
+            self.section_offset,
+            self.compressed_size,
+            self.decompressed_size,
+            self.alignment_size,
+            self.stop_0,
+            self.stop_1,
+        ] {
+            writer.write_all(&endianness.u32_to_bytes(value))?;
+        }
+        self.relocation_header.write(writer, endianness)?;
+        self.marshalling_header.write(writer, endianness)
+    }
+
+    /// Size of a section header in the file.
+    pub const fn sizeof() -> usize {
+        44
+    }
+
     pub fn read_data<T: Read + Seek>(&self, reader: &mut T) -> Result<Vec<u8>, SectionError> {
         reader.seek(std::io::SeekFrom::Start(u64::from(self.section_offset)))?;
         if self.compression_mode == CompressionMode::None {
@@ -131,13 +169,59 @@ impl Section {
                     Oodle::decompress(reader, compressed_size, decompressed_size, stop_0, stop_1)
                         .map_err(SectionError::from)
                 }
-                CompressionMode::Bitknit1 | CompressionMode::Bitknit2 => {
-                    Ok(vec![0; compressed_size])
+                CompressionMode::Bitknit2 => {
+                    Bitknit::decompress(reader, compressed_size, decompressed_size)
+                        .map_err(SectionError::from)
+                }
+                CompressionMode::Bitknit1 => {
+                    Err(SectionError::UnsupportedCompression(self.compression_mode))
                 }
                 CompressionMode::None => unreachable!("CompressionMode None already dealt with."),
             }
         }
     }
+
+    /// Data of the section in `file`, borrowed when it is stored
+    /// uncompressed.
+    pub fn data<'a>(&self, file: &'a [u8]) -> Result<Cow<'a, [u8]>, SectionError> {
+        if self.compression_mode != CompressionMode::None {
+            return self.read_data(&mut Cursor::new(file)).map(Cow::Owned);
+        }
+
+        let (Ok(start), Ok(size)) = (
+            usize::try_from(self.section_offset),
+            usize::try_from(self.decompressed_size),
+        ) else {
+            return Err(SectionError::BufferCreation(self.decompressed_size));
+        };
+        let Some(data) = start.checked_add(size).and_then(|end| file.get(start..end)) else {
+            return Err(SectionError::from(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Section data would be read after end of file.",
+            )));
+        };
+        Ok(Cow::Borrowed(data))
+    }
+
+    /// Compresses `data` with the section's compression mode, splitting
+    /// Oodle blocks at `stop_0` and `stop_1`.
+    pub fn write_data(&self, data: &[u8]) -> Result<Vec<u8>, SectionError> {
+        match self.compression_mode {
+            CompressionMode::None => Ok(data.to_vec()),
+            CompressionMode::Oodle0 | CompressionMode::Oodle1 => {
+                let Ok(stop_0) = usize::try_from(self.stop_0) else {
+                    return Err(SectionError::BufferCreation(self.stop_0));
+                };
+                let Ok(stop_1) = usize::try_from(self.stop_1) else {
+                    return Err(SectionError::BufferCreation(self.stop_1));
+                };
+                Oodle::compress(data, stop_0, stop_1).map_err(SectionError::from)
+            }
+            CompressionMode::Bitknit1 | CompressionMode::Bitknit2 => {
+                Err(SectionError::UnsupportedCompression(self.compression_mode))
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -145,6 +229,8 @@ pub enum SectionError {
     BufferCreation(u32),
     NoCompressionSizeMismatch(u32, u32),
     CompressionMode,
+    UnsupportedCompression(CompressionMode),
+    Decompression,
     Io,
 }
 
@@ -160,6 +246,14 @@ impl Display for SectionError {
                 compressed_size, decompressed_size
             ),
             Self::CompressionMode => write!(f, "Section had invalid compression mode."),
+            Self::UnsupportedCompression(compression_mode) => {
+                write!(
+                    f,
+                    "Compression mode {:?} is not supported.",
+                    compression_mode
+                )
+            }
+            Self::Decompression => write!(f, "Section data could not be decompressed."),
             Self::Io => write!(f, "Couldn't parse Section due to Io error."),
         }
     }
@@ -182,6 +276,80 @@ impl From<CompressionModeError> for SectionError {
 impl From<OodleError> for SectionError {
     fn from(value: OodleError) -> Self {
         log::error!("{}", value);
-        Self::CompressionMode
+        Self::Decompression
+    }
+}
+
+impl From<BitknitError> for SectionError {
+    fn from(value: BitknitError) -> Self {
+        log::error!("{}", value);
+        Self::Decompression
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{CompressionMode, MarshallingHeader, RelocationHeader, Section, SectionError};
+
+    fn section(compression_mode: CompressionMode, compressed_size: u32) -> Section {
+        Section {
+            compression_mode,
+            section_offset: 0,
+            compressed_size,
+            decompressed_size: 16,
+            alignment_size: 4,
+            stop_0: 0,
+            stop_1: 0,
+            relocation_header: RelocationHeader {
+                offset: 0,
+                count: 0,
+            },
+            marshalling_header: MarshallingHeader {
+                offset: 0,
+                count: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn bitknit_failure_is_a_decompression_error() {
+        // Valid magic followed by a truncated quantum
+        let data = [0xb1, 0x75, 0xff, 0xff];
+        let section = section(CompressionMode::Bitknit2, 4);
+        assert!(matches!(
+            section.read_data(&mut Cursor::new(&data)),
+            Err(SectionError::Decompression)
+        ));
+    }
+
+    #[test]
+    fn oodle_failure_is_a_decompression_error() {
+        // Too short to hold the parameters of the three blocks
+        let data = [0xff; 4];
+        for mode in [CompressionMode::Oodle0, CompressionMode::Oodle1] {
+            assert!(matches!(
+                section(mode, 4).read_data(&mut Cursor::new(&data)),
+                Err(SectionError::Decompression)
+            ));
+        }
+    }
+
+    #[test]
+    fn bitknit1_is_unsupported() {
+        let section = section(CompressionMode::Bitknit1, 4);
+        assert!(matches!(
+            section.read_data(&mut Cursor::new(&[0; 4])),
+            Err(SectionError::UnsupportedCompression(
+                CompressionMode::Bitknit1
+            ))
+        ));
+        assert!(matches!(
+            section.write_data(&[0; 16]),
+            Err(SectionError::UnsupportedCompression(
+                CompressionMode::Bitknit1
+            ))
+        ));
     }
 }
@@ -1,4 +1,6 @@
-use std::io::Read;
+use std::io::{Read, Write};
+
+use crate::granny2::format::Endianness;
 
 #[derive(Debug)]
 pub struct Relocation {
@@ -8,22 +10,10 @@ pub struct Relocation {
 }
 
 impl Relocation {
-    pub fn parse<T: Read>(reader: &mut T) -> Result<Self, std::io::Error> {
-        let src_offset = {
-            let mut buffer = [0; 8];
-            reader.read_exact(&mut buffer[0..4])?;
-            usize::from_le_bytes(buffer)
-        };
-        let dst_section = {
-            let mut buffer = [0; 8];
-            reader.read_exact(&mut buffer[0..4])?;
-            usize::from_le_bytes(buffer)
-        };
-        let dst_offset = {
-            let mut buffer = [0; 8];
-            reader.read_exact(&mut buffer[0..4])?;
-            usize::from_le_bytes(buffer)
-        };
+    pub fn parse<T: Read>(reader: &mut T, endianness: Endianness) -> Result<Self, std::io::Error> {
+        let src_offset = endianness.read_usize(reader)?;
+        let dst_section = endianness.read_usize(reader)?;
+        let dst_offset = endianness.read_usize(reader)?;
 
         Ok(Self {
             src_offset,
@@ -32,6 +22,16 @@ impl Relocation {
         })
     }
 
+    pub fn write<T: Write>(
+        &self,
+        writer: &mut T,
+        endianness: Endianness,
+    ) -> Result<(), std::io::Error> {
+        endianness.write_usize(writer, self.src_offset)?;
+        endianness.write_usize(writer, self.dst_section)?;
+        endianness.write_usize(writer, self.dst_offset)
+    }
+
     pub const fn sizeof() -> usize {
         12
     }
@@ -0,0 +1,44 @@
+use std::ops::Mul;
+
+/// 4x4 matrix stored like Granny's `InverseWorld4x4`, column by column,
+/// with the translation on the last column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix(pub [f32; 16]);
+
+impl Matrix {
+    pub const IDENTITY: Self = Self([
+        1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 1.,
+    ]);
+
+    pub fn translation(&self) -> [f32; 3] {
+        [self.0[12], self.0[13], self.0[14]]
+    }
+
+    /// Applies the matrix to a point.
+    pub fn transform_point(&self, point: [f32; 3]) -> [f32; 3] {
+        let mut result = self.translation();
+        for (row, value) in result.iter_mut().enumerate() {
+            *value += (0..3)
+                .map(|column| self.0[column * 4 + row] * point[column])
+                .sum::<f32>();
+        }
+        result
+    }
+}
+
+impl Mul for Matrix {
+    type Output = Self;
+
+    /// Composes `rhs` followed by `self`.
+    fn mul(self, rhs: Self) -> Self {
+        let mut result = [0.; 16];
+        for column in 0..4 {
+            for row in 0..4 {
+                result[column * 4 + row] = (0..4)
+                    .map(|i| self.0[i * 4 + row] * rhs.0[column * 4 + i])
+                    .sum();
+            }
+        }
+        Self(result)
+    }
+}
@@ -1,14 +1,36 @@
-use std::io::Read;
+mod matrix;
 
+use std::io::{Read, Write};
+
+pub use self::matrix::Matrix;
+
+/// Position, orientation and scale/shear of an object.
+///
+/// `flags` tells which of the components are set, the others must be
+/// treated as identity.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transform {
-    flags: u32,
-    translation: [f32; 3],
-    rotation: [f32; 4],
-    scale_shear: [f32; 9],
+    pub flags: u32,
+    pub translation: [f32; 3],
+    /// Quaternion as `[x, y, z, w]`
+    pub rotation: [f32; 4],
+    /// 3x3 matrix in row major order
+    pub scale_shear: [f32; 9],
 }
 
 impl Transform {
+    pub const HAS_POSITION: u32 = 0x1;
+    pub const HAS_ORIENTATION: u32 = 0x2;
+    pub const HAS_SCALE_SHEAR: u32 = 0x4;
+
+    pub const IDENTITY: Self = Self {
+        flags: 0,
+        translation: [0.; 3],
+        rotation: [0., 0., 0., 1.],
+        scale_shear: [1., 0., 0., 0., 1., 0., 0., 0., 1.],
+    };
+
     pub fn parse<T: Read>(reader: &mut T) -> Result<Self, std::io::Error> {
         let flags = {
             let mut buffer = [0; 4];
@@ -67,4 +89,68 @@ impl Transform {
             scale_shear,
         })
     }
+
+    pub fn write<T: Write>(&self, writer: &mut T) -> Result<(), std::io::Error> {
+        writer.write_all(&self.flags.to_le_bytes())?;
+        for value in self
+            .translation
+            .iter()
+            .chain(self.rotation.iter())
+            .chain(self.scale_shear.iter())
+        {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Matrix applying scale/shear, then orientation, then position.
+    pub fn matrix(&self) -> Matrix {
+        let mut linear = [1., 0., 0., 0., 1., 0., 0., 0., 1.];
+        if self.flags & Self::HAS_SCALE_SHEAR != 0 {
+            linear = self.scale_shear;
+        }
+        if self.flags & Self::HAS_ORIENTATION != 0 {
+            linear = Self::multiply_3x3(&Self::rotation_3x3(&self.rotation), &linear);
+        }
+        let translation = if self.flags & Self::HAS_POSITION != 0 {
+            self.translation
+        } else {
+            [0.; 3]
+        };
+
+        let mut matrix = Matrix::IDENTITY;
+        for row in 0..3 {
+            for column in 0..3 {
+                matrix.0[column * 4 + row] = linear[row * 3 + column];
+            }
+            matrix.0[12 + row] = translation[row];
+        }
+        matrix
+    }
+
+    fn rotation_3x3(rotation: &[f32; 4]) -> [f32; 9] {
+        let [x, y, z, w] = *rotation;
+        [
+            1. - 2. * (y * y + z * z),
+            2. * (x * y - z * w),
+            2. * (x * z + y * w),
+            2. * (x * y + z * w),
+            1. - 2. * (x * x + z * z),
+            2. * (y * z - x * w),
+            2. * (x * z - y * w),
+            2. * (y * z + x * w),
+            1. - 2. * (x * x + y * y),
+        ]
+    }
+
+    fn multiply_3x3(lhs: &[f32; 9], rhs: &[f32; 9]) -> [f32; 9] {
+        let mut result = [0.; 9];
+        for row in 0..3 {
+            for column in 0..3 {
+                result[row * 3 + column] =
+                    (0..3).map(|i| lhs[row * 3 + i] * rhs[i * 3 + column]).sum();
+            }
+        }
+        result
+    }
 }
@@ -0,0 +1,608 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use super::{
+    element::{f32_to_f16, Data, Element, Info, TypeDefinition, TypeId},
+    format::Format,
+    section::Relocation,
+};
+
+/// Member of a type definition, used to share identical type definitions.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct MemberKey {
+    element_type: TypeId,
+    name: Box<str>,
+    array_size: usize,
+    extra: [u8; 12],
+    extra_ptr: usize,
+    children: Option<usize>,
+}
+
+/// Type definition written by the [`Writer`], compared with the
+/// `extra_ptr` of its members and of the types they reference, which
+/// [`TypeDefinition`] equality leaves out.
+#[derive(Debug)]
+struct DefinitionKey(Arc<TypeDefinition>);
+
+impl DefinitionKey {
+    fn same(definition: &TypeDefinition, other: &TypeDefinition) -> bool {
+        definition.members.len() == other.members.len()
+            && definition
+                .members
+                .iter()
+                .zip(&other.members)
+                .all(|(member, other)| {
+                    member == other
+                        && member.extra_ptr == other.extra_ptr
+                        && match (&member.definition, &other.definition) {
+                            (Some(definition), Some(other)) => Self::same(definition, other),
+                            _ => true,
+                        }
+                })
+    }
+}
+
+impl PartialEq for DefinitionKey {
+    fn eq(&self, other: &Self) -> bool {
+        Self::same(&self.0, &other.0)
+    }
+}
+
+impl Eq for DefinitionKey {}
+
+impl Hash for DefinitionKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Keys that are the same are equal definitions too
+        self.0.hash(state);
+    }
+}
+
+/// Lays out type definitions and objects of an [`Element`] tree into the
+/// data of a single section.
+///
+/// Every pointer written gets a [`Relocation`] into the same section.
+/// The section starts with an empty type definition so that no object
+/// lives at offset `0`, which would read back as a null pointer.
+///
+/// References with the same [`Element::object`] are written as a single
+/// object.
+pub struct Writer {
+    format: Format,
+    data: Vec<u8>,
+    relocations: Vec<Relocation>,
+    strings: HashMap<Box<str>, usize>,
+    types: HashMap<Vec<MemberKey>, usize>,
+    /// Offsets of types written from a definition, definitions equal down
+    /// to their `extra_ptr` sharing one even when they weren't parsed from
+    /// the same position.
+    definitions: HashMap<DefinitionKey, usize>,
+    /// Offsets of referenced objects already written, by their identifier.
+    objects: HashMap<usize, usize>,
+}
+
+impl Writer {
+    const ALIGNMENT: usize = 4;
+
+    pub fn new(format: Format) -> Self {
+        let mut types = HashMap::new();
+        types.insert(vec![], 0);
+        Self {
+            format,
+            data: vec![0; Info::sizeof(format)],
+            relocations: vec![],
+            strings: HashMap::new(),
+            types,
+            definitions: HashMap::new(),
+            objects: HashMap::new(),
+        }
+    }
+
+    /// Writes the root object, returning the offsets of its type
+    /// definition and of the object.
+    pub fn write_root(&mut self, root: &[Element]) -> Result<(usize, usize), WriterError> {
+        let type_pos = self.write_type(&[root], None)?;
+        let object_pos = self.allocate(self.struct_size(root)?, Self::ALIGNMENT);
+        self.write_struct(root, object_pos)?;
+        Ok((type_pos, object_pos))
+    }
+
+    /// Returns the section data and its relocations.
+    pub fn finish(self) -> (Vec<u8>, Vec<Relocation>) {
+        (self.data, self.relocations)
+    }
+
+    /// Writes the type definition shared by `instances`, the members of
+    /// objects of the same type.
+    ///
+    /// Types parsed from a file are written from their `definition`, which
+    /// is known even for empty arrays and null references, as long as it
+    /// still describes the instances. Otherwise the type is taken from
+    /// every instance, so a reference that is null on the first instance
+    /// still gets its type from the others.
+    fn write_type(
+        &mut self,
+        instances: &[&[Element]],
+        definition: Option<&Arc<TypeDefinition>>,
+    ) -> Result<usize, WriterError> {
+        if let Some(definition) =
+            definition.filter(|definition| Self::describes(definition, instances))
+        {
+            return self.write_definition(definition, instances);
+        }
+        let Some(template) = instances.first() else {
+            return Ok(0);
+        };
+
+        let mut key = Vec::with_capacity(template.len());
+        for (i, member) in template.iter().enumerate() {
+            let members = instances
+                .iter()
+                .filter_map(|instance| instance.get(i))
+                .collect::<Vec<_>>();
+            let definition = members.iter().find_map(|member| member.definition.as_ref());
+            key.push(MemberKey {
+                element_type: member.info.element_type,
+                name: member.name.clone(),
+                array_size: Self::stored_array_size(
+                    member.info.element_type,
+                    member.info.array_size,
+                ),
+                extra: member.info.extra,
+                extra_ptr: member.info.extra_ptr,
+                children: self.write_children(member.info.element_type, &members, definition)?,
+            });
+        }
+
+        if let Some(type_pos) = self.types.get(&key) {
+            return Ok(*type_pos);
+        }
+        let info_size = Info::sizeof(self.format);
+        let type_pos = self.allocate(info_size * (key.len() + 1), Self::ALIGNMENT);
+        for (i, member) in key.iter().enumerate() {
+            self.write_member(type_pos + i * info_size, member)?;
+        }
+        self.types.insert(key, type_pos);
+        Ok(type_pos)
+    }
+
+    /// Writes a type definition parsed from a file, once per definition.
+    ///
+    /// Its space is reserved before the types it references are written,
+    /// so types referencing themselves point back to it.
+    fn write_definition(
+        &mut self,
+        definition: &Arc<TypeDefinition>,
+        instances: &[&[Element]],
+    ) -> Result<usize, WriterError> {
+        let key = DefinitionKey(definition.clone());
+        if let Some(type_pos) = self.definitions.get(&key) {
+            return Ok(*type_pos);
+        }
+
+        let info_size = Info::sizeof(self.format);
+        let type_pos = self.allocate(info_size * (definition.members.len() + 1), Self::ALIGNMENT);
+        self.definitions.insert(key, type_pos);
+
+        for (i, member) in definition.members.iter().enumerate() {
+            let members = instances
+                .iter()
+                .filter_map(|instance| instance.get(i))
+                .collect::<Vec<_>>();
+            // A member referencing a type it is part of has no definition,
+            // but its elements know which one it is
+            let nested = member
+                .definition
+                .as_ref()
+                .or_else(|| members.iter().find_map(|member| member.definition.as_ref()));
+            let key = MemberKey {
+                element_type: member.member_type,
+                name: member.name.clone(),
+                array_size: Self::stored_array_size(member.member_type, member.array_size),
+                extra: member.extra,
+                extra_ptr: member.extra_ptr,
+                children: self.write_children(member.member_type, &members, nested)?,
+            };
+            self.write_member(type_pos + i * info_size, &key)?;
+        }
+
+        Ok(type_pos)
+    }
+
+    /// Writes the type of the objects referenced or inlined by `members`,
+    /// elements of the same member on different objects.
+    fn write_children(
+        &mut self,
+        element_type: TypeId,
+        members: &[&Element],
+        definition: Option<&Arc<TypeDefinition>>,
+    ) -> Result<Option<usize>, WriterError> {
+        let instances = match element_type {
+            TypeId::Inline => members
+                .iter()
+                .flat_map(|member| Self::inline_instances(member))
+                .collect::<Vec<_>>(),
+            TypeId::Reference | TypeId::EmptyReference => members
+                .iter()
+                .filter(|member| !matches!(member.data[..], [Data::Reference(0)]))
+                .map(|member| &member.children[..])
+                .collect::<Vec<_>>(),
+            TypeId::ReferenceToArray | TypeId::ArrayOfReferences => members
+                .iter()
+                .flat_map(|member| member.children.iter())
+                .filter(|item| !item.children.is_empty())
+                .map(|item| &item.children[..])
+                .collect::<Vec<_>>(),
+            _ => return Ok(None),
+        };
+        self.write_type(&instances, definition).map(Some)
+    }
+
+    /// Whether every instance has the members of `definition`.
+    fn describes(definition: &TypeDefinition, instances: &[&[Element]]) -> bool {
+        instances.iter().all(|instance| {
+            instance.len() == definition.members.len()
+                && instance
+                    .iter()
+                    .zip(&definition.members)
+                    .all(|(element, member)| {
+                        element.name == member.name
+                            && element.info.element_type == member.member_type
+                            && element.info.array_size == member.array_size
+                    })
+        })
+    }
+
+    /// Writes the member of a type definition at `pos`.
+    fn write_member(&mut self, pos: usize, member: &MemberKey) -> Result<(), WriterError> {
+        let pointer_size = self.format.pointer_size();
+        self.write_u32(pos, u32::from(member.element_type));
+        let name = self.write_string(&member.name)?;
+        self.write_pointer(pos + 4, name);
+        let children = member.children.filter(|children| *children != 0);
+        self.write_pointer(pos + 4 + pointer_size, children);
+        let Ok(array_size) = u32::try_from(member.array_size) else {
+            return Err(WriterError::TooLarge);
+        };
+        self.write_u32(pos + 4 + 2 * pointer_size, array_size);
+        self.data[(pos + 8 + 2 * pointer_size)..(pos + 20 + 2 * pointer_size)]
+            .copy_from_slice(&member.extra);
+        // Stored as read, it is never relocated
+        let extra_ptr = member.extra_ptr.to_le_bytes();
+        if extra_ptr[pointer_size..].iter().any(|byte| *byte != 0) {
+            return Err(WriterError::TooLarge);
+        }
+        self.data[(pos + 20 + 2 * pointer_size)..(pos + 20 + 3 * pointer_size)]
+            .copy_from_slice(&extra_ptr[..pointer_size]);
+        Ok(())
+    }
+
+    /// Writes `members` into the space reserved at `pos`.
+    fn write_struct(&mut self, members: &[Element], pos: usize) -> Result<(), WriterError> {
+        let pointer_size = self.format.pointer_size();
+        let mut pos = pos;
+        for member in members {
+            let size = self.member_size(member)?;
+            match (member.info.element_type, member.data.as_slice()) {
+                (TypeId::Transform, data) => {
+                    let mut buffer = Vec::with_capacity(size);
+                    for data in data {
+                        let Data::Transform(transform) = data else {
+                            return Err(WriterError::DataMismatch(member.name.clone()));
+                        };
+                        transform.write(&mut buffer)?;
+                    }
+                    self.write_bytes(pos, &buffer, member)?;
+                }
+                (TypeId::String, [Data::String(string)]) => {
+                    let target = self.write_string(string)?;
+                    self.write_pointer(pos, target);
+                }
+                (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(0)]) => (),
+                (TypeId::Reference | TypeId::EmptyReference, [Data::Reference(_)]) => {
+                    let target = self.write_object(member)?;
+                    self.write_pointer(pos, Some(target));
+                }
+                (TypeId::ReferenceToArray, [Data::Array(..)]) => {
+                    self.write_count(pos, member.children.len())?;
+                    let target = self.write_array(&member.children)?;
+                    self.write_pointer(pos + 4, target);
+                }
+                (TypeId::ArrayOfReferences, [Data::ArrayOfReferences(references)]) => {
+                    self.write_count(pos, member.children.len())?;
+                    if !member.children.is_empty() {
+                        let table =
+                            self.allocate(pointer_size * member.children.len(), Self::ALIGNMENT);
+                        for (i, (item, reference)) in
+                            member.children.iter().zip(references).enumerate()
+                        {
+                            if *reference != 0 {
+                                let target = self.write_object(item)?;
+                                self.write_pointer(table + i * pointer_size, Some(target));
+                            }
+                        }
+                        self.write_pointer(pos + 4, Some(table));
+                    }
+                }
+                (TypeId::VariantReference, [Data::Variant(0, _)]) => (),
+                (TypeId::VariantReference, [Data::Variant(_, 0)]) => {
+                    // Null variants still keep their type
+                    let variant_type = self.write_type(&[], member.definition.as_ref())?;
+                    self.write_pointer(pos, Some(variant_type).filter(|pos| *pos != 0));
+                }
+                (TypeId::VariantReference, [Data::Variant(..)]) => {
+                    let variant_type =
+                        self.write_type(&[&member.children[..]], member.definition.as_ref())?;
+                    self.write_pointer(pos, Some(variant_type));
+                    let target = self.write_object(member)?;
+                    self.write_pointer(pos + pointer_size, Some(target));
+                }
+                (TypeId::ReferenceToVariantArray, [Data::VariantArray(..)]) => {
+                    let variant_type = self.write_type(
+                        &member
+                            .children
+                            .iter()
+                            .map(|item| &item.children[..])
+                            .collect::<Vec<_>>(),
+                        member.definition.as_ref(),
+                    )?;
+                    self.write_pointer(pos, Some(variant_type).filter(|pos| *pos != 0));
+                    self.write_count(pos + pointer_size, member.children.len())?;
+                    let target = self.write_array(&member.children)?;
+                    self.write_pointer(pos + pointer_size + 4, target);
+                }
+                (TypeId::Inline, _) => {
+                    let mut inline_pos = pos;
+                    for instance in Self::inline_instances(member) {
+                        self.write_struct(instance, inline_pos)?;
+                        inline_pos += self.struct_size(instance)?;
+                    }
+                }
+                (TypeId::None | TypeId::Removed, _) => (),
+                (
+                    TypeId::Int8
+                    | TypeId::UInt8
+                    | TypeId::Int8Norm
+                    | TypeId::UInt8Norm
+                    | TypeId::Int16
+                    | TypeId::UInt16
+                    | TypeId::Int16Norm
+                    | TypeId::UInt16Norm
+                    | TypeId::Real16
+                    | TypeId::Int32
+                    | TypeId::UInt32
+                    | TypeId::Real32,
+                    data,
+                ) => {
+                    let mut buffer = Vec::with_capacity(size);
+                    for data in data {
+                        match data {
+                            Data::Int8(value) | Data::Int8Norm(value) => {
+                                buffer.extend_from_slice(&value.to_le_bytes())
+                            }
+                            Data::UInt8(value) | Data::UInt8Norm(value) => {
+                                buffer.extend_from_slice(&value.to_le_bytes())
+                            }
+                            Data::Int16(value) | Data::Int16Norm(value) => {
+                                buffer.extend_from_slice(&value.to_le_bytes())
+                            }
+                            Data::UInt16(value) | Data::UInt16Norm(value) => {
+                                buffer.extend_from_slice(&value.to_le_bytes())
+                            }
+                            Data::Int32(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+                            Data::UInt32(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+                            Data::Real16(value) => {
+                                buffer.extend_from_slice(&f32_to_f16(*value).to_le_bytes())
+                            }
+                            Data::Real32(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+                            Data::Int8Array(values) | Data::Int8NormArray(values) => values
+                                .iter()
+                                .for_each(|value| buffer.extend_from_slice(&value.to_le_bytes())),
+                            Data::UInt8Array(values) | Data::UInt8NormArray(values) => {
+                                buffer.extend_from_slice(values)
+                            }
+                            Data::Int16Array(values) | Data::Int16NormArray(values) => values
+                                .iter()
+                                .for_each(|value| buffer.extend_from_slice(&value.to_le_bytes())),
+                            Data::UInt16Array(values) | Data::UInt16NormArray(values) => values
+                                .iter()
+                                .for_each(|value| buffer.extend_from_slice(&value.to_le_bytes())),
+                            Data::Int32Array(values) => values
+                                .iter()
+                                .for_each(|value| buffer.extend_from_slice(&value.to_le_bytes())),
+                            Data::UInt32Array(values) => values
+                                .iter()
+                                .for_each(|value| buffer.extend_from_slice(&value.to_le_bytes())),
+                            Data::Real16Array(values) => values.iter().for_each(|value| {
+                                buffer.extend_from_slice(&f32_to_f16(*value).to_le_bytes())
+                            }),
+                            Data::Real32Array(values) => values
+                                .iter()
+                                .for_each(|value| buffer.extend_from_slice(&value.to_le_bytes())),
+                            _ => return Err(WriterError::DataMismatch(member.name.clone())),
+                        }
+                    }
+                    self.write_bytes(pos, &buffer, member)?;
+                }
+                _ => return Err(WriterError::DataMismatch(member.name.clone())),
+            }
+            pos += size;
+        }
+        Ok(())
+    }
+
+    /// Allocates and writes the object referenced by `reference`, unless
+    /// an object with the same identifier was already written.
+    fn write_object(&mut self, reference: &Element) -> Result<usize, WriterError> {
+        if let Some(target) = reference.object.and_then(|id| self.objects.get(&id)) {
+            return Ok(*target);
+        }
+        let target = self.allocate(self.struct_size(&reference.children)?, Self::ALIGNMENT);
+        self.write_struct(&reference.children, target)?;
+        if let Some(id) = reference.object {
+            self.objects.insert(id, target);
+        }
+        Ok(target)
+    }
+
+    /// Allocates and writes contiguous objects, one per item.
+    fn write_array(&mut self, items: &[Element]) -> Result<Option<usize>, WriterError> {
+        let Some(first) = items.first() else {
+            return Ok(None);
+        };
+        let item_size = self.struct_size(&first.children)?;
+        let target = self.allocate(item_size * items.len(), Self::ALIGNMENT);
+        for (i, item) in items.iter().enumerate() {
+            if self.struct_size(&item.children)? != item_size {
+                return Err(WriterError::DataMismatch(item.name.clone()));
+            }
+            self.write_struct(&item.children, target + i * item_size)?;
+        }
+        Ok(Some(target))
+    }
+
+    /// Writes a null terminated string, returning `None` for empty strings.
+    fn write_string(&mut self, string: &str) -> Result<Option<usize>, WriterError> {
+        if string.is_empty() {
+            return Ok(None);
+        }
+        if let Some(pos) = self.strings.get(string) {
+            return Ok(Some(*pos));
+        }
+        let pos = self.allocate(string.len() + 1, 1);
+        self.data[pos..(pos + string.len())].copy_from_slice(string.as_bytes());
+        self.strings.insert(string.into(), pos);
+        Ok(Some(pos))
+    }
+
+    fn write_bytes(
+        &mut self,
+        pos: usize,
+        bytes: &[u8],
+        member: &Element,
+    ) -> Result<(), WriterError> {
+        if bytes.len() != self.member_size(member)? {
+            return Err(WriterError::DataMismatch(member.name.clone()));
+        }
+        self.data[pos..(pos + bytes.len())].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn write_count(&mut self, pos: usize, count: usize) -> Result<(), WriterError> {
+        let Ok(count) = u32::try_from(count) else {
+            return Err(WriterError::TooLarge);
+        };
+        self.write_u32(pos, count);
+        Ok(())
+    }
+
+    fn write_u32(&mut self, pos: usize, value: u32) {
+        self.data[pos..(pos + 4)].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Records a relocation for a pointer at `pos`, leaving null pointers
+    /// as zeros.
+    fn write_pointer(&mut self, pos: usize, target: Option<usize>) {
+        if let Some(target) = target {
+            self.relocations.push(Relocation {
+                src_offset: pos,
+                dst_section: 0,
+                dst_offset: target,
+            });
+        }
+    }
+
+    fn allocate(&mut self, size: usize, alignment: usize) -> usize {
+        let pos = self.data.len().next_multiple_of(alignment);
+        self.data.resize(pos + size, 0);
+        pos
+    }
+
+    fn struct_size(&self, members: &[Element]) -> Result<usize, WriterError> {
+        members.iter().map(|member| self.member_size(member)).sum()
+    }
+
+    fn member_size(&self, member: &Element) -> Result<usize, WriterError> {
+        let pointer_size = self.format.pointer_size();
+        let size = match member.info.element_type {
+            TypeId::Int8 | TypeId::UInt8 | TypeId::Int8Norm | TypeId::UInt8Norm => {
+                member.info.array_size
+            }
+            TypeId::Int16
+            | TypeId::UInt16
+            | TypeId::Int16Norm
+            | TypeId::UInt16Norm
+            | TypeId::Real16 => 2 * member.info.array_size,
+            TypeId::Int32 | TypeId::UInt32 | TypeId::Real32 => 4 * member.info.array_size,
+            TypeId::Transform => 68 * member.info.array_size,
+            TypeId::String | TypeId::Reference | TypeId::EmptyReference => pointer_size,
+            TypeId::ReferenceToArray | TypeId::ArrayOfReferences => 4 + pointer_size,
+            TypeId::VariantReference => 2 * pointer_size,
+            TypeId::ReferenceToVariantArray => 4 + 2 * pointer_size,
+            TypeId::Inline => Self::inline_instances(member)
+                .map(|instance| self.struct_size(instance))
+                .sum::<Result<usize, _>>()?,
+            TypeId::None | TypeId::Removed => 0,
+        };
+        Ok(size)
+    }
+
+    /// Members of each inlined object, arrays of inlined objects having
+    /// one child per object.
+    fn inline_instances(member: &Element) -> impl Iterator<Item = &[Element]> {
+        let items = member.info.array_size > 1;
+        let flat = (!items).then_some(&member.children[..]);
+        flat.into_iter().chain(
+            member
+                .children
+                .iter()
+                .filter(move |_| items)
+                .map(|item| &item.children[..]),
+        )
+    }
+
+    fn stored_array_size(element_type: TypeId, array_size: usize) -> usize {
+        match element_type {
+            TypeId::Reference
+            | TypeId::ReferenceToArray
+            | TypeId::ArrayOfReferences
+            | TypeId::VariantReference
+            | TypeId::ReferenceToVariantArray
+            | TypeId::String
+            | TypeId::EmptyReference => 0,
+            _ => array_size,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WriterError {
+    DataMismatch(Box<str>),
+    TooLarge,
+    Io,
+}
+
+impl Display for WriterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DataMismatch(name) => {
+                write!(f, "Data of {} does not match its type.", name)
+            }
+            Self::TooLarge => write!(f, "Data is too large to be written."),
+            Self::Io => write!(f, "Couldn't write data due to Io error."),
+        }
+    }
+}
+
+impl Error for WriterError {}
+
+impl From<std::io::Error> for WriterError {
+    fn from(value: std::io::Error) -> Self {
+        log::error!("{}", value);
+        Self::Io
+    }
+}
@@ -1,19 +1,461 @@
-use std::io::{Cursor, Read, Seek, SeekFrom};
+#[cfg(feature = "memmap")]
+use std::path::Path;
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    error::Error,
+    fmt::Display,
+    io::{Cursor, Read, Seek, SeekFrom, Write},
+};
 
-use granny2::section::{Section, SectionError};
+#[cfg(feature = "gltf")]
+use granny2::export::gltf::{Gltf, GltfError};
+use granny2::{
+    element::LazyObject,
+    export::obj::ObjError,
+    file_info::FileInfoError,
+    format::{Endianness, Format},
+    reference::Reference,
+    section::{
+        CompressionMode, Marshaller, Marshalling, MarshallingHeader, Relocation, RelocationHeader,
+        Section, SectionData, SectionError,
+    },
+    writer::{Writer, WriterError},
+};
 
 pub mod granny2;
 
 extern crate alloc;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Granny2 {
     pub header: granny2::Header,
     pub sections: Vec<granny2::section::Section>,
     pub root: Vec<granny2::element::Element>,
 }
 
+/// Options controlling how a file is parsed.
+///
+/// The default verifies the checksum, so [`Granny2::parse`] and the other
+/// methods without options fail with [`Granny2Error::ChecksumMismatch`] on
+/// files whose data does not match their header.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Compares the checksum stored on the header against the data.
+    ///
+    /// Turning it off saves a pass over the whole file.
+    pub verify_checksum: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            verify_checksum: true,
+        }
+    }
+}
+
+/// Options controlling how a file is written.
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    /// Compression used for section data. BitKnit is not supported.
+    pub compression_mode: CompressionMode,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            compression_mode: CompressionMode::None,
+        }
+    }
+}
+
 impl Granny2 {
-    pub fn parse<T: Read + Seek>(mut reader: T) -> Result<Self, Granny2Error> {
+    /// Parses a file with the default [`ParseOptions`], verifying its
+    /// checksum.
+    pub fn parse<T: Read + Seek>(reader: T) -> Result<Self, Granny2Error> {
+        Self::parse_with_options(reader, &ParseOptions::default())
+    }
+
+    pub fn parse_with_options<T: Read + Seek>(
+        reader: T,
+        options: &ParseOptions,
+    ) -> Result<Self, Granny2Error> {
+        Self::from_lazy(LazyGranny2::parse_with_options(reader, options)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Granny2Error> {
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
+
+    /// Parses a file already in memory, instead of reading it into a
+    /// buffer first.
+    ///
+    /// Sections stored uncompressed are read in place, see
+    /// [`LazyGranny2::from_bytes_with_options`], but elements own their
+    /// values. [`LazyGranny2`] borrows them instead.
+    pub fn from_bytes_with_options(
+        bytes: &[u8],
+        options: &ParseOptions,
+    ) -> Result<Self, Granny2Error> {
+        Self::from_lazy(LazyGranny2::from_bytes_with_options(bytes, options)?)
+    }
+
+    #[cfg(feature = "memmap")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Granny2Error> {
+        Self::open_with_options(path, &ParseOptions::default())
+    }
+
+    /// Parses the file at `path` through a memory map, instead of reading
+    /// it into a buffer first.
+    #[cfg(feature = "memmap")]
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Self, Granny2Error> {
+        // SAFETY: The map is only read while parsing, the elements owning
+        // their values. Changing the file meanwhile is undefined
+        // behaviour, as with any memory map.
+        let map = unsafe { LazyGranny2::map(path)? };
+        Self::from_lazy(LazyGranny2::from_bytes_with_options(&map, options)?)
+    }
+
+    /// Decodes the element tree of a file.
+    fn from_lazy(file: LazyGranny2) -> Result<Self, Granny2Error> {
+        let root = granny2::element::Element::parse(
+            &mut file.data.reader(),
+            file.types_pos,
+            file.object_pos,
+            file.header.format,
+        )?;
+
+        Ok(Self {
+            header: file.header,
+            sections: file.sections,
+            root,
+        })
+    }
+
+    /// Type definition of the root object.
+    pub fn definition(&self) -> granny2::element::TypeDefinition {
+        granny2::element::TypeDefinition {
+            members: self
+                .root
+                .iter()
+                .map(granny2::element::Element::member_definition)
+                .collect(),
+        }
+    }
+
+    /// First element matching `path` from the root object, such as
+    /// `Meshes/0/PrimaryVertexData/Vertices`.
+    pub fn get(&self, path: &str) -> Option<&granny2::element::Element> {
+        self.query(path).next()
+    }
+
+    /// Elements matching `path` from the root object, which may hold
+    /// wildcards such as `Models/*/Name`.
+    pub fn query(&self, path: &str) -> granny2::element::Query<'_> {
+        granny2::element::Query::new(&self.root, path)
+    }
+
+    /// Typed view of the root object.
+    pub fn file_info(&self) -> Result<granny2::file_info::FileInfo<'_>, FileInfoError> {
+        granny2::file_info::FileInfo::parse(&self.root)
+    }
+
+    /// Writes the meshes of the file as OBJ, and their materials as MTL
+    /// to be saved at `mtl_name`.
+    pub fn write_obj<O: Write, M: Write>(
+        &self,
+        obj: &mut O,
+        mtl: &mut M,
+        mtl_name: &str,
+    ) -> Result<(), ObjError> {
+        granny2::export::obj::write_obj(&self.file_info()?, obj, mtl, mtl_name)
+    }
+
+    /// Converts the models, meshes and animations of the file to glTF.
+    #[cfg(feature = "gltf")]
+    pub fn to_gltf(&self) -> Result<Gltf, GltfError> {
+        Gltf::new(&self.file_info()?)
+    }
+
+    /// Rewrites a file with every section stored uncompressed.
+    ///
+    /// Unlike [`Granny2::write`], the original layout is kept: sections
+    /// hold the same data on the same order, the endianness is not
+    /// changed, and relocations and marshallings are copied as they are.
+    pub fn decompress<R: Read + Seek, W: Write>(reader: R, writer: W) -> Result<(), Granny2Error> {
+        Self::decompress_with_options(reader, writer, &ParseOptions::default())
+    }
+
+    /// Rewrites a file with every section stored uncompressed, verifying
+    /// its checksum first unless `options` turn it off.
+    pub fn decompress_with_options<R: Read + Seek, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        options: &ParseOptions,
+    ) -> Result<(), Granny2Error> {
+        let mut header = granny2::Header::parse(&mut reader)?;
+        let endianness = header.format.endianness;
+
+        reader.rewind()?;
+        let Ok(file_size) = usize::try_from(header.file_size) else {
+            unreachable!("File size must be smaller than usize");
+        };
+        let input_data = {
+            let mut buffer = vec![0; file_size];
+            reader.read_exact(&mut buffer)?;
+            buffer
+        };
+        if options.verify_checksum {
+            LazyGranny2::verify_checksum(&header, &input_data)?;
+        }
+        let truncated = |what: &str| {
+            Granny2Error::from(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!("{} would occur after end of file.", what),
+            ))
+        };
+
+        let (Ok(section_offset), Ok(section_count)) = (
+            usize::try_from(header.section_offset),
+            usize::try_from(header.section_count),
+        ) else {
+            unreachable!("Section Offset and Count must be smaller than usize.");
+        };
+        let header_size = 32 + section_offset + section_count * Section::sizeof();
+        let mut sections = input_data
+            .get((32 + section_offset)..header_size)
+            .ok_or_else(|| truncated("Section"))?
+            .chunks_exact(Section::sizeof())
+            .map(|mut section_raw| Section::parse(&mut section_raw, endianness))
+            .collect::<Result<Vec<_>, _>>()?;
+        let section_data = sections
+            .iter()
+            .map(|section| section.read_data(&mut Cursor::new(&input_data)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let to_u32 = |value: usize| u32::try_from(value).map_err(|_| WriterError::TooLarge);
+        let mut body = Vec::new();
+        for (section, data) in sections.iter_mut().zip(section_data) {
+            section.compression_mode = CompressionMode::None;
+            section.section_offset = to_u32(header_size + body.len())?;
+            section.compressed_size = to_u32(data.len())?;
+            section.decompressed_size = to_u32(data.len())?;
+            section.stop_0 = to_u32(data.len())?;
+            section.stop_1 = to_u32(data.len())?;
+            body.extend_from_slice(&data);
+            body.resize(body.len().next_multiple_of(4), 0);
+        }
+
+        // Tables are copied after all data, keeping their byte order
+        let mut copy_table = |offset: &mut u32,
+                              count: u32,
+                              entry_size: usize,
+                              what: &str|
+         -> Result<(), Granny2Error> {
+            let (Ok(start), Ok(count)) = (usize::try_from(*offset), usize::try_from(count)) else {
+                unreachable!("Table position must be smaller than usize.");
+            };
+            let entries = input_data
+                .get(start..(start + count * entry_size))
+                .ok_or_else(|| truncated(what))?;
+            *offset = to_u32(header_size + body.len())?;
+            body.extend_from_slice(entries);
+            Ok(())
+        };
+        for section in &mut sections {
+            copy_table(
+                &mut section.relocation_header.offset,
+                section.relocation_header.count,
+                Relocation::sizeof(),
+                "Relocation",
+            )?;
+            copy_table(
+                &mut section.marshalling_header.offset,
+                section.marshalling_header.count,
+                Marshalling::sizeof(),
+                "Marshalling",
+            )?;
+        }
+
+        header.header_size = to_u32(header_size)?;
+        header.file_size = to_u32(header_size + body.len())?;
+        header.checksum = granny2::checksum::crc32(&body);
+
+        header.write(&mut writer)?;
+        for section in &sections {
+            section.write(&mut writer, endianness)?;
+        }
+        writer.write_all(&body)?;
+
+        Ok(())
+    }
+
+    pub fn write<T: Write>(&self, writer: T) -> Result<(), Granny2Error> {
+        self.write_with_options(writer, &WriteOptions::default())
+    }
+
+    /// Writes the element tree as a little endian file.
+    ///
+    /// All data is laid out in the first section, keeping the pointer width,
+    /// version and user data of the header. Any other section is written
+    /// empty so that the section count is preserved.
+    pub fn write_with_options<T: Write>(
+        &self,
+        mut writer: T,
+        options: &WriteOptions,
+    ) -> Result<(), Granny2Error> {
+        let format = Format {
+            endianness: Endianness::Little,
+            pointer_width: self.header.format.pointer_width,
+        };
+        let magic = if Format::from_magic(&self.header.magic) == Some(format) {
+            self.header.magic
+        } else {
+            format.magic()
+        };
+
+        let mut layout = Writer::new(format);
+        let (type_pos, object_pos) = layout.write_root(&self.root)?;
+        let (data, relocations) = layout.finish();
+
+        let to_u32 = |value: usize| u32::try_from(value).map_err(|_| WriterError::TooLarge);
+        let alignment_size = self
+            .sections
+            .first()
+            .map_or(4, |section| section.alignment_size);
+        let empty_section = |compression_mode| Section {
+            compression_mode,
+            section_offset: 0,
+            compressed_size: 0,
+            decompressed_size: 0,
+            alignment_size,
+            stop_0: 0,
+            stop_1: 0,
+            relocation_header: RelocationHeader {
+                offset: 0,
+                count: 0,
+            },
+            marshalling_header: MarshallingHeader {
+                offset: 0,
+                count: 0,
+            },
+        };
+
+        // Everything is compressed as a single block
+        let mut sections = vec![Section {
+            decompressed_size: to_u32(data.len())?,
+            stop_0: to_u32(data.len())?,
+            stop_1: to_u32(data.len())?,
+            ..empty_section(options.compression_mode)
+        }];
+        let section_data = sections[0].write_data(&data)?;
+        sections.extend((1..self.sections.len()).map(|_| empty_section(CompressionMode::None)));
+
+        let section_offset = 40 + self.header.user_data.len();
+        let header_size = 32 + section_offset + sections.len() * Section::sizeof();
+        let data_end = header_size + section_data.len();
+        let relocations_end = data_end + relocations.len() * Relocation::sizeof();
+
+        for (section_id, section) in sections.iter_mut().enumerate() {
+            if section_id == 0 {
+                section.section_offset = to_u32(header_size)?;
+                section.compressed_size = to_u32(section_data.len())?;
+                section.relocation_header.count = to_u32(relocations.len())?;
+            } else {
+                section.section_offset = to_u32(data_end)?;
+            }
+            section.relocation_header.offset = to_u32(data_end)?;
+            section.marshalling_header.offset = to_u32(relocations_end)?;
+        }
+
+        let mut body = Vec::with_capacity(relocations_end - header_size);
+        body.extend_from_slice(&section_data);
+        for relocation in &relocations {
+            relocation.write(&mut body, format.endianness)?;
+        }
+
+        let header = granny2::Header {
+            magic,
+            format,
+            header_size: to_u32(header_size)?,
+            compression_type: self.header.compression_type,
+            extra_bytes: self.header.extra_bytes,
+            version: self.header.version,
+            file_size: to_u32(relocations_end)?,
+            checksum: granny2::checksum::crc32(&body),
+            section_offset: to_u32(section_offset)?,
+            section_count: to_u32(sections.len())?,
+            root_node_type: Reference {
+                section: 0,
+                offset: type_pos,
+            },
+            root_node_object: Reference {
+                section: 0,
+                offset: object_pos,
+            },
+            user_tag: self.header.user_tag,
+            user_data: self.header.user_data.clone(),
+        };
+
+        header.write(&mut writer)?;
+        for section in &sections {
+            section.write(&mut writer, format.endianness)?;
+        }
+        writer.write_all(&body)?;
+
+        Ok(())
+    }
+}
+
+/// File whose elements are decoded only when accessed, keeping the
+/// section data instead of an [`Element`] tree.
+///
+/// Parsed from bytes, sections that need no decompression, relocation nor
+/// marshalling are borrowed from them, and so are the strings and aligned
+/// values read from those sections.
+///
+/// [`Element`]: granny2::element::Element
+pub struct LazyGranny2<'a> {
+    pub header: granny2::Header,
+    pub sections: Vec<granny2::section::Section>,
+    data: SectionData<'a>,
+    types_pos: u64,
+    object_pos: u64,
+}
+
+impl LazyGranny2<'static> {
+    /// Compares the checksum on `header` against `input_data`, the whole
+    /// file, of which it covers everything after the headers.
+    fn verify_checksum(header: &granny2::Header, input_data: &[u8]) -> Result<(), Granny2Error> {
+        let Ok(header_size) = usize::try_from(header.header_size) else {
+            unreachable!("Header size must be smaller than usize.");
+        };
+        let Some(checked) = input_data.get(header_size..) else {
+            return Err(Granny2Error::from(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Header size is larger than the file.",
+            )));
+        };
+        let checksum = granny2::checksum::crc32(checked);
+        if checksum != header.checksum {
+            return Err(Granny2Error::ChecksumMismatch(header.checksum, checksum));
+        }
+        Ok(())
+    }
+
+    pub fn parse<T: Read + Seek>(reader: T) -> Result<Self, Granny2Error> {
+        Self::parse_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Parses a file read into a buffer, which nothing borrows afterwards.
+    pub fn parse_with_options<T: Read + Seek>(
+        mut reader: T,
+        options: &ParseOptions,
+    ) -> Result<Self, Granny2Error> {
         // Reads header
         let header = granny2::Header::parse(&mut reader)?;
 
@@ -42,29 +484,87 @@ impl Granny2 {
             buffer
         };
 
+        LazyGranny2::from_bytes_with_options(&input_data, options).map(LazyGranny2::into_owned)
+    }
+
+    /// Maps the file at `path` into memory, for
+    /// [`LazyGranny2::from_bytes`] to borrow its sections from the map
+    /// instead of reading the file.
+    ///
+    /// # Safety
+    ///
+    /// The file must not change while it is mapped, as with any memory
+    /// map.
+    #[cfg(feature = "memmap")]
+    pub unsafe fn map<P: AsRef<Path>>(path: P) -> Result<memmap2::Mmap, Granny2Error> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: Left to the caller.
+        Ok(unsafe { memmap2::Mmap::map(&file)? })
+    }
+}
+
+impl<'a> LazyGranny2<'a> {
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Granny2Error> {
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
+
+    /// Parses a file already in memory, borrowing the sections stored
+    /// uncompressed in `bytes`.
+    ///
+    /// Sections are only copied to be decompressed, to apply relocations
+    /// to their pointers, or to be marshalled when big endian, which
+    /// copies them all.
+    pub fn from_bytes_with_options(
+        bytes: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<Self, Granny2Error> {
+        let header = granny2::Header::parse(&mut &bytes[..])?;
+        let format = header.format;
+        let big_endian = format.endianness == Endianness::Big;
+
+        let Ok(file_size) = usize::try_from(header.file_size) else {
+            unreachable!("File size must be smaller than usize");
+        };
+        let Some(input_data) = bytes.get(..file_size) else {
+            return Err(Granny2Error::from(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "File is smaller than the size on its header.",
+            )));
+        };
+
+        if options.verify_checksum {
+            LazyGranny2::verify_checksum(&header, input_data)?;
+        }
+
         // Reads all sections infos
         let Ok(section_count) = usize::try_from(header.section_count) else {
             unreachable!("Section Count must be smaller than usize.");
         };
         let sections = (0..section_count)
             .map(|section_id| -> Result<Section, SectionError> {
-                let Ok(section_start) = usize::try_from(header.section_offset).map(|offset| {
-                    32 + offset + section_id * size_of::<granny2::section::Section>()
-                }) else {
+                let Ok(section_start) = usize::try_from(header.section_offset)
+                    .map(|offset| 32 + offset + section_id * Section::sizeof())
+                else {
                     unreachable!("Section Start must be smaller than usize.");
                 };
 
                 // This does not Seek internally, so we can pass just the
                 // relevant data
-                let mut section_raw = Cursor::new(&input_data[section_start..(section_start + 44)]);
-                let section = granny2::section::Section::parse(&mut section_raw)?;
+                let Some(section_raw) = input_data.get(section_start..(section_start + 44)) else {
+                    return Err(SectionError::from(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "Section info would occur after end of file.",
+                    )));
+                };
+                let mut section_raw = Cursor::new(section_raw);
+                let section =
+                    granny2::section::Section::parse(&mut section_raw, format.endianness)?;
                 assert_eq!(section_raw.stream_position()?, 44);
 
                 Ok(section)
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Prepares buffer to hold decompressed data
         let Ok(decompressed_sizes) = sections
             .iter()
             .map(|section| usize::try_from(section.decompressed_size))
@@ -72,8 +572,6 @@ impl Granny2 {
         else {
             unreachable!("Total Decompressed Size must be smaller than usize.");
         };
-        let mut decompressed_data = vec![0u8; decompressed_sizes.iter().sum()];
-
         let section_offsets = decompressed_sizes
             .iter()
             .scan(0, |accum, decompressed_size| {
@@ -83,39 +581,52 @@ impl Granny2 {
             })
             .collect::<Vec<_>>();
 
-        // Read decompressed data
-        for (section, (decompressed_size, offset)) in sections.iter().zip(
-            decompressed_sizes
-                .iter()
-                .zip(section_offsets.iter().copied()),
-        ) {
-            // This Seek internally, so we pass the entire data
-            let mut section_data = section.read_data(&mut Cursor::new(&input_data))?;
+        // Sections stay borrowed from the file until something is written
+        // to them
+        let mut section_data = Vec::with_capacity(sections.len());
+        let mut relocations = vec![];
+        let mut marshallings = vec![];
 
-            decompressed_data[offset..(offset + decompressed_size)]
-                .swap_with_slice(&mut section_data);
+        // Read decompressed data
+        for (section_id, (section, (decompressed_size, offset))) in sections
+            .iter()
+            .zip(
+                decompressed_sizes
+                    .iter()
+                    .zip(section_offsets.iter().copied()),
+            )
+            .enumerate()
+        {
+            let data = section.data(input_data)?;
+            if data.len() != *decompressed_size {
+                return Err(invalid_data(
+                    "Section data does not match its decompressed size.",
+                ));
+            }
+            section_data.push(data);
 
-            #[cfg(target_endian = "big")]
             for marshalling in 0..section.marshalling_header.count {
                 let Ok(pos) = usize::try_from(section.marshalling_header.offset)
                     .and_then(|offset| {
                         usize::try_from(marshalling).map(|marshalling| (offset, marshalling))
                     })
-                    .map(|(offset, marshalling)| {
-                        offset + marshalling * size_of::<granny2::section::Relocation>()
-                    })
+                    .map(|(offset, marshalling)| offset + marshalling * Marshalling::sizeof())
                 else {
                     unreachable!("Marshalling position must be smaller than usize");
                 };
 
-                if pos > input_data.len() {
+                if pos + Marshalling::sizeof() > input_data.len() {
                     return Err(Granny2Error::from(std::io::Error::new(
                         std::io::ErrorKind::UnexpectedEof,
                         "Marshalling would occur after end of file.",
                     )));
                 }
 
-                compile_error!("Big endian systems not supported");
+                let marshalling = Marshalling::parse(
+                    &mut &input_data[pos..(pos + Marshalling::sizeof())],
+                    format.endianness,
+                )?;
+                marshallings.push((offset, marshalling));
             }
 
             for rellocation in 0..section.relocation_header.count {
@@ -130,7 +641,7 @@ impl Granny2 {
                     unreachable!("Rellocation position must be smaller than usize");
                 };
 
-                if pos > input_data.len() {
+                if pos + granny2::section::Relocation::sizeof() > input_data.len() {
                     return Err(Granny2Error::from(std::io::Error::new(
                         std::io::ErrorKind::UnexpectedEof,
                         "Relocation would occur after end of file.",
@@ -139,40 +650,152 @@ impl Granny2 {
 
                 let rellocation = granny2::section::Relocation::parse(
                     &mut &input_data[pos..(pos + granny2::section::Relocation::sizeof())],
+                    format.endianness,
                 )?;
 
-                let virtual_src = offset + rellocation.src_offset;
-                let virtual_dst = section_offsets[rellocation.dst_section] + rellocation.dst_offset;
-
-                decompressed_data[virtual_src..(virtual_src + 4)]
-                    .swap_with_slice(&mut virtual_dst.to_le_bytes()[0..4]);
+                if rellocation
+                    .src_offset
+                    .checked_add(format.pointer_size())
+                    .filter(|end| *end <= *decompressed_size)
+                    .is_none()
+                {
+                    return Err(invalid_data("Relocation source is outside of its section."));
+                }
+                let Ok(virtual_dst) = u64::try_from(virtual_position(
+                    &section_offsets,
+                    rellocation.dst_section,
+                    rellocation.dst_offset,
+                )?) else {
+                    unreachable!("Relocation destination must be smaller than u64.");
+                };
+                relocations.push((section_id, rellocation.src_offset, virtual_dst));
             }
         }
 
-        let Ok(type_section) = u64::try_from(
-            section_offsets[header.root_node_type.section] + header.root_node_type.offset,
-        ) else {
+        let Ok(type_section) = u64::try_from(virtual_position(
+            &section_offsets,
+            header.root_node_type.section,
+            header.root_node_type.offset,
+        )?) else {
             unreachable!("Type Section must be smaller than u64.");
         };
 
-        let Ok(object_section) = u64::try_from(
-            section_offsets[header.root_node_object.section] + header.root_node_object.offset,
-        ) else {
+        let Ok(object_section) = u64::try_from(virtual_position(
+            &section_offsets,
+            header.root_node_object.section,
+            header.root_node_object.offset,
+        )?) else {
             unreachable!("Type Section must be smaller than u64.");
         };
 
-        let root = granny2::element::Element::parse(
-            &mut Cursor::new(decompressed_data),
-            type_section,
-            object_section,
-        )?;
+        let pointer_size = format.pointer_size();
+        if big_endian {
+            // Big endian data is swapped in place, so the original bytes are
+            // kept around to restore anything that is not 32 bits wide
+            let original_data = section_data.concat();
+            let mut decompressed_data = original_data.clone();
+            for (offset, decompressed_size) in section_offsets.iter().zip(&decompressed_sizes) {
+                Marshaller::reverse_32(
+                    &mut decompressed_data[*offset..(offset + decompressed_size)],
+                );
+            }
+
+            let mut relocated = HashSet::new();
+            for (section_id, src_offset, virtual_dst) in relocations {
+                let virtual_src = section_offsets[section_id] + src_offset;
+                decompressed_data[virtual_src..(virtual_src + pointer_size)]
+                    .copy_from_slice(&virtual_dst.to_le_bytes()[..pointer_size]);
+                relocated.insert(virtual_src);
+            }
+
+            let mut marshaller =
+                Marshaller::new(&mut decompressed_data, &original_data, &relocated, format);
+            marshaller.marshall(type_section, object_section, 1)?;
+            for (offset, marshalling) in marshallings {
+                let Ok(types_pos) = u64::try_from(virtual_position(
+                    &section_offsets,
+                    marshalling.dst_section,
+                    marshalling.dst_offset,
+                )?) else {
+                    unreachable!("Marshalling type must be smaller than u64.");
+                };
+                let Ok(object_pos) = u64::try_from(offset + marshalling.src_offset) else {
+                    unreachable!("Marshalling object must be smaller than u64.");
+                };
+                marshaller.marshall(types_pos, object_pos, marshalling.count)?;
+            }
+
+            section_data = section_offsets
+                .iter()
+                .zip(&decompressed_sizes)
+                .map(|(offset, decompressed_size)| {
+                    Cow::Owned(decompressed_data[*offset..(offset + decompressed_size)].to_vec())
+                })
+                .collect();
+        } else {
+            for (section_id, src_offset, virtual_dst) in relocations {
+                // Copies the section when the first of its pointers is
+                // relocated
+                section_data[section_id].to_mut()[src_offset..(src_offset + pointer_size)]
+                    .copy_from_slice(&virtual_dst.to_le_bytes()[..pointer_size]);
+            }
+        }
 
         Ok(Self {
             header,
             sections,
-            root,
+            data: SectionData::new(section_data),
+            types_pos: type_section,
+            object_pos: object_section,
         })
     }
+
+    /// Section data the elements are read from.
+    pub fn data(&self) -> &SectionData<'a> {
+        &self.data
+    }
+
+    /// Copies the sections borrowed from the file, see
+    /// [`SectionData::into_owned`].
+    pub fn into_owned(self) -> LazyGranny2<'static> {
+        LazyGranny2 {
+            header: self.header,
+            sections: self.sections,
+            data: self.data.into_owned(),
+            types_pos: self.types_pos,
+            object_pos: self.object_pos,
+        }
+    }
+
+    /// Root object of the file.
+    pub fn root(&self) -> LazyObject<'_> {
+        LazyObject::new(
+            &self.data,
+            self.header.format,
+            self.types_pos,
+            self.object_pos,
+        )
+    }
+}
+
+/// Position of `offset` in section `section` on the buffer holding all
+/// sections back to back.
+fn virtual_position(
+    section_offsets: &[usize],
+    section: usize,
+    offset: usize,
+) -> Result<usize, Granny2Error> {
+    section_offsets
+        .get(section)
+        .and_then(|start| start.checked_add(offset))
+        .ok_or_else(|| invalid_data("Reference points to a section that does not exist."))
+}
+
+fn invalid_data(message: &str) -> Granny2Error {
+    Granny2Error::from(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message,
+    ))
 }
 
 #[derive(Debug)]
@@ -180,9 +803,32 @@ pub enum Granny2Error {
     Header,
     Section,
     Element,
+    Marshalling,
+    Writer,
+    ChecksumMismatch(u32, u32),
     Io,
 }
 
+impl Display for Granny2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Header => write!(f, "Failed to parse header."),
+            Self::Section => write!(f, "Failed to read section."),
+            Self::Element => write!(f, "Failed to parse elements."),
+            Self::Marshalling => write!(f, "Failed to marshall section data."),
+            Self::Writer => write!(f, "Failed to write elements."),
+            Self::ChecksumMismatch(expected, found) => write!(
+                f,
+                "Checksum {:#010x} does not match header checksum {:#010x}.",
+                found, expected
+            ),
+            Self::Io => write!(f, "Failed due to Io error."),
+        }
+    }
+}
+
+impl Error for Granny2Error {}
+
 impl From<granny2::HeaderError> for Granny2Error {
     fn from(value: granny2::HeaderError) -> Self {
         log::error!("{}", value);
@@ -197,6 +843,13 @@ impl From<granny2::section::SectionError> for Granny2Error {
     }
 }
 
+impl From<granny2::section::MarshallingError> for Granny2Error {
+    fn from(value: granny2::section::MarshallingError) -> Self {
+        log::error!("{}", value);
+        Self::Marshalling
+    }
+}
+
 impl From<granny2::element::ElementError> for Granny2Error {
     fn from(value: granny2::element::ElementError) -> Self {
         log::error!("{}", value);
@@ -204,6 +857,13 @@ impl From<granny2::element::ElementError> for Granny2Error {
     }
 }
 
+impl From<WriterError> for Granny2Error {
+    fn from(value: WriterError) -> Self {
+        log::error!("{}", value);
+        Self::Writer
+    }
+}
+
 impl From<std::io::Error> for Granny2Error {
     fn from(value: std::io::Error) -> Self {
         log::error!("{}", value);
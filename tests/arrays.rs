@@ -0,0 +1,107 @@
+mod common;
+
+use common::fixture;
+use granny2::{granny2::element::Data, Granny2, LazyGranny2, ParseOptions};
+
+#[test]
+fn numeric_arrays_keep_items() {
+    let file = Granny2::from_bytes(&fixture("c4.gr2")).unwrap();
+    let vertices = file.get("VertexDatas/0/Vertices").unwrap();
+    let names = vertices
+        .children
+        .iter()
+        .map(|vertex| &*vertex.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["0", "1", "2", "3", "4", "5"]);
+
+    let vertex = file.get("VertexDatas/0/Vertices/2").unwrap();
+    let members = vertex
+        .children
+        .iter()
+        .map(|member| &*member.name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        members,
+        [
+            "Position",
+            "BoneWeights",
+            "BoneIndices",
+            "Normal",
+            "Tangent",
+            "TextureCoordinates0"
+        ]
+    );
+    assert_eq!(
+        vertex.get("Position").unwrap().data,
+        [Data::Real32Array(vec![1., 1., 0.])]
+    );
+    assert_eq!(
+        vertex.get("BoneWeights").unwrap().data,
+        [Data::UInt8NormArray(vec![128, 127, 0, 0])]
+    );
+    assert!(vertex.get("Position").unwrap().children.is_empty());
+}
+
+#[test]
+fn numeric_items_match_lazy_reads() {
+    for (name, path) in [
+        ("c4.gr2", "VertexDatas/0/Vertices"),
+        ("c4.gr2", "TriTopologies/0/Groups"),
+        ("t4be.gr2", "Items"),
+        ("t8le.gr2", "Items"),
+    ] {
+        let bytes = fixture(name);
+        let file = Granny2::from_bytes(&bytes).unwrap();
+        let items = &file.get(path).unwrap().children;
+
+        let lazy = LazyGranny2::from_bytes(&bytes).unwrap();
+        let root = lazy.root();
+        let mut segments = path.split('/');
+        let mut array = root.child(segments.next().unwrap()).unwrap();
+        while let (Some(index), Some(member)) = (segments.next(), segments.next()) {
+            array = array
+                .index(index.parse().unwrap())
+                .and_then(|object| object.child(member))
+                .unwrap();
+        }
+
+        assert_eq!(items.len(), array.len().unwrap(), "{} {}", name, path);
+        for (i, item) in items.iter().enumerate() {
+            let object = array.index(i).unwrap();
+            for member in item.children.iter() {
+                let lazy_member = object.child(&member.name).unwrap();
+                assert_eq!(
+                    member.data,
+                    lazy_member.data().unwrap(),
+                    "{} {}/{}/{}",
+                    name,
+                    path,
+                    i,
+                    member.name
+                );
+                assert_eq!(member.info.element_type, lazy_member.element_type());
+            }
+        }
+    }
+}
+
+#[test]
+fn oversized_numeric_array_fails() {
+    let mut bytes = fixture("t4le.gr2");
+    let read_u32 = |bytes: &[u8], pos: usize| {
+        let Ok(value) = bytes[pos..(pos + 4)].try_into().map(u32::from_le_bytes) else {
+            unreachable!("Slice has 4 bytes.");
+        };
+        usize::try_from(value).unwrap()
+    };
+    // Count of Items, after Name, Count, Child and the three Vals
+    let section_data = read_u32(&bytes, 32 + read_u32(&bytes, 44) + 4);
+    let items = section_data + read_u32(&bytes, 64) + 24;
+    assert_eq!(read_u32(&bytes, items), 2);
+    bytes[items..(items + 4)].copy_from_slice(&0x7fff_ffffu32.to_le_bytes());
+
+    let options = ParseOptions {
+        verify_checksum: false,
+    };
+    assert!(Granny2::from_bytes_with_options(&bytes, &options).is_err());
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use std::borrow::Cow;
+
+use common::{fixture, flatten};
+use granny2::{granny2::element::ElementError, Granny2, LazyGranny2};
+
+/// Whether `data` points into `file`.
+fn borrows<T>(data: &[T], file: &[u8]) -> bool {
+    file.as_ptr_range().contains(&data.as_ptr().cast())
+}
+
+#[test]
+fn uncompressed_sections_are_borrowed() {
+    // Strings and arrays of numbers are on a section without pointers
+    let bytes = fixture("c4s.gr2");
+    let file = LazyGranny2::from_bytes(&bytes).unwrap();
+    let sections = file.data().sections();
+    assert_eq!(sections.len(), 2);
+    // Relocating the pointers of the first section copies it
+    assert!(matches!(sections[0], Cow::Owned(_)));
+    let Cow::Borrowed(second) = &sections[1] else {
+        panic!("Section without pointers must be borrowed.");
+    };
+    let offset = usize::try_from(file.sections[1].section_offset).unwrap();
+    assert_eq!(second.as_ptr(), bytes[offset..].as_ptr());
+
+    // Sections with relocations are copied
+    let bytes = fixture("c4.gr2");
+    let file = LazyGranny2::from_bytes(&bytes).unwrap();
+    assert!(matches!(file.data().sections()[0], Cow::Owned(_)));
+}
+
+#[test]
+fn big_endian_sections_are_copied() {
+    let bytes = fixture("t4be.gr2");
+    let file = LazyGranny2::from_bytes(&bytes).unwrap();
+    assert!(file
+        .data()
+        .sections()
+        .iter()
+        .all(|section| matches!(section, Cow::Owned(_))));
+}
+
+#[test]
+fn strings_and_values_are_borrowed() {
+    let bytes = fixture("c4s.gr2");
+    let file = LazyGranny2::from_bytes(&bytes).unwrap();
+    let root = file.root();
+
+    let name = root.child("FromFileName").unwrap().string().unwrap();
+    assert_eq!(name, "C:/art/body.max");
+    assert!(matches!(&name, Cow::Borrowed(name) if borrows(name.as_bytes(), &bytes)));
+
+    // An array of objects holding a single number
+    let indices = root
+        .child("TriTopologies")
+        .and_then(|topologies| topologies.index(0))
+        .and_then(|topology| topology.child("Indices16"))
+        .unwrap()
+        .as_u16_slice()
+        .unwrap();
+    assert_eq!(*indices, [0, 1, 2, 0, 2, 3, 3, 2, 5, 3, 5, 4]);
+    assert!(matches!(&indices, Cow::Borrowed(indices) if borrows(indices, &bytes)));
+
+    let vertices = root
+        .child("VertexDatas")
+        .and_then(|datas| datas.index(0))
+        .and_then(|data| data.child("Vertices"))
+        .unwrap();
+    let position = vertices
+        .index(5)
+        .and_then(|vertex| vertex.child("Position"))
+        .unwrap()
+        .as_f32_slice()
+        .unwrap();
+    assert_eq!(*position, [1., 2., 0.]);
+    assert!(matches!(&position, Cow::Borrowed(position) if borrows(position, &bytes)));
+
+    // Vertices mix floats and bytes
+    assert!(matches!(
+        vertices.as_f32_slice(),
+        Err(ElementError::WrongType(_))
+    ));
+    assert!(root.child("FromFileName").unwrap().as_u8_slice().is_err());
+}
+
+#[test]
+fn unaligned_values_are_copied() {
+    let mut bytes = vec![0];
+    bytes.extend(fixture("c4s.gr2"));
+    let file = LazyGranny2::from_bytes(&bytes[1..]).unwrap();
+    let indices = file
+        .root()
+        .child("TriTopologies")
+        .and_then(|topologies| topologies.index(0))
+        .and_then(|topology| topology.child("Indices16"))
+        .unwrap()
+        .as_u16_slice()
+        .unwrap();
+    assert!(matches!(indices, Cow::Owned(_)));
+    assert_eq!(*indices, [0, 1, 2, 0, 2, 3, 3, 2, 5, 3, 5, 4]);
+
+    // Strings need no alignment
+    let name = file.root().child("FromFileName").unwrap().string().unwrap();
+    assert!(matches!(name, Cow::Borrowed("C:/art/body.max")));
+}
+
+#[test]
+fn owned_files_outlive_the_bytes() {
+    let file = LazyGranny2::from_bytes(&fixture("c4s.gr2"))
+        .unwrap()
+        .into_owned();
+    assert!(file
+        .data()
+        .sections()
+        .iter()
+        .all(|section| matches!(section, Cow::Owned(_))));
+    assert_eq!(
+        file.root().child("FromFileName").unwrap().string().unwrap(),
+        "C:/art/body.max"
+    );
+}
+
+#[test]
+fn sections_are_read_as_one() {
+    let split = Granny2::from_bytes(&fixture("c4s.gr2")).unwrap();
+    let single = Granny2::from_bytes(&fixture("c4.gr2")).unwrap();
+    assert_eq!(flatten(&split.root), flatten(&single.root));
+    assert_eq!(
+        split.file_info().unwrap().meshes[0].name,
+        single.file_info().unwrap().meshes[0].name
+    );
+}
+
+#[cfg(feature = "memmap")]
+mod memmap {
+    use std::borrow::Cow;
+
+    use granny2::{Granny2, LazyGranny2};
+
+    use super::{
+        borrows,
+        common::{fixture, fixture_path, flatten},
+    };
+
+    #[test]
+    fn mapped_sections_are_borrowed() {
+        // SAFETY: Fixtures don't change while the tests run.
+        let map = unsafe { LazyGranny2::map(fixture_path("c4s.gr2")) }.unwrap();
+        let file = LazyGranny2::from_bytes(&map).unwrap();
+        assert!(matches!(
+            &file.data().sections()[1],
+            Cow::Borrowed(section) if borrows(section, &map)
+        ));
+        let name = file.root().child("FromFileName").unwrap().string().unwrap();
+        assert!(matches!(&name, Cow::Borrowed(name) if borrows(name.as_bytes(), &map)));
+    }
+
+    #[test]
+    fn open_matches_from_bytes() {
+        for name in ["c4s.gr2", "t8be.gr2"] {
+            let opened = Granny2::open(fixture_path(name)).unwrap();
+            let read = Granny2::from_bytes(&fixture(name)).unwrap();
+            assert_eq!(flatten(&opened.root), flatten(&read.root));
+        }
+        assert!(Granny2::open(fixture_path("missing.gr2")).is_err());
+    }
+}
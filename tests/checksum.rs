@@ -0,0 +1,69 @@
+mod common;
+
+use common::fixture;
+use granny2::{Granny2, Granny2Error, ParseOptions};
+
+/// Fixture with a byte of its root name changed, leaving the header as is.
+fn corrupted() -> Vec<u8> {
+    let mut bytes = fixture("t4le.gr2");
+    let Some(pos) = bytes.windows(5).position(|window| window == b"hello") else {
+        panic!("Fixture must hold the root name.");
+    };
+    bytes[pos] = b'j';
+    bytes
+}
+
+#[test]
+fn corrupted_file_fails_by_default() {
+    assert!(matches!(
+        Granny2::from_bytes(&corrupted()),
+        Err(Granny2Error::ChecksumMismatch(..))
+    ));
+    assert!(matches!(
+        Granny2::parse(std::io::Cursor::new(corrupted())),
+        Err(Granny2Error::ChecksumMismatch(..))
+    ));
+}
+
+#[test]
+fn skipping_verification_parses_corrupted_file() {
+    let options = ParseOptions {
+        verify_checksum: false,
+    };
+    let file = Granny2::from_bytes_with_options(&corrupted(), &options).unwrap();
+    assert_eq!(
+        file.get("Name").unwrap().data,
+        [granny2::granny2::element::Data::String("jello".into())]
+    );
+}
+
+#[test]
+fn intact_file_passes() {
+    assert!(Granny2::from_bytes(&fixture("t4le.gr2")).is_ok());
+}
+
+#[test]
+fn decompress_verifies_by_default() {
+    let mut output = vec![];
+    assert!(matches!(
+        Granny2::decompress(std::io::Cursor::new(corrupted()), &mut output),
+        Err(Granny2Error::ChecksumMismatch(..))
+    ));
+}
+
+#[test]
+fn decompress_can_skip_verification() {
+    let options = ParseOptions {
+        verify_checksum: false,
+    };
+    let mut output = vec![];
+    Granny2::decompress_with_options(std::io::Cursor::new(corrupted()), &mut output, &options)
+        .unwrap();
+
+    // The rewritten file gets a checksum matching its data
+    let file = Granny2::from_bytes(&output).unwrap();
+    assert_eq!(
+        file.get("Name").unwrap().data,
+        [granny2::granny2::element::Data::String("jello".into())]
+    );
+}
@@ -0,0 +1,144 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+fn fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Runs gr2 with `args`, the fixtures being given by name.
+fn gr2(args: &[&str]) -> Output {
+    let args = args.iter().map(|arg| {
+        if arg.ends_with(".gr2") && !Path::new(arg).is_absolute() {
+            fixture_path(arg).into_os_string()
+        } else {
+            arg.into()
+        }
+    });
+    Command::new(env!("CARGO_BIN_EXE_gr2"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Standard output of a successful run.
+fn stdout(args: &[&str]) -> String {
+    let output = gr2(args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Standard error of a failed run.
+fn stderr(args: &[&str]) -> String {
+    let output = gr2(args);
+    assert!(!output.status.success());
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn info_prints_header_and_sections() {
+    let out = stdout(&["info", "t4le.gr2"]);
+    assert!(out.starts_with("Format: little endian, 32 bit pointers\n"));
+    assert!(out.contains("Root object: section 0, offset 0x1c0\n"));
+    assert!(out.contains("  0  None  "));
+
+    let out = stdout(&["info", "t8be.gr2"]);
+    assert!(out.starts_with("Format: big endian, 64 bit pointers\n"));
+}
+
+#[test]
+fn dump_prints_the_tree() {
+    let out = stdout(&["dump", "t4le.gr2"]);
+    assert!(out.starts_with("Name: String = \"hello\"\nCount: Int32 = 7\n"));
+    assert!(out.contains("\n  A: Int16 = -5\n"));
+}
+
+#[test]
+fn dump_limits_depth() {
+    let out = stdout(&["dump", "--depth", "1", "c4.gr2"]);
+    assert!(out.contains("Models: ArrayOfReferences = 1 references ...\n"));
+    assert!(out.lines().all(|line| !line.starts_with(' ')));
+
+    assert_eq!(stdout(&["dump", "--depth", "0", "c4.gr2"]), "");
+    assert!(stderr(&["dump", "--depth", "x", "c4.gr2"]).contains("Invalid depth"));
+}
+
+#[test]
+fn dump_prints_paths() {
+    let out = stdout(&["dump", "--path", "Models/*/Name", "c4.gr2"]);
+    assert_eq!(out, "Models/0/Name: String = \"Body\"\n");
+
+    let out = stdout(&[
+        "dump",
+        "--path",
+        "FromFileName",
+        "--path",
+        "Skeletons/0/Name",
+        "c4.gr2",
+    ]);
+    assert_eq!(
+        out,
+        "FromFileName: String = \"C:/art/body.max\"\nSkeletons/0/Name: String = \"Body\"\n"
+    );
+}
+
+#[test]
+fn dump_fails_without_matches() {
+    let output = gr2(&["dump", "--path", "Nope", "c4.gr2"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: No element matches Nope.\n"
+    );
+
+    // Paths before the missing one are still printed
+    let output = gr2(&["dump", "--path", "FromFileName", "--path", "Nope", "c4.gr2"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "FromFileName: String = \"C:/art/body.max\"\n"
+    );
+}
+
+#[test]
+fn decompress_writes_a_readable_file() {
+    let output = std::env::temp_dir().join(format!("gr2-cli-{}.gr2", std::process::id()));
+    let output_arg = output.to_str().unwrap();
+    stdout(&["decompress", "c4.gr2", output_arg]);
+
+    let info = stdout(&["info", output_arg]);
+    let dump = stdout(&["dump", output_arg]);
+    std::fs::remove_file(&output).unwrap();
+
+    assert!(info.lines().skip(11).all(|line| line.contains("  None  ")));
+    assert_eq!(dump, stdout(&["dump", "c4.gr2"]));
+}
+
+#[test]
+fn schema_diff_prints_changes() {
+    assert_eq!(
+        stdout(&["schema-diff", "t4le.gr2", "t8be.gr2"]),
+        "No changes.\n"
+    );
+
+    let out = stdout(&["schema-diff", "t4le.gr2", "c4.gr2"]);
+    assert!(out.starts_with("(root)\n  - Name: String\n"));
+    assert!(out.contains("\n  + FromFileName: String\n"));
+}
+
+#[test]
+fn reports_bad_arguments() {
+    assert!(stderr(&[]).starts_with("Usage: gr2"));
+    assert!(stderr(&["convert"]).contains("Unknown command convert"));
+    assert!(stderr(&["info", "--verbose", "c4.gr2"]).contains("Unknown option --verbose."));
+    assert!(stderr(&["info"]).contains("Missing file."));
+    assert!(stderr(&["schema-diff", "c4.gr2"]).contains("Expected the two files"));
+    assert!(stderr(&["info", "missing.gr2"]).contains("missing.gr2"));
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use granny2::granny2::element::{Data, Element};
+
+#[allow(dead_code)]
+pub fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+#[allow(dead_code)]
+pub fn fixture(name: &str) -> Vec<u8> {
+    let path = fixture_path(name);
+    std::fs::read(&path).unwrap_or_else(|err| panic!("Couldn't read {}: {}", path.display(), err))
+}
+
+/// One line per element of the tree, with its path, type and data.
+///
+/// Pointers only keep whether they are null, since positions change
+/// between files holding the same tree.
+#[allow(dead_code)]
+pub fn flatten(elements: &[Element]) -> Vec<String> {
+    let mut lines = vec![];
+    flatten_into(elements, "", &mut lines);
+    lines
+}
+
+fn flatten_into(elements: &[Element], path: &str, lines: &mut Vec<String>) {
+    for element in elements {
+        let path = format!("{}/{}", path, element.name);
+        let data = element
+            .data
+            .iter()
+            .map(|data| match data {
+                Data::Array(count, _) => format!("Array({})", count),
+                Data::Reference(pos) => format!("Reference({})", *pos != 0),
+                Data::ArrayOfReferences(pos) => format!(
+                    "ArrayOfReferences({:?})",
+                    pos.iter().map(|pos| *pos != 0).collect::<Vec<_>>()
+                ),
+                Data::Variant(offset, pos) => format!("Variant({}, {})", *offset != 0, *pos != 0),
+                Data::VariantArray(count, offset, _) => {
+                    format!("VariantArray({}, {})", count, *offset != 0)
+                }
+                data => format!("{:?}", data),
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!(
+            "{}: {:?}[{}] {}",
+            path,
+            element.info.element_type,
+            element.info.array_size,
+            data.join(", ")
+        ));
+        flatten_into(&element.children, &path, lines);
+    }
+}
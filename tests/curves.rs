@@ -0,0 +1,272 @@
+mod common;
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use common::fixture;
+use granny2::{
+    granny2::file_info::{Curve, CurveFormat},
+    Granny2,
+};
+
+/// Every curve of curves.gr2, in the order of its generator.
+fn curves() -> Vec<Curve> {
+    let file = Granny2::from_bytes(&fixture("curves.gr2")).unwrap();
+    let curves = file
+        .root
+        .iter()
+        .find(|element| &*element.name == "Curves")
+        .unwrap();
+    curves
+        .children
+        .iter()
+        .map(|item| Curve::parse(&item.children).unwrap())
+        .collect()
+}
+
+fn assert_near(found: &[f32], expected: &[f32]) {
+    assert_eq!(found.len(), expected.len(), "{:?} != {:?}", found, expected);
+    assert!(
+        found
+            .iter()
+            .zip(expected)
+            .all(|(found, expected)| (found - expected).abs() < 1e-4),
+        "{:?} != {:?}",
+        found,
+        expected
+    );
+}
+
+fn assert_curve(
+    curve: &Curve,
+    format: CurveFormat,
+    degree: u8,
+    knots: &[f32],
+    controls: &[&[f32]],
+) {
+    assert_eq!(curve.format, format);
+    assert_eq!(curve.degree, degree);
+    assert_near(&curve.knots, knots);
+    assert_eq!(curve.dimension, controls[0].len());
+    assert_near(&curve.controls, &controls.concat());
+}
+
+const IDENTITY: [f32; 9] = [1., 0., 0., 0., 1., 0., 0., 0., 1.];
+
+fn diagonal(x: f32, y: f32, z: f32) -> [f32; 9] {
+    [x, 0., 0., 0., y, 0., 0., 0., z]
+}
+
+#[test]
+fn every_format_is_covered() {
+    let curves = curves();
+    for format in (0..19).map(|id| CurveFormat::try_from(id).unwrap()) {
+        assert!(
+            curves.iter().any(|curve| curve.format == format),
+            "No {} curve.",
+            format.name()
+        );
+    }
+}
+
+#[test]
+fn decodes_float_curves() {
+    let curves = curves();
+    // Keyframes get one knot per control
+    assert_curve(
+        &curves[0],
+        CurveFormat::DaKeyframes32f,
+        0,
+        &[0., 1., 2.],
+        &[&[1., 2.], &[3., 4.], &[5., 6.]],
+    );
+    // Dimension comes from the number of knots
+    assert_curve(
+        &curves[1],
+        CurveFormat::DaK32fC32f,
+        1,
+        &[0., 0.5],
+        &[&[1., 2., 3.], &[4., 5., 6.]],
+    );
+}
+
+#[test]
+fn decodes_identity_and_constant_curves() {
+    let curves = curves();
+    assert_curve(&curves[2], CurveFormat::DaIdentity, 0, &[0.], &[&[0.; 3]]);
+    assert_curve(
+        &curves[3],
+        CurveFormat::DaIdentity,
+        0,
+        &[0.],
+        &[&[0., 0., 0., 1.]],
+    );
+    assert_curve(&curves[4], CurveFormat::DaIdentity, 0, &[0.], &[&IDENTITY]);
+    assert_curve(
+        &curves[5],
+        CurveFormat::DaConstant32f,
+        0,
+        &[0.],
+        &[&[1., 2.]],
+    );
+    assert_curve(
+        &curves[6],
+        CurveFormat::D3Constant32f,
+        0,
+        &[0.],
+        &[&[1., 2., 3.]],
+    );
+    assert_curve(
+        &curves[7],
+        CurveFormat::D4Constant32f,
+        0,
+        &[0.],
+        &[&[0., 0., 0., 1.]],
+    );
+}
+
+#[test]
+fn decodes_scale_offset_curves() {
+    let curves = curves();
+    // Scales [0.5, 2] and offsets [1, -1] from ControlScaleOffsets
+    let controls: [&[f32]; 3] = [&[2., 5.], &[3., 9.], &[4., 13.]];
+    assert_curve(
+        &curves[8],
+        CurveFormat::DaK16uC16u,
+        2,
+        &[0., 0.5, 1.],
+        &controls,
+    );
+    assert_curve(
+        &curves[9],
+        CurveFormat::DaK8uC8u,
+        2,
+        &[0., 0.5, 1.],
+        &controls,
+    );
+
+    // Scales [0.25, 0.5, 2] and offsets [0, 1, -2]
+    let controls: [&[f32]; 2] = [&[1., 2., 0.], &[3., 1., 4.]];
+    assert_curve(
+        &curves[13],
+        CurveFormat::D3K16uC16u,
+        1,
+        &[0., 1.],
+        &controls,
+    );
+    assert_curve(&curves[14], CurveFormat::D3K8uC8u, 1, &[0., 1.], &controls);
+}
+
+#[test]
+fn decodes_scale_shear_curves() {
+    let curves = curves();
+    // One value on the whole diagonal, scale 0.5 and offset 1
+    let controls: [&[f32]; 2] = [&IDENTITY, &diagonal(2., 2., 2.)];
+    assert_curve(
+        &curves[15],
+        CurveFormat::D9I1K16uC16u,
+        1,
+        &[0., 1.],
+        &controls,
+    );
+    assert_curve(
+        &curves[17],
+        CurveFormat::D9I1K8uC8u,
+        1,
+        &[0., 1.],
+        &controls,
+    );
+
+    // Scales [1, 2, 3] and offsets [0, 0, 1]
+    let controls: [&[f32]; 2] = [&diagonal(1., 2., 4.), &diagonal(2., 4., 7.)];
+    assert_curve(
+        &curves[16],
+        CurveFormat::D9I3K16uC16u,
+        1,
+        &[0., 1.],
+        &controls,
+    );
+    assert_curve(
+        &curves[18],
+        CurveFormat::D9I3K8uC8u,
+        1,
+        &[0., 1.],
+        &controls,
+    );
+}
+
+#[test]
+fn decodes_single_channel_vector_curves() {
+    let curves = curves();
+    // One value scaled per axis, scales [1, 2, 3] and offsets [0, 0, 1]
+    let controls: [&[f32]; 2] = [&[1., 2., 4.], &[2., 4., 7.]];
+    // Knots are stored as is
+    assert_curve(
+        &curves[19],
+        CurveFormat::D3I1K32fC32f,
+        1,
+        &[0., 0.5],
+        &controls,
+    );
+    assert_curve(
+        &curves[20],
+        CurveFormat::D3I1K16uC16u,
+        1,
+        &[0., 1.],
+        &controls,
+    );
+    assert_curve(
+        &curves[21],
+        CurveFormat::D3I1K8uC8u,
+        1,
+        &[0., 1.],
+        &controls,
+    );
+}
+
+#[test]
+fn unpacks_15_bit_quaternions() {
+    let curves = curves();
+    // Entries 0, 8 and 1 give x a range of [-0.71, 0.71], y of [0.71, -0.71]
+    // and z of [-0.35, 0.35], so 16384 is close to 0 for all of them.
+    assert_curve(
+        &curves[10],
+        CurveFormat::D4nK16uC15u,
+        1,
+        &[0., 0.5, 1.],
+        &[
+            // Drops w, x comes from the first value
+            &[FRAC_1_SQRT_2, 0., 0., FRAC_1_SQRT_2],
+            // Drops a negative x, y comes from the first value
+            &[-FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0., 0.],
+            // Drops z, w comes from the first value
+            &[0., 0., FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+        ],
+    );
+
+    // Entries 4, 5, 6 and 7, at the bottom of the range of x and z and the
+    // top of the one of y
+    let x = FRAC_1_SQRT_2 / 4.;
+    let z = -FRAC_1_SQRT_2 / 8.;
+    let w = (1. - x * x - z * z).sqrt();
+    assert_curve(
+        &curves[11],
+        CurveFormat::D4nK16uC15u,
+        0,
+        &[0.],
+        &[&[x, 0., z, w]],
+    );
+}
+
+#[test]
+fn unpacks_7_bit_quaternions() {
+    let curves = curves();
+    // Entry 7 maps [0, 127] to [0, 0.18]
+    let x = FRAC_1_SQRT_2 / 4.;
+    assert_curve(
+        &curves[12],
+        CurveFormat::D4nK8uC7u,
+        1,
+        &[0., 1.],
+        &[&[0., 1., 0., 0.], &[x, 0., 0., -(1. - x * x).sqrt()]],
+    );
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::fixture;
+use granny2::{
+    granny2::{
+        element::TypeId,
+        file_info::{FileInfo, FileInfoError},
+    },
+    Granny2,
+};
+
+fn file() -> Granny2 {
+    Granny2::from_bytes(&fixture("c4.gr2")).unwrap()
+}
+
+#[test]
+fn reads_tool_and_exporter_info() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    assert_eq!(info.from_file_name, "C:/art/body.max");
+
+    let art_tool = info.art_tool_info.unwrap();
+    assert_eq!(art_tool.from_art_tool_name, "Blender");
+    assert_eq!(
+        (
+            art_tool.art_tool_major_revision,
+            art_tool.art_tool_minor_revision
+        ),
+        (4, 1)
+    );
+    assert_eq!(art_tool.units_per_meter, 1.);
+    assert_eq!(art_tool.right_vector, [1., 0., 0.]);
+    assert_eq!(art_tool.up_vector, [0., 1., 0.]);
+    assert_eq!(art_tool.back_vector, [0., 0., 1.]);
+
+    let exporter = info.exporter_info.unwrap();
+    assert_eq!(exporter.exporter_name, "Synthetic");
+    assert_eq!(
+        (
+            exporter.exporter_major_revision,
+            exporter.exporter_minor_revision
+        ),
+        (2, 9)
+    );
+    assert_eq!(exporter.exporter_build_number, 12);
+}
+
+#[test]
+fn reads_every_list() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    assert_eq!(info.textures.len(), 1);
+    assert_eq!(info.materials.len(), 3);
+    assert_eq!(info.skeletons.len(), 1);
+    assert_eq!(info.vertex_datas.len(), 1);
+    assert_eq!(info.tri_topologies.len(), 1);
+    assert_eq!(info.meshes.len(), 1);
+    assert_eq!(info.models.len(), 1);
+    assert_eq!(info.track_groups.len(), 1);
+    assert_eq!(info.animations.len(), 1);
+
+    let texture = &info.textures[0];
+    assert_eq!(texture.from_file_name, "textures/skin.png");
+    assert_eq!((texture.width, texture.height), (256, 128));
+    assert_eq!(texture.encoding, 1);
+
+    let names = info
+        .materials
+        .iter()
+        .map(|material| material.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Skin", "SkinDiffuse", "Cloth"]);
+}
+
+#[test]
+fn resolves_references_to_indices() {
+    let file = file();
+    let info = file.file_info().unwrap();
+
+    let model = &info.models[0];
+    assert_eq!(model.name, "Body");
+    assert_eq!(model.skeleton, Some(0));
+    assert_eq!(model.mesh_bindings, [Some(0)]);
+
+    let mesh = &info.meshes[0];
+    assert_eq!(mesh.primary_vertex_data, Some(0));
+    assert_eq!(mesh.primary_topology, Some(0));
+    assert_eq!(mesh.material_bindings, [Some(0), Some(2)]);
+    assert_eq!(mesh.bone_bindings, ["root", "spine", "head"]);
+
+    let animation = &info.animations[0];
+    assert_eq!(animation.track_groups, [Some(0)]);
+}
+
+#[test]
+fn finds_textures_through_material_maps() {
+    let file = file();
+    let info = file.file_info().unwrap();
+
+    // The texture is on the map's material, not on the material itself
+    let skin = &info.materials[0];
+    assert_eq!(skin.texture, None);
+    assert_eq!(skin.maps.len(), 1);
+    assert_eq!(skin.maps[0].usage, "Diffuse Color");
+    assert_eq!(skin.maps[0].material, Some(1));
+    assert_eq!(info.material_texture(skin), Some(0));
+
+    assert_eq!(info.materials[1].texture, Some(0));
+    assert_eq!(info.material_texture(&info.materials[2]), None);
+}
+
+#[test]
+fn missing_member_is_an_error() {
+    let mut root = file().root;
+    root.retain(|member| &*member.name != "FromFileName");
+    assert!(matches!(
+        FileInfo::parse(&root),
+        Err(FileInfoError::MissingMember(name)) if &*name == "FromFileName"
+    ));
+
+    // Files that aren't laid out as a FileInfo have none of its members
+    let other = Granny2::from_bytes(&fixture("t4le.gr2")).unwrap();
+    assert!(matches!(
+        other.file_info(),
+        Err(FileInfoError::MissingMember(_))
+    ));
+}
+
+#[test]
+fn wrong_member_type_is_an_error() {
+    let mut root = file().root;
+    root.retain(|member| &*member.name != "Textures");
+    let Some(name) = root
+        .iter_mut()
+        .find(|member| &*member.name == "FromFileName")
+    else {
+        panic!("FileInfo must have a file name.");
+    };
+    name.name = "Textures".into();
+    assert!(matches!(
+        FileInfo::parse(&root),
+        Err(FileInfoError::WrongType(name, TypeId::String)) if &*name == "Textures"
+    ));
+}
@@ -0,0 +1,146 @@
+"""Writes c4.gr2.
+
+A file with the members an exporter fills in: art tool and
+exporter info, a textured material, a three bone skeleton, a skinned mesh
+with two material groups, and an animation using every curve format.
+
+With `split`, strings and arrays of numbers such as the vertices go into
+a second section without pointers, as exporters lay out mesh data.
+
+usage: character.py out.gr2 [4|8] [split]
+"""
+import math, struct, sys
+from gr2lib import *
+from curve_types import *
+
+ptr = int(sys.argv[2]) if len(sys.argv) > 2 else 4
+out = sys.argv[1]
+split = sys.argv[3:] == ['split']
+
+Empty = []
+STRING = [(STR, 'String', None, 0)]
+INT32 = [(I32, 'Int32', None, 0)]
+
+ArtToolInfo = [(STR, 'FromArtToolName', None, 0), (I32, 'ArtToolMajorRevision', None, 0), (I32, 'ArtToolMinorRevision', None, 0),
+               (R32, 'UnitsPerMeter', None, 0), (R32, 'Origin', None, 3), (R32, 'RightVector', None, 3), (R32, 'UpVector', None, 3),
+               (R32, 'BackVector', None, 3), (VAR, 'ExtendedData', None, 0)]
+ExporterInfo = [(STR, 'ExporterName', None, 0), (I32, 'ExporterMajorRevision', None, 0), (I32, 'ExporterMinorRevision', None, 0),
+                (I32, 'ExporterCustomization', None, 0), (I32, 'ExporterBuildNumber', None, 0), (VAR, 'ExtendedData', None, 0)]
+PixelLayout = [(I32, 'BytesPerPixel', None, 0), (I32, 'ShiftForComponent', None, 4), (I32, 'BitsForComponent', None, 4)]
+Texture = [(STR, 'FromFileName', None, 0), (I32, 'TextureType', None, 0), (I32, 'Width', None, 0), (I32, 'Height', None, 0),
+           (I32, 'Encoding', None, 0), (I32, 'SubFormat', None, 0), (INLINE, 'Layout', PixelLayout, 0),
+           (REFARR, 'Images', Empty, 0), (VAR, 'ExtendedData', None, 0)]
+Material = []
+MaterialMap = [(STR, 'Usage', None, 0), (REF, 'Material', Material, 0)]
+Material += [(STR, 'Name', None, 0), (REFARR, 'Maps', MaterialMap, 0), (REF, 'Texture', Texture, 0), (VAR, 'ExtendedData', None, 0)]
+Bone = [(STR, 'Name', None, 0), (I32, 'ParentIndex', None, 0), (XF, 'LocalTransform', None, 0), (R32, 'InverseWorld4x4', None, 16),
+        (R32, 'LODError', None, 0), (VAR, 'ExtendedData', None, 0)]
+Skeleton = [(STR, 'Name', None, 0), (REFARR, 'Bones', Bone, 0), (I32, 'LODType', None, 0), (VAR, 'ExtendedData', None, 0)]
+Vertex = [(R32, 'Position', None, 3), (U8N, 'BoneWeights', None, 4), (U8, 'BoneIndices', None, 4), (R32, 'Normal', None, 3),
+          (R32, 'Tangent', None, 3), (R32, 'TextureCoordinates0', None, 2)]
+VertexData = [(VARARR, 'Vertices', None, 0), (REFARR, 'VertexComponentNames', STRING, 0), (REFARR, 'VertexAnnotationSets', Empty, 0)]
+Group = [(I32, 'MaterialIndex', None, 0), (I32, 'TriFirst', None, 0), (I32, 'TriCount', None, 0)]
+TriTopology = [(REFARR, 'Groups', Group, 0), (REFARR, 'Indices', INT32, 0), (REFARR, 'Indices16', INT16, 0),
+               (REFARR, 'VertexToVertexMap', INT32, 0), (REFARR, 'VertexToTriangleMap', INT32, 0), (REFARR, 'SideToNeighborMap', INT32, 0),
+               (REFARR, 'BonesForTriangle', INT32, 0), (REFARR, 'TriangleToBoneIndices', INT32, 0), (REFARR, 'TriAnnotationSets', Empty, 0)]
+BoneBinding = [(STR, 'BoneName', None, 0), (R32, 'OBBMin', None, 3), (R32, 'OBBMax', None, 3), (REFARR, 'TriangleIndices', INT32, 0)]
+MaterialBinding = [(REF, 'Material', Material, 0)]
+Mesh = [(STR, 'Name', None, 0), (REF, 'PrimaryVertexData', VertexData, 0), (REFARR, 'MorphTargets', Empty, 0),
+        (REF, 'PrimaryTopology', TriTopology, 0), (REFARR, 'MaterialBindings', MaterialBinding, 0), (REFARR, 'BoneBindings', BoneBinding, 0),
+        (VAR, 'ExtendedData', None, 0)]
+MeshBinding = [(REF, 'Mesh', Mesh, 0)]
+Model = [(STR, 'Name', None, 0), (REF, 'Skeleton', Skeleton, 0), (XF, 'InitialPlacement', None, 0), (REFARR, 'MeshBindings', MeshBinding, 0)]
+Curve2 = [(VAR, 'CurveData', None, 0)]
+TransformTrack = [(STR, 'Name', None, 0), (I32, 'Flags', None, 0), (INLINE, 'OrientationCurve', Curve2, 0),
+                  (INLINE, 'PositionCurve', Curve2, 0), (INLINE, 'ScaleShearCurve', Curve2, 0)]
+TrackGroup = [(STR, 'Name', None, 0), (REFARR, 'VectorTracks', Empty, 0), (REFARR, 'TransformTracks', TransformTrack, 0),
+              (REFARR, 'TransformLODErrors', REAL, 0), (REFARR, 'TextTracks', Empty, 0), (XF, 'InitialPlacement', None, 0),
+              (I32, 'AccumulationFlags', None, 0), (R32, 'LoopTranslation', None, 3), (REF, 'PeriodicLoop', Empty, 0),
+              (REF, 'RootMotion', Empty, 0), (VAR, 'ExtendedData', None, 0)]
+Animation = [(STR, 'Name', None, 0), (R32, 'Duration', None, 0), (R32, 'TimeStep', None, 0), (R32, 'Oversampling', None, 0),
+             (ARRREF, 'TrackGroups', TrackGroup, 0), (I32, 'DefaultLoopCount', None, 0), (I32, 'Flags', None, 0), (VAR, 'ExtendedData', None, 0)]
+FileInfo = [(REF, 'ArtToolInfo', ArtToolInfo, 0), (REF, 'ExporterInfo', ExporterInfo, 0), (STR, 'FromFileName', None, 0),
+            (ARRREF, 'Textures', Texture, 0), (ARRREF, 'Materials', Material, 0), (ARRREF, 'Skeletons', Skeleton, 0),
+            (ARRREF, 'VertexDatas', VertexData, 0), (ARRREF, 'TriTopologies', TriTopology, 0), (ARRREF, 'Meshes', Mesh, 0),
+            (ARRREF, 'Models', Model, 0), (ARRREF, 'TrackGroups', TrackGroup, 0), (ARRREF, 'Animations', Animation, 0),
+            (VAR, 'ExtendedData', None, 0)]
+
+# --- content ---
+def xf(t=(0, 0, 0), q=(0, 0, 0, 1), s=1.0):
+    return (7,) + tuple(t) + tuple(q) + (s, 0, 0, 0, s, 0, 0, 0, s)
+
+def inv_translate(t):
+    return [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, -t[0], -t[1], -t[2], 1]
+
+tex = {'FromFileName': 'textures/skin.png', 'TextureType': 0, 'Width': 256, 'Height': 128, 'Encoding': 1, 'SubFormat': 0,
+       'Layout': {'BytesPerPixel': 4, 'ShiftForComponent': [16, 8, 0, 24], 'BitsForComponent': [8, 8, 8, 8]}}
+mat_diffuse = {'Name': 'SkinDiffuse', 'Maps': [], 'Texture': tex}
+mat = {'Name': 'Skin', 'Maps': [{'Usage': 'Diffuse Color', 'Material': mat_diffuse}], 'Texture': None}
+mat2 = {'Name': 'Cloth', 'Maps': [], 'Texture': None}
+
+bones = [
+    {'Name': 'root', 'ParentIndex': -1, 'LocalTransform': xf(), 'InverseWorld4x4': inv_translate((0, 0, 0)), 'LODError': 0.5},
+    {'Name': 'spine', 'ParentIndex': 0, 'LocalTransform': xf((0, 1, 0)), 'InverseWorld4x4': inv_translate((0, 1, 0)), 'LODError': 0.25},
+    {'Name': 'head', 'ParentIndex': 1, 'LocalTransform': xf((0, 1, 0), (0, 0, math.sin(math.pi / 4), math.cos(math.pi / 4))),
+     'InverseWorld4x4': [0, -1, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, -2, 0, 0, 1], 'LODError': 0.0},
+]
+skel = {'Name': 'Body', 'Bones': bones, 'LODType': 0}
+
+verts = []
+for i, (x, y, z) in enumerate([(0, 0, 0), (1, 0, 0), (1, 1, 0), (0, 1, 0), (0, 2, 0), (1, 2, 0)]):
+    b = 0 if y == 0 else (1 if y == 1 else 2)
+    verts.append({'Position': [x, y, z], 'BoneWeights': [255, 0, 0, 0] if b != 1 else [128, 127, 0, 0],
+                  'BoneIndices': [b, 0, 0, 0] if b != 1 else [1, 2, 0, 0], 'Normal': [0, 0, 1], 'Tangent': [1, 0, 0],
+                  'TextureCoordinates0': [x, y / 2]})
+vdata = {'Vertices': (Vertex, verts), 'VertexComponentNames': [{'String': n} for n in ['Position', 'BoneWeights', 'BoneIndices', 'Normal', 'Tangent', 'TextureCoordinates0']]}
+indices = [0, 1, 2, 0, 2, 3, 3, 2, 5, 3, 5, 4]
+topo = {'Groups': [{'MaterialIndex': 0, 'TriFirst': 0, 'TriCount': 2}, {'MaterialIndex': 1, 'TriFirst': 2, 'TriCount': 2}],
+        'Indices': [], 'Indices16': [{'UInt16': i} for i in indices]}
+mesh = {'Name': 'Quad', 'PrimaryVertexData': vdata, 'PrimaryTopology': topo,
+        'MaterialBindings': [{'Material': mat}, {'Material': mat2}],
+        'BoneBindings': [{'BoneName': b['Name'], 'OBBMin': [0, 0, 0], 'OBBMax': [1, 1, 0]} for b in bones]}
+model = {'Name': 'Body', 'Skeleton': skel, 'InitialPlacement': xf(), 'MeshBindings': [{'Mesh': mesh}]}
+
+def track(name, o, p, s):
+    return {'Name': name, 'Flags': 0, 'OrientationCurve': o, 'PositionCurve': p, 'ScaleShearCurve': s}
+
+tracks = [
+    track('root', ident(4), ident(3), ident(9)),
+    track('spine', curve('D4Constant32f', Controls=[0, 0, 0, 1]),
+          curve('DaK32fC32f', degree=1, Knots=[0, 1], Controls=[0, 1, 0, 0, 2, 0]), ident(9)),
+    track('head', curve('DaK32fC32f', degree=1, Knots=[0, 1], Controls=[0, 0, 0, 1, 0, 0, 0.7071068, 0.7071068]),
+          curve('D3Constant32f', Controls=[0, 1, 0]), ident(9)),
+]
+
+C = [
+ curve('DaKeyframes32f', Dimension=3, Controls=[1,2,3,4,5,6]),
+ curve('DaConstant32f', Controls=[1,2]),
+ curve('DaK16uC16u', degree=2, OneOverKnotScaleTrunc=trunc(30.0), ControlScaleOffsets=[0.1,0.2,1.0,2.0], KnotsControls=[0,15,30,0,0,10,10,20,20]),
+ curve('DaK8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScaleOffsets=[0.1,0.2,1.0,2.0], KnotsControls=[0,15,30,0,0,10,10,20,20]),
+ curve('D4nK16uC15u', degree=1, ScaleOffsetTableEntries=0, OneOverKnotScale=30.0, KnotsControls=[0,30,16384,0x8000|16384,0x8000|16384,16384,0x8000|16384,0xffff]),
+ curve('D4nK8uC7u', degree=1, ScaleOffsetTableEntries=0, OneOverKnotScale=30.0, KnotsControls=[0,30,64,0x80|64,0x80|64,64,0x80|64,0xff]),
+ curve('D3K16uC16u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[0.01]*3, ControlOffsets=[0,1,2], KnotsControls=[0,30,100,200,300,0,0,0]),
+ curve('D3K8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[0.01]*3, ControlOffsets=[0,1,2], KnotsControls=[0,30,100,200,250,0,0,0]),
+ curve('D9I1K16uC16u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScale=0.5, ControlOffset=1.0, KnotsControls=[0,30,0,2]),
+ curve('D9I3K16uC16u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[1,2,3], ControlOffsets=[0,0,1], KnotsControls=[0,30,1,1,1,2,2,2]),
+ curve('D9I1K8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScale=0.5, ControlOffset=1.0, KnotsControls=[0,30,0,2]),
+ curve('D9I3K8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[1,2,3], ControlOffsets=[0,0,1], KnotsControls=[0,30,1,1,1,2,2,2]),
+ curve('D3I1K32fC32f', degree=1, ControlScales=[1,2,3], ControlOffsets=[0,0,0], KnotsControls=[0,1,0.5,1.0]),
+ curve('D3I1K16uC16u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[1,2,3], ControlOffsets=[0,0,1], KnotsControls=[0,30,1,2]),
+ curve('D3I1K8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[1,2,3], ControlOffsets=[0,0,1], KnotsControls=[0,30,1,2]),
+]
+while len(C) % 3: C.append(ident(3))
+for i in range(0, len(C), 3):
+    tracks.append(track('extra%d' % i, C[i], C[i+1], C[i+2]))
+tg = {'Name': 'Body', 'TransformTracks': tracks, 'InitialPlacement': xf(), 'LoopTranslation': [0, 0, 0]}
+anim = {'Name': 'Wave', 'Duration': 1.0, 'TimeStep': 1 / 30, 'Oversampling': 1.0, 'TrackGroups': [tg], 'DefaultLoopCount': 1}
+
+root = {'ArtToolInfo': {'FromArtToolName': 'Blender', 'ArtToolMajorRevision': 4, 'ArtToolMinorRevision': 1, 'UnitsPerMeter': 1.0,
+                        'Origin': [0, 0, 0], 'RightVector': [1, 0, 0], 'UpVector': [0, 1, 0], 'BackVector': [0, 0, 1]},
+        'ExporterInfo': {'ExporterName': 'Synthetic', 'ExporterMajorRevision': 2, 'ExporterMinorRevision': 9,
+                         'ExporterCustomization': 0, 'ExporterBuildNumber': 12},
+        'FromFileName': 'C:/art/body.max',
+        'Textures': [tex], 'Materials': [mat, mat_diffuse, mat2], 'Skeletons': [skel], 'VertexDatas': [vdata],
+        'TriTopologies': [topo], 'Meshes': [mesh], 'Models': [model], 'TrackGroups': [tg], 'Animations': [anim]}
+
+W(ptr, split).save(out, FileInfo, root)
@@ -0,0 +1,54 @@
+"""Granny2 curve types, one per Curve2 format.
+
+`curve` builds the CurveData variant of a Curve2 from the name of its
+format and its members.
+"""
+import struct
+from gr2lib import *
+
+REAL = [(R32, 'Real32', None, 0)]
+INT16 = [(U16, 'UInt16', None, 0)]
+UINT8 = [(U8, 'UInt8', None, 0)]
+Header = [(U8, 'Format', None, 0), (U8, 'Degree', None, 0)]
+def header_type(name):
+    return (INLINE, 'CurveDataHeader_' + name, Header, 0)
+CURVES = {
+    'DaKeyframes32f': [header_type('DaKeyframes32f'), (I16, 'Dimension', None, 0), (REFARR, 'Controls', REAL, 0)],
+    'DaK32fC32f': [header_type('DaK32fC32f'), (I16, 'Padding', None, 0), (REFARR, 'Knots', REAL, 0), (REFARR, 'Controls', REAL, 0)],
+    'DaIdentity': [header_type('DaIdentity'), (I16, 'Dimension', None, 0)],
+    'DaConstant32f': [header_type('DaConstant32f'), (I16, 'Padding', None, 0), (REFARR, 'Controls', REAL, 0)],
+    'D3Constant32f': [header_type('D3Constant32f'), (I16, 'Padding', None, 0), (R32, 'Controls', None, 3)],
+    'D4Constant32f': [header_type('D4Constant32f'), (I16, 'Padding', None, 0), (R32, 'Controls', None, 4)],
+    'DaK16uC16u': [header_type('DaK16uC16u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (REFARR, 'ControlScaleOffsets', REAL, 0), (REFARR, 'KnotsControls', INT16, 0)],
+    'DaK8uC8u': [header_type('DaK8uC8u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (REFARR, 'ControlScaleOffsets', REAL, 0), (REFARR, 'KnotsControls', UINT8, 0)],
+    'D4nK16uC15u': [header_type('D4nK16uC15u'), (U16, 'ScaleOffsetTableEntries', None, 0), (R32, 'OneOverKnotScale', None, 0), (REFARR, 'KnotsControls', INT16, 0)],
+    'D4nK8uC7u': [header_type('D4nK8uC7u'), (U16, 'ScaleOffsetTableEntries', None, 0), (R32, 'OneOverKnotScale', None, 0), (REFARR, 'KnotsControls', UINT8, 0)],
+    'D3K16uC16u': [header_type('D3K16uC16u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (R32, 'ControlScales', None, 3), (R32, 'ControlOffsets', None, 3), (REFARR, 'KnotsControls', INT16, 0)],
+    'D3K8uC8u': [header_type('D3K8uC8u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (R32, 'ControlScales', None, 3), (R32, 'ControlOffsets', None, 3), (REFARR, 'KnotsControls', UINT8, 0)],
+    'D9I1K16uC16u': [header_type('D9I1K16uC16u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (R32, 'ControlScale', None, 0), (R32, 'ControlOffset', None, 0), (REFARR, 'KnotsControls', INT16, 0)],
+    'D9I3K16uC16u': [header_type('D9I3K16uC16u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (R32, 'ControlScales', None, 3), (R32, 'ControlOffsets', None, 3), (REFARR, 'KnotsControls', INT16, 0)],
+    'D9I1K8uC8u': [header_type('D9I1K8uC8u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (R32, 'ControlScale', None, 0), (R32, 'ControlOffset', None, 0), (REFARR, 'KnotsControls', UINT8, 0)],
+    'D9I3K8uC8u': [header_type('D9I3K8uC8u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (R32, 'ControlScales', None, 3), (R32, 'ControlOffsets', None, 3), (REFARR, 'KnotsControls', UINT8, 0)],
+    'D3I1K32fC32f': [header_type('D3I1K32fC32f'), (I16, 'Padding', None, 0), (R32, 'ControlScales', None, 3), (R32, 'ControlOffsets', None, 3), (REFARR, 'KnotsControls', REAL, 0)],
+    'D3I1K16uC16u': [header_type('D3I1K16uC16u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (R32, 'ControlScales', None, 3), (R32, 'ControlOffsets', None, 3), (REFARR, 'KnotsControls', INT16, 0)],
+    'D3I1K8uC8u': [header_type('D3I1K8uC8u'), (U16, 'OneOverKnotScaleTrunc', None, 0), (R32, 'ControlScales', None, 3), (R32, 'ControlOffsets', None, 3), (REFARR, 'KnotsControls', UINT8, 0)],
+}
+FORMAT_IDS = ['DaKeyframes32f', 'DaK32fC32f', 'DaIdentity', 'DaConstant32f', 'D3Constant32f', 'D4Constant32f', 'DaK16uC16u',
+              'DaK8uC8u', 'D4nK16uC15u', 'D4nK8uC7u', 'D3K16uC16u', 'D3K8uC8u', 'D9I1K16uC16u', 'D9I3K16uC16u', 'D9I1K8uC8u',
+              'D9I3K8uC8u', 'D3I1K32fC32f', 'D3I1K16uC16u', 'D3I1K8uC8u']
+
+def curve(name, degree=0, **fields):
+    obj = {'CurveDataHeader_' + name: {'Format': FORMAT_IDS.index(name), 'Degree': degree}}
+    for k, v in fields.items():
+        if isinstance(v, list) and k in ('Knots', 'Controls', 'ControlScaleOffsets', 'KnotsControls') and not (name in ('D3Constant32f', 'D4Constant32f')):
+            key = {R32: 'Real32', U16: 'UInt16', U8: 'UInt8'}
+            sub = dict((m[1], m) for m in CURVES[name])[k][2]
+            obj[k] = [{sub[0][1]: x} for x in v]
+        else:
+            obj[k] = v
+    return {'CurveData': (CURVES[name], obj)}
+
+def ident(dim):
+    return curve('DaIdentity', Dimension=dim)
+
+def trunc(x): return struct.unpack('<I', struct.pack('<f', x))[0] >> 16
@@ -0,0 +1,63 @@
+"""Writes curves.gr2.
+
+A root holding one Curve2 per entry of `CURVES` below, with values picked
+so the decoded controls are easy to work out by hand. The packed
+quaternions drop a different component each and use several entries of
+the scale/offset table.
+
+usage: curves.py out.gr2
+"""
+import sys
+from gr2lib import *
+from curve_types import *
+
+out = sys.argv[1]
+
+Curve2 = [(VAR, 'CurveData', None, 0)]
+Root = [(REFARR, 'Curves', Curve2, 0)]
+
+CURVES = [
+    curve('DaKeyframes32f', Dimension=2, Controls=[1, 2, 3, 4, 5, 6]),
+    curve('DaK32fC32f', degree=1, Knots=[0, 0.5], Controls=[1, 2, 3, 4, 5, 6]),
+    ident(3),
+    ident(4),
+    ident(9),
+    curve('DaConstant32f', Controls=[1, 2]),
+    curve('D3Constant32f', Controls=[1, 2, 3]),
+    curve('D4Constant32f', Controls=[0, 0, 0, 1]),
+    curve('DaK16uC16u', degree=2, OneOverKnotScaleTrunc=trunc(30.0), ControlScaleOffsets=[0.5, 2, 1, -1],
+          KnotsControls=[0, 15, 30, 2, 3, 4, 5, 6, 7]),
+    curve('DaK8uC8u', degree=2, OneOverKnotScaleTrunc=trunc(10.0), ControlScaleOffsets=[0.5, 2, 1, -1],
+          KnotsControls=[0, 5, 10, 2, 3, 4, 5, 6, 7]),
+    # entries 0, 8 and 1 for x, y and z, dropping w, x and z in turn
+    curve('D4nK16uC15u', degree=1, ScaleOffsetTableEntries=0x0180, OneOverKnotScale=30.0,
+          KnotsControls=[0, 15, 30,
+                         32767, 0x8000 | 16384, 0x8000 | 16384,
+                         0x8000 | 0, 16384, 16384,
+                         32767, 0x8000 | 16384, 16384]),
+    # entries 4 to 7, at the ends of their ranges
+    curve('D4nK16uC15u', degree=0, ScaleOffsetTableEntries=0x7654, OneOverKnotScale=1.0,
+          KnotsControls=[0, 0, 0x8000 | 32767, 0x8000 | 0]),
+    # entry 7 everywhere, dropping y and then a negative w
+    curve('D4nK8uC7u', degree=1, ScaleOffsetTableEntries=0x7777, OneOverKnotScale=10.0,
+          KnotsControls=[0, 10, 0, 0, 0x80 | 0, 0x80 | 127, 0x80 | 0, 0x80 | 0]),
+    curve('D3K16uC16u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[0.25, 0.5, 2],
+          ControlOffsets=[0, 1, -2], KnotsControls=[0, 30, 4, 2, 1, 12, 0, 3]),
+    curve('D3K8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(10.0), ControlScales=[0.25, 0.5, 2],
+          ControlOffsets=[0, 1, -2], KnotsControls=[0, 10, 4, 2, 1, 12, 0, 3]),
+    curve('D9I1K16uC16u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScale=0.5, ControlOffset=1.0,
+          KnotsControls=[0, 30, 0, 2]),
+    curve('D9I3K16uC16u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[1, 2, 3], ControlOffsets=[0, 0, 1],
+          KnotsControls=[0, 30, 1, 1, 1, 2, 2, 2]),
+    curve('D9I1K8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(10.0), ControlScale=0.5, ControlOffset=1.0,
+          KnotsControls=[0, 10, 0, 2]),
+    curve('D9I3K8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(10.0), ControlScales=[1, 2, 3], ControlOffsets=[0, 0, 1],
+          KnotsControls=[0, 10, 1, 1, 1, 2, 2, 2]),
+    curve('D3I1K32fC32f', degree=1, ControlScales=[1, 2, 3], ControlOffsets=[0, 0, 1], KnotsControls=[0, 0.5, 1, 2]),
+    curve('D3I1K16uC16u', degree=1, OneOverKnotScaleTrunc=trunc(30.0), ControlScales=[1, 2, 3], ControlOffsets=[0, 0, 1],
+          KnotsControls=[0, 30, 1, 2]),
+    curve('D3I1K8uC8u', degree=1, OneOverKnotScaleTrunc=trunc(10.0), ControlScales=[1, 2, 3], ControlOffsets=[0, 0, 1],
+          KnotsControls=[0, 10, 1, 2]),
+]
+
+W(4).save(out, Root, {'Curves': CURVES})
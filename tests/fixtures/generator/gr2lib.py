@@ -0,0 +1,206 @@
+"""Minimal little-endian Granny2 writer used by the generator scripts.
+
+Objects are dicts keyed by member name, types lists of
+(type id, name, child type, array size) tuples. Everything goes into a
+single uncompressed section, unless `split` moves strings and arrays of
+numbers into a second one holding no pointers.
+"""
+import struct, zlib
+
+# TypeIds
+NONE, INLINE, REF, REFARR, ARRREF, VAR, REMOVED, VARARR, STR, XF, R32, I8, U8, I8N, U8N, I16, U16, I16N, U16N, I32, U32, R16, EREF = range(23)
+
+SIZES = {R32: 4, I8: 1, U8: 1, I8N: 1, U8N: 1, I16: 2, U16: 2, I16N: 2, U16N: 2, I32: 4, U32: 4, R16: 2, XF: 68}
+FMT = {R32: 'f', I8: 'b', U8: 'B', I8N: 'b', U8N: 'B', I16: 'h', U16: 'H', I16N: 'h', U16N: 'H', I32: 'i', U32: 'I', R16: 'H'}
+
+MAG = {
+    4: bytes([184, 103, 176, 202, 248, 109, 177, 15, 132, 114, 140, 126, 94, 25, 0, 30]),
+    8: bytes([229, 155, 73, 94, 111, 99, 31, 20, 30, 19, 235, 169, 144, 190, 237, 196]),
+}
+
+
+class W:
+    def __init__(self, ptr=4, split=False):
+        self.ptr = ptr
+        self.split = split
+        self.data = bytearray(b'\0' * (20 + 3 * ptr))  # empty type at 0
+        self.raw = bytearray()  # second section, targets are (1, offset)
+        self.relocs = []
+        self.types = {}
+        self.objs = {}
+        self.strs = {}
+
+    def alloc(self, n, a=4):
+        while len(self.data) % a:
+            self.data.append(0)
+        p = len(self.data)
+        self.data.extend(b'\0' * n)
+        return p
+
+    def alloc_raw(self, n, a=4):
+        while len(self.raw) % a:
+            self.raw.append(0)
+        p = len(self.raw)
+        self.raw.extend(b'\0' * n)
+        return p
+
+    def setptr(self, at, target):
+        if target is None:
+            return
+        self.relocs.append((at, target))
+
+    def string(self, s):
+        if s is None:
+            return None
+        if s not in self.strs:
+            if self.split:
+                p = self.alloc_raw(len(s) + 1, 1)
+                self.raw[p:p + len(s)] = s.encode()
+                self.strs[s] = (1, p)
+            else:
+                p = self.alloc(len(s) + 1, 1)
+                self.data[p:p + len(s)] = s.encode()
+                self.strs[s] = p
+        return self.strs[s]
+
+    def msize(self, m):
+        t, n, sub, arr = m
+        c = max(arr, 1)
+        P = self.ptr
+        if t in SIZES:
+            return SIZES[t] * c
+        if t in (STR, REF, EREF):
+            return P
+        if t in (REFARR, ARRREF):
+            return 4 + P
+        if t == VAR:
+            return 2 * P
+        if t == VARARR:
+            return 4 + 2 * P
+        if t == INLINE:
+            return self.ssize(sub) * c
+        return 0
+
+    def ssize(self, typ):
+        return sum(self.msize(m) for m in typ)
+
+    def typedef(self, typ):
+        if id(typ) in self.types:
+            return self.types[id(typ)]
+        I = 20 + 3 * self.ptr
+        p = self.alloc(I * (len(typ) + 1))
+        self.types[id(typ)] = p
+        for i, (t, n, sub, arr) in enumerate(typ):
+            at = p + i * I
+            struct.pack_into('<I', self.data, at, t)
+            self.setptr(at + 4, self.string(n))
+            if sub is not None:
+                self.setptr(at + 4 + self.ptr, self.typedef(sub))
+            struct.pack_into('<I', self.data, at + 4 + 2 * self.ptr, arr)
+        return p
+
+    def obj(self, typ, o):
+        if o is None:
+            return None
+        if id(o) in self.objs:
+            return self.objs[id(o)]
+        p = self.alloc(self.ssize(typ))
+        self.objs[id(o)] = p
+        self.fill(typ, o, p)
+        return p
+
+    def array(self, typ, items):
+        if not items:
+            return None
+        sz = self.ssize(typ)
+        if self.split and all(m[0] in FMT for m in typ):
+            p = self.alloc_raw(sz * len(items))
+            for i, it in enumerate(items):
+                self.fill(typ, it, p + i * sz, self.raw)
+            return (1, p)
+        p = self.alloc(sz * len(items))
+        for i, it in enumerate(items):
+            self.objs[id(it)] = p + i * sz
+            self.fill(typ, it, p + i * sz)
+        return p
+
+    def fill(self, typ, o, p, buf=None):
+        P = self.ptr
+        buf = self.data if buf is None else buf
+        for m in typ:
+            t, n, sub, arr = m
+            v = o.get(n)
+            if t in FMT:
+                vals = v if isinstance(v, (list, tuple)) else [v if v is not None else 0]
+                for i, x in enumerate(vals):
+                    struct.pack_into('<' + FMT[t], buf, p + i * SIZES[t], x)
+            elif t == XF:
+                x = v or (0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 1, 0, 0, 0, 1)
+                struct.pack_into('<I16f', self.data, p, *x)
+            elif t == STR:
+                self.setptr(p, self.string(v))
+            elif t in (REF, EREF):
+                self.setptr(p, self.obj(sub, v))
+            elif t == REFARR:
+                v = v or []
+                struct.pack_into('<I', self.data, p, len(v))
+                self.setptr(p + 4, self.array(sub, v))
+            elif t == ARRREF:
+                v = v or []
+                struct.pack_into('<I', self.data, p, len(v))
+                if v:
+                    tbl = self.alloc(P * len(v))
+                    for i, it in enumerate(v):
+                        self.setptr(tbl + i * P, self.obj(sub, it))
+                    self.setptr(p + 4, tbl)
+            elif t == VAR:
+                if v is not None:
+                    vt, vo = v
+                    self.setptr(p, self.typedef(vt))
+                    self.setptr(p + P, self.obj(vt, vo))
+            elif t == VARARR:
+                if v is not None:
+                    vt, items = v
+                    self.setptr(p, self.typedef(vt))
+                    struct.pack_into('<I', self.data, p + P, len(items))
+                    self.setptr(p + P + 4, self.array(vt, items))
+            elif t == INLINE:
+                if arr > 1:
+                    for i, it in enumerate(v):
+                        self.fill(sub, it, p + i * self.ssize(sub))
+                else:
+                    self.fill(sub, v or {}, p)
+            p += self.msize(m)
+
+    def save(self, path, root_type, root):
+        tp = self.typedef(root_type)
+        op = self.obj(root_type, root)
+        while len(self.data) % 4:
+            self.data.append(0)
+        while len(self.raw) % 4:
+            self.raw.append(0)
+        sections = [bytes(self.data)] + ([bytes(self.raw)] if self.split else [])
+        section_offset = 40
+        hs = 32 + section_offset + 44 * len(sections)
+        rel_off = hs + sum(len(data) for data in sections)
+        end = rel_off + 12 * len(self.relocs)
+        out = bytearray(MAG[self.ptr])
+        out += struct.pack('<II', hs, 0) + b'\0' * 8
+        out += struct.pack('<IIIII', 7, end, 0, section_offset, len(sections))
+        out += struct.pack('<IIII', 0, tp, 0, op)
+        out += struct.pack('<I', 0x80000037)
+        data_off = hs
+        for i, data in enumerate(sections):
+            # Only the first section holds pointers
+            relocs = len(self.relocs) if i == 0 else 0
+            out += struct.pack('<' + 'I' * 11, 0, data_off, len(data), len(data), 4, len(data), len(data),
+                               rel_off if i == 0 else end, relocs, end, 0)
+            data_off += len(data)
+        for data in sections:
+            out += data
+        for s, d in self.relocs:
+            ds, d = d if isinstance(d, tuple) else (0, d)
+            out += struct.pack('<III', s, ds, d)
+        crc = zlib.crc32(bytes(out[hs:])) & 0xffffffff
+        struct.pack_into('<I', out, 40, crc)
+        open(path, 'wb').write(out)
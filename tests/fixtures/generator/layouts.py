@@ -0,0 +1,92 @@
+"""Writes t4le.gr2, t4be.gr2, t8le.gr2 and t8be.gr2.
+
+One object using every kind of member, laid out with 4 or 8 byte pointers
+in either endianness, with the marshalling entries big-endian files need.
+
+usage: layouts.py out.gr2 4|8 le|be
+"""
+import struct, sys, zlib
+out, ptr, end = sys.argv[1], int(sys.argv[2]), sys.argv[3]
+E = '<' if end=='le' else '>'
+MAG = {
+ (4,'le'): bytes([184,103,176,202,248,109,177,15,132,114,140,126,94,25,0,30]),
+ (8,'le'): bytes([229,155,73,94,111,99,31,20,30,19,235,169,144,190,237,196]),
+ (4,'be'): bytes([14,17,149,181,106,165,181,75,235,40,40,80,37,120,179,4]),
+ (8,'be'): bytes([49,149,212,227,32,220,79,98,204,54,208,58,177,130,255,137]),
+}[(ptr,end)]
+data = bytearray()
+relocs = []  # (src_off, dst_off)
+marsh = []
+def align(n=4):
+    while len(data)%n: data.append(0)
+def u32(v): data.extend(struct.pack(E+'I',v))
+def i32(v): data.extend(struct.pack(E+'i',v))
+def f32(v): data.extend(struct.pack(E+'f',v))
+def i16(v): data.extend(struct.pack(E+'h',v))
+# layout plan: types first, then strings, then objects; we use fixups by name
+INFO = 4+ptr+ptr+4+12+ptr
+def type_def(members):  # members: (typeid, name, child_label, array)
+    start = len(data)
+    for t,n,c,a in members:
+        u32(t); ptrf(('str',n)); ptrf(c); u32(a); data.extend(b'\0'*12); ptrf(None)
+    u32(0); data.extend(b'\0'*(INFO-4))
+    return start
+labels = {}
+pending = []
+def ptrf(target):
+    if target is not None: pending.append((len(data), target))
+    data.extend(b'\0'*ptr)
+labels['root_t'] = type_def([(8,'Name',None,0),(19,'Count',None,0),(2,'Child',('lbl','child_t'),0),(10,'Vals',None,3),(3,'Items',('lbl','child_t'),0),(4,'Refs',('lbl','child_t'),0),(9,'Xf',None,0),(5,'Var',None,0),(1,'Inl',('lbl','child_t'),0)])
+labels['child_t'] = type_def([(15,'A',None,0),(12,'B',None,2),(13,'C',None,0)])
+# object
+align()
+labels['root_o'] = len(data)
+ptrf(('str','hello'))
+i32(7)
+ptrf(('lbl','child_o'))
+f32(1.5); f32(2.5); f32(-3.0)
+i32(2); ptrf(('lbl','arr_o'))
+i32(2); ptrf(('lbl','refs_o'))
+u32(0); [f32(x) for x in [1,2,3, 0,0,0,1, 1,0,0,0,1,0,0,0,1]]
+ptrf(('lbl','child_t')); ptrf(('lbl','child_o'))
+i16(-42); data.extend(bytes([9,8])); data.append(200)
+align()
+labels['child_o'] = len(data); i16(-5); data.extend(bytes([1,2])); data.append(255)
+align()
+labels['arr_o'] = len(data); i16(100); data.extend(bytes([3,4])); data.append(1); i16(200); data.extend(bytes([5,6])); data.append(2)
+align()
+labels['refs_o'] = len(data); ptrf(('lbl','child_o')); ptrf(('lbl','arr_o'))
+# strings
+for name in ['Name','Count','Child','Vals','Items','Refs','Xf','Var','Inl','A','B','C','hello']:
+    labels[('str',name)] = len(data); data.extend(name.encode()+b'\0')
+align()
+for off,t in pending:
+    lab = labels[t[1]] if t[0]=='lbl' else labels[t]
+    relocs.append((off, lab))
+# marshalling: child type objects (mixed) at child_o, arr_o(2), root_o(whole root is mixed)
+marsh = [(1, labels['child_o'], labels['child_t']), (2, labels['arr_o'], labels['child_t']), (1, labels['root_o'], labels['root_t'])]
+# BE: we wrote in BE with proper per-field sizes; Granny's writer stores natural BE bytes.
+# file
+hdr_extra = 4*4+4  # extra tags + something
+section_offset = 40 + hdr_extra
+nsec=1
+headers_size = 32 + section_offset + 44*nsec
+sec_off = headers_size
+rel_off = sec_off + len(data)
+mar_off = rel_off + 12*len(relocs)
+file_size = mar_off + 16*len(marsh)
+out_b = bytearray()
+out_b += MAG
+out_b += struct.pack(E+'II', headers_size, 0) + b'\0'*8
+out_b += struct.pack(E+'IIIII', 7, file_size, 0, section_offset, nsec)
+out_b += struct.pack(E+'IIII', 0, labels['root_t'], 0, labels['root_o'])
+out_b += struct.pack(E+'I', 0x80000037) + b'\0'*hdr_extra
+assert len(out_b) == 32+section_offset
+out_b += struct.pack(E+'I'*11, 0, sec_off, len(data), len(data), 4, len(data), len(data), rel_off, len(relocs), mar_off, len(marsh))
+out_b += data
+for s_,d in relocs: out_b += struct.pack(E+'III', s_, 0, d)
+for c,s_,d in marsh: out_b += struct.pack(E+'IIII', c, s_, 0, d)
+assert len(out_b)==file_size
+crc = zlib.crc32(bytes(out_b[headers_size:])) & 0xffffffff
+out_b[32+8:32+12] = struct.pack(E+'I', crc)
+open(out,'wb').write(out_b)
@@ -0,0 +1,116 @@
+#![cfg(feature = "gltf")]
+
+mod common;
+
+use common::fixture;
+use granny2::{granny2::export::gltf::Gltf, Granny2};
+use serde_json::Value;
+
+fn export() -> Gltf {
+    let bytes = fixture("c4.gr2");
+    let file = Granny2::from_bytes(&bytes).unwrap();
+    file.to_gltf().unwrap()
+}
+
+fn index(value: &Value) -> usize {
+    usize::try_from(value.as_u64().unwrap()).unwrap()
+}
+
+fn names(values: &Value) -> Vec<&str> {
+    values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value["name"].as_str().unwrap())
+        .collect()
+}
+
+/// Floats an accessor points at on the buffer.
+fn accessor_floats(gltf: &Gltf, accessor: &Value) -> Vec<f32> {
+    let view = &gltf.json["bufferViews"][index(&accessor["bufferView"])];
+    let offset = view.get("byteOffset").map_or(0, index);
+    let length = index(&view["byteLength"]);
+    gltf.buffer[offset..offset + length]
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect()
+}
+
+#[test]
+fn exports_scene() {
+    let gltf = export();
+    assert_eq!(gltf.json["asset"]["version"], "2.0");
+    assert_eq!(
+        names(&gltf.json["nodes"]),
+        ["Body", "root", "spine", "head", "Quad"]
+    );
+    assert_eq!(names(&gltf.json["meshes"]), ["Quad"]);
+    assert_eq!(
+        names(&gltf.json["materials"]),
+        ["Skin", "SkinDiffuse", "Cloth"]
+    );
+
+    let primitives = gltf.json["meshes"][0]["primitives"].as_array().unwrap();
+    assert_eq!(primitives.len(), 2);
+    let position = &gltf.json["accessors"][index(&primitives[0]["attributes"]["POSITION"])];
+    assert_eq!(position["count"], 6);
+    assert_eq!(position["max"], serde_json::json!([1.0, 2.0, 0.0]));
+
+    let skin = &gltf.json["skins"][0];
+    assert_eq!(skin["name"], "Body");
+    assert_eq!(skin["joints"], serde_json::json!([1, 2, 3]));
+}
+
+#[test]
+fn exports_animation() {
+    let gltf = export();
+    let animations = gltf.json["animations"].as_array().unwrap();
+    assert_eq!(animations.len(), 1);
+
+    // Translation, rotation and scale for each of the three bones
+    let channels = animations[0]["channels"].as_array().unwrap();
+    assert_eq!(channels.len(), 9);
+
+    let sampler = &animations[0]["samplers"][0];
+    let input = &gltf.json["accessors"][index(&sampler["input"])];
+    let times = accessor_floats(&gltf, input);
+    assert_eq!(times.len(), 31);
+    assert_eq!(times.first(), Some(&0.));
+    assert_eq!(times.last(), Some(&1.));
+    assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn writes_glb() {
+    let gltf = export();
+    let mut glb = vec![];
+    gltf.write_glb(&mut glb).unwrap();
+
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(u32::from_le_bytes(glb[4..8].try_into().unwrap()), 2);
+    assert_eq!(
+        usize::try_from(u32::from_le_bytes(glb[8..12].try_into().unwrap())).unwrap(),
+        glb.len()
+    );
+
+    let json_length = usize::try_from(u32::from_le_bytes(glb[12..16].try_into().unwrap())).unwrap();
+    assert_eq!(&glb[16..20], b"JSON");
+    let json: Value = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+    assert_eq!(json, gltf.json);
+
+    let bin = 20 + json_length;
+    assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+    assert_eq!(&glb[bin + 8..bin + 8 + gltf.buffer.len()], gltf.buffer);
+}
+
+#[test]
+fn writes_gltf_with_embedded_buffer() {
+    let gltf = export();
+    let mut text = vec![];
+    gltf.write_gltf(&mut text).unwrap();
+
+    let json: Value = serde_json::from_slice(&text).unwrap();
+    let uri = json["buffers"][0]["uri"].as_str().unwrap();
+    assert!(uri.starts_with("data:application/octet-stream;base64,"));
+    assert_eq!(json["meshes"], gltf.json["meshes"]);
+}
@@ -0,0 +1,295 @@
+mod common;
+
+use std::borrow::Cow;
+
+use common::fixture;
+use granny2::{
+    granny2::{
+        element::{Data, ElementError, LazyObject, TypeId},
+        format::Format,
+        section::SectionData,
+    },
+    Granny2, LazyGranny2,
+};
+
+fn lazy(name: &str) -> LazyGranny2<'static> {
+    LazyGranny2::from_bytes(&fixture(name))
+        .unwrap()
+        .into_owned()
+}
+
+#[test]
+fn reads_members_by_name() {
+    let file = lazy("c4.gr2");
+    let root = file.root();
+    let name = root.child("FromFileName").unwrap();
+    assert_eq!(name.element_type(), TypeId::String);
+    assert_eq!(name.name().unwrap(), "FromFileName");
+    assert_eq!(name.string().unwrap(), "C:/art/body.max");
+
+    let exporter = root.child("ExporterInfo").unwrap();
+    assert_eq!(
+        exporter.child("ExporterName").unwrap().string().unwrap(),
+        "Synthetic"
+    );
+    assert_eq!(
+        exporter
+            .child("ExporterBuildNumber")
+            .unwrap()
+            .data()
+            .unwrap(),
+        [Data::Int32(12)]
+    );
+}
+
+#[test]
+fn indexes_arrays() {
+    let file = lazy("c4.gr2");
+    let root = file.root();
+
+    // Array of references
+    let materials = root.child("Materials").unwrap();
+    assert_eq!(materials.len().unwrap(), 3);
+    let names = (0..3)
+        .map(|i| {
+            let name = materials.index(i).unwrap().child("Name").unwrap();
+            name.string().unwrap().into_owned()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Skin", "SkinDiffuse", "Cloth"]);
+
+    // Array of objects
+    let bones = root
+        .child("Skeletons")
+        .and_then(|skeletons| skeletons.index(0))
+        .and_then(|skeleton| skeleton.child("Bones"))
+        .unwrap();
+    assert_eq!(bones.len().unwrap(), 3);
+    assert!(!bones.is_empty().unwrap());
+    let head = bones.index(2).unwrap();
+    assert_eq!(head.child("Name").unwrap().string().unwrap(), "head");
+    assert_eq!(
+        head.child("ParentIndex").unwrap().data().unwrap(),
+        [Data::Int32(1)]
+    );
+}
+
+#[test]
+fn array_bytes_hold_every_item() {
+    let bytes = fixture("c4.gr2");
+    let file = LazyGranny2::from_bytes(&bytes).unwrap();
+    let vertices = file
+        .root()
+        .child("VertexDatas")
+        .and_then(|datas| datas.index(0))
+        .and_then(|data| data.child("Vertices"))
+        .unwrap();
+    let array = vertices.array_bytes().unwrap();
+    // Position, normal and tangent of 12 bytes, weights and indices of 4
+    // and texture coordinates of 8
+    assert_eq!(array.len(), 6 * 52);
+
+    let eager = Granny2::from_bytes(&bytes).unwrap();
+    let position = eager.get("VertexDatas/0/Vertices/5/Position").unwrap();
+    let Data::Real32Array(position) = &position.data[0] else {
+        panic!("Position must be an array of floats.");
+    };
+    let floats = array[5 * 52..5 * 52 + 12]
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(&floats, position);
+
+    assert!(matches!(
+        file.root().child("FromFileName").unwrap().array_bytes(),
+        Err(ElementError::WrongType(TypeId::String))
+    ));
+}
+
+#[test]
+fn missing_items_are_errors() {
+    let file = lazy("c4.gr2");
+    let root = file.root();
+    assert!(matches!(
+        root.child("Nope"),
+        Err(ElementError::NotFound(name)) if &*name == "Nope"
+    ));
+    assert!(matches!(
+        root.child("Materials").unwrap().index(3),
+        Err(ElementError::OutOfBounds(3))
+    ));
+    let bones = root
+        .child("Skeletons")
+        .and_then(|skeletons| skeletons.index(0))
+        .and_then(|skeleton| skeleton.child("Bones"))
+        .unwrap();
+    assert!(matches!(bones.index(3), Err(ElementError::OutOfBounds(3))));
+
+    // Null reference
+    let texture = root
+        .child("Materials")
+        .and_then(|materials| materials.index(2))
+        .and_then(|material| material.child("Texture"))
+        .unwrap();
+    assert!(texture.object().unwrap().is_none());
+    assert!(matches!(
+        texture.child("Width"),
+        Err(ElementError::NullReference)
+    ));
+
+    // Members that hold no objects
+    let name = root.child("FromFileName").unwrap();
+    assert!(matches!(name.len(), Err(ElementError::WrongType(_))));
+    assert!(matches!(name.index(0), Err(ElementError::WrongType(_))));
+    assert!(matches!(
+        root.child("ExporterInfo").unwrap().string(),
+        Err(ElementError::WrongType(TypeId::Reference))
+    ));
+}
+
+const RECORD: usize = 32;
+const NAME: usize = 0;
+const ITEMS: usize = RECORD;
+const ITEM_TYPE: usize = 3 * RECORD;
+const OBJECT: usize = 5 * RECORD;
+const STRINGS: usize = OBJECT + 12;
+
+fn write_u32(bytes: &mut [u8], pos: usize, value: usize) {
+    let value = u32::try_from(value).unwrap();
+    bytes[pos..(pos + 4)].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Little endian 32-bit data holding an object with a `Name` string and
+/// `Items`, an array of one object with an `Int32` named `Value`.
+fn crafted() -> Vec<u8> {
+    let mut bytes = vec![0; STRINGS];
+    for name in ["Name\0", "Items\0", "Value\0", "hello\0"] {
+        bytes.extend_from_slice(name.as_bytes());
+    }
+    let items = bytes.len();
+    bytes.extend_from_slice(&7_i32.to_le_bytes());
+
+    write_u32(&mut bytes, NAME, 8);
+    write_u32(&mut bytes, NAME + 4, STRINGS);
+    write_u32(&mut bytes, ITEMS, 3);
+    write_u32(&mut bytes, ITEMS + 4, STRINGS + 5);
+    write_u32(&mut bytes, ITEMS + 8, ITEM_TYPE);
+    write_u32(&mut bytes, ITEM_TYPE, 19);
+    write_u32(&mut bytes, ITEM_TYPE + 4, STRINGS + 11);
+
+    write_u32(&mut bytes, OBJECT, STRINGS + 17);
+    write_u32(&mut bytes, OBJECT + 4, 1);
+    write_u32(&mut bytes, OBJECT + 8, items);
+    bytes
+}
+
+/// Data of a file with a single section holding `bytes`.
+fn sections(bytes: &[u8]) -> SectionData<'_> {
+    SectionData::new(vec![Cow::Borrowed(bytes)])
+}
+
+fn root<'a>(data: &'a SectionData<'a>) -> LazyObject<'a> {
+    LazyObject::new(data, Format::LITTLE_ENDIAN_32, 0, OBJECT as u64)
+}
+
+#[test]
+fn hand_written_data_reads() {
+    let bytes = crafted();
+    let data = sections(&bytes);
+    let root = root(&data);
+    assert_eq!(root.child("Name").unwrap().string().unwrap(), "hello");
+    let items = root.child("Items").unwrap();
+    assert_eq!(items.len().unwrap(), 1);
+    let value = items.index(0).and_then(|item| item.child("Value")).unwrap();
+    assert_eq!(value.data().unwrap(), [Data::Int32(7)]);
+    assert_eq!(items.array_bytes().unwrap(), 7_i32.to_le_bytes());
+}
+
+#[test]
+fn bad_names_are_errors() {
+    // Past the end of the data
+    let mut bytes = crafted();
+    write_u32(&mut bytes, NAME + 4, 10_000);
+    let data = sections(&bytes);
+    assert!(matches!(
+        root(&data).child("Name"),
+        Err(ElementError::OutOfRange(10_000))
+    ));
+    assert!(root(&data).members().unwrap()[0].name().is_err());
+
+    // Running into the end of the data
+    let mut bytes = crafted();
+    let name = bytes.len();
+    bytes.extend_from_slice(b"Value");
+    write_u32(&mut bytes, ITEM_TYPE + 4, name);
+    let data = sections(&bytes);
+    let items = root(&data).child("Items").unwrap();
+    let item = items.index(0).unwrap();
+    assert!(matches!(
+        item.child("Value"),
+        Err(ElementError::UnterminatedString(_))
+    ));
+}
+
+#[test]
+fn bad_strings_are_errors() {
+    let mut bytes = crafted();
+    write_u32(&mut bytes, OBJECT, 10_000);
+    let data = sections(&bytes);
+    let name = root(&data).child("Name").unwrap();
+    assert!(matches!(
+        name.string(),
+        Err(ElementError::OutOfRange(10_000))
+    ));
+    assert!(name.data().is_err());
+}
+
+#[test]
+fn bad_arrays_are_errors() {
+    // More items than the data holds
+    let mut bytes = crafted();
+    write_u32(&mut bytes, OBJECT + 4, u32::MAX as usize);
+    let data = sections(&bytes);
+    let items = root(&data).child("Items").unwrap();
+    assert_eq!(items.len().unwrap(), u32::MAX as usize);
+    assert!(matches!(
+        items.array_bytes(),
+        Err(ElementError::OutOfRange(_))
+    ));
+    assert!(items.as_i32_slice().is_err());
+    let last = items.index(u32::MAX as usize - 1).unwrap();
+    assert!(last.child("Value").unwrap().data().is_err());
+    assert!(matches!(
+        items.index(u32::MAX as usize),
+        Err(ElementError::OutOfBounds(_))
+    ));
+
+    // Items past the end
+    let mut bytes = crafted();
+    write_u32(&mut bytes, OBJECT + 8, u32::MAX as usize);
+    let data = sections(&bytes);
+    let items = root(&data).child("Items").unwrap();
+    assert!(items.array_bytes().is_err());
+    assert!(items
+        .index(0)
+        .and_then(|item| item.child("Value"))
+        .and_then(|value| value.data())
+        .is_err());
+}
+
+#[test]
+fn bad_types_are_errors() {
+    let bytes = crafted();
+    let data = sections(&bytes);
+    let object = LazyObject::new(&data, Format::LITTLE_ENDIAN_32, 10_000, 0);
+    assert!(object.members().is_err());
+    assert!(object.child("Name").is_err());
+    assert!(object.definition().is_err());
+
+    // An inlined object can't hold itself
+    let mut bytes = crafted();
+    write_u32(&mut bytes, ITEMS, 1);
+    write_u32(&mut bytes, ITEMS + 8, 0);
+    let data = sections(&bytes);
+    assert!(root(&data).members().is_err());
+}
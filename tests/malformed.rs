@@ -0,0 +1,94 @@
+mod common;
+
+use common::fixture;
+use granny2::{Granny2, LazyGranny2, ParseOptions};
+
+const OPTIONS: ParseOptions = ParseOptions {
+    verify_checksum: false,
+};
+
+fn read_u32(bytes: &[u8], pos: usize) -> usize {
+    let Ok(value) = bytes[pos..(pos + 4)].try_into().map(u32::from_le_bytes) else {
+        unreachable!("Slice has 4 bytes.");
+    };
+    usize::try_from(value).unwrap()
+}
+
+fn write_u32(bytes: &mut [u8], pos: usize, value: usize) {
+    let value = u32::try_from(value).unwrap();
+    bytes[pos..(pos + 4)].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Position of the info of the first section.
+fn section_info(bytes: &[u8]) -> usize {
+    32 + read_u32(bytes, 44)
+}
+
+/// Position of the first relocation of the first section.
+fn first_relocation(bytes: &[u8]) -> usize {
+    let section = section_info(bytes);
+    assert!(
+        read_u32(bytes, section + 32) > 0,
+        "Fixture must have relocations."
+    );
+    read_u32(bytes, section + 28)
+}
+
+/// Both parsers reject `bytes` with an error instead of panicking.
+fn assert_rejected(bytes: &[u8]) {
+    assert!(Granny2::from_bytes_with_options(bytes, &OPTIONS).is_err());
+    assert!(LazyGranny2::from_bytes_with_options(bytes, &OPTIONS).is_err());
+}
+
+#[test]
+fn intact_fixture_parses() {
+    let bytes = fixture("t4le.gr2");
+    assert!(Granny2::from_bytes_with_options(&bytes, &OPTIONS).is_ok());
+}
+
+#[test]
+fn relocation_table_past_end_of_file() {
+    let mut bytes = fixture("t4le.gr2");
+    let section = section_info(&bytes);
+    // Starts inside the file, but the relocation does not fit
+    let end = bytes.len() - 4;
+    write_u32(&mut bytes, section + 28, end);
+    assert_rejected(&bytes);
+}
+
+#[test]
+fn relocation_to_missing_section() {
+    let mut bytes = fixture("t4le.gr2");
+    let relocation = first_relocation(&bytes);
+    write_u32(&mut bytes, relocation + 4, 1000);
+    assert_rejected(&bytes);
+}
+
+#[test]
+fn relocation_outside_its_section() {
+    let mut bytes = fixture("t4le.gr2");
+    let section = section_info(&bytes);
+    let size = read_u32(&bytes, section + 12);
+    let relocation = first_relocation(&bytes);
+    // Last pointer would only partially fit
+    write_u32(&mut bytes, relocation, size - 2);
+    assert_rejected(&bytes);
+}
+
+#[test]
+fn root_in_missing_section() {
+    let mut bytes = fixture("t4le.gr2");
+    write_u32(&mut bytes, 52, 1000);
+    assert_rejected(&bytes);
+
+    let mut bytes = fixture("t4le.gr2");
+    write_u32(&mut bytes, 60, 1000);
+    assert_rejected(&bytes);
+}
+
+#[test]
+fn section_info_past_end_of_file() {
+    let mut bytes = fixture("t4le.gr2");
+    write_u32(&mut bytes, 48, 1000);
+    assert_rejected(&bytes);
+}
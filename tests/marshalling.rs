@@ -0,0 +1,27 @@
+mod common;
+
+use common::{fixture, flatten};
+use granny2::{granny2::element::Data, Granny2};
+
+#[test]
+fn big_endian_matches_little_endian() {
+    for (little, big) in [("t4le.gr2", "t4be.gr2"), ("t8le.gr2", "t8be.gr2")] {
+        let little = Granny2::from_bytes(&fixture(little)).unwrap();
+        let big = Granny2::from_bytes(&fixture(big)).unwrap();
+        assert_eq!(flatten(&big.root), flatten(&little.root));
+    }
+}
+
+#[test]
+fn big_endian_values() {
+    let file = Granny2::from_bytes(&fixture("t4be.gr2")).unwrap();
+    let value = |path: &str| file.get(path).unwrap().data.clone();
+
+    assert_eq!(value("Count"), [Data::Int32(7)]);
+    assert_eq!(value("Vals"), [Data::Real32Array(vec![1.5, 2.5, -3.0])]);
+    assert_eq!(value("Child/A"), [Data::Int16(-5)]);
+    assert_eq!(value("Items/1/A"), [Data::Int16(200)]);
+    assert_eq!(value("Items/0/C"), [Data::Int8Norm(1)]);
+    assert_eq!(value("Inl/A"), [Data::Int16(-42)]);
+    assert_eq!(value("Inl/B"), [Data::UInt8Array(vec![9, 8])]);
+}
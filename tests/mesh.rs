@@ -0,0 +1,95 @@
+mod common;
+
+use common::fixture;
+use granny2::Granny2;
+
+fn file() -> Granny2 {
+    Granny2::from_bytes(&fixture("c4.gr2")).unwrap()
+}
+
+#[test]
+fn reads_vertex_attributes() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    let geometry = info.meshes[0].geometry().unwrap();
+
+    assert_eq!(
+        geometry.positions,
+        [
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [1., 1., 0.],
+            [0., 1., 0.],
+            [0., 2., 0.],
+            [1., 2., 0.]
+        ]
+    );
+    assert_eq!(geometry.normals, Some(vec![[0., 0., 1.]; 6]));
+    assert_eq!(geometry.tangents, Some(vec![[1., 0., 0.]; 6]));
+    assert_eq!(geometry.binormals, None);
+    assert_eq!(
+        geometry.texture_coordinates,
+        [vec![
+            [0., 0.],
+            [1., 0.],
+            [1., 0.5],
+            [0., 0.5],
+            [0., 1.],
+            [1., 1.]
+        ]]
+    );
+}
+
+#[test]
+fn reads_skinning_attributes() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    let geometry = info.meshes[0].geometry().unwrap();
+
+    // Normalized bytes, the middle vertices split between spine and head
+    let single = [1., 0., 0., 0.];
+    let split = [128. / 255., 127. / 255., 0., 0.];
+    assert_eq!(
+        geometry.bone_weights,
+        Some(vec![single, single, split, split, single, single])
+    );
+    assert_eq!(
+        geometry.bone_indices,
+        Some(vec![
+            [0, 0, 0, 0],
+            [0, 0, 0, 0],
+            [1, 2, 0, 0],
+            [1, 2, 0, 0],
+            [2, 0, 0, 0],
+            [2, 0, 0, 0]
+        ])
+    );
+
+    let skeleton = &info.skeletons[0];
+    assert_eq!(
+        info.meshes[0].bone_binding_indices(skeleton),
+        [Some(0), Some(1), Some(2)]
+    );
+}
+
+#[test]
+fn reads_triangles_and_groups() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    let geometry = info.meshes[0].geometry().unwrap();
+
+    // Only Indices16 is filled in, Indices being empty
+    assert_eq!(geometry.indices, [0, 1, 2, 0, 2, 3, 3, 2, 5, 3, 5, 4]);
+    let groups = geometry
+        .groups
+        .iter()
+        .map(|group| (group.material_index, group.tri_first, group.tri_count))
+        .collect::<Vec<_>>();
+    assert_eq!(groups, [(0, 0, 2), (1, 2, 2)]);
+
+    // Every index points at a vertex
+    assert!(geometry
+        .indices
+        .iter()
+        .all(|index| usize::try_from(*index).unwrap() < geometry.positions.len()));
+}
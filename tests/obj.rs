@@ -0,0 +1,169 @@
+mod common;
+
+use common::fixture;
+use granny2::{
+    granny2::{export::obj::write_obj, file_info::FileInfo},
+    Granny2,
+};
+
+fn file() -> Granny2 {
+    Granny2::from_bytes(&fixture("c4.gr2")).unwrap()
+}
+
+/// OBJ and MTL files written for `file_info`.
+fn export(file_info: &FileInfo) -> (String, String) {
+    let mut obj = Vec::new();
+    let mut mtl = Vec::new();
+    write_obj(file_info, &mut obj, &mut mtl, "body.mtl").unwrap();
+    (
+        String::from_utf8(obj).unwrap(),
+        String::from_utf8(mtl).unwrap(),
+    )
+}
+
+fn lines_starting<'a>(text: &'a str, prefix: &str) -> Vec<&'a str> {
+    text.lines()
+        .filter(|line| line.starts_with(prefix))
+        .collect()
+}
+
+#[test]
+fn writes_geometry() {
+    let file = file();
+    let (obj, _) = export(&file.file_info().unwrap());
+
+    assert_eq!(obj.lines().next(), Some("mtllib body.mtl"));
+    assert_eq!(lines_starting(&obj, "o "), ["o Quad"]);
+    assert_eq!(
+        lines_starting(&obj, "v "),
+        ["v 0 0 0", "v 1 0 0", "v 1 1 0", "v 0 1 0", "v 0 2 0", "v 1 2 0"]
+    );
+    assert_eq!(lines_starting(&obj, "vt ").len(), 6);
+    assert_eq!(lines_starting(&obj, "vt ")[4], "vt 0 1");
+    assert_eq!(lines_starting(&obj, "vn ").len(), 6);
+    assert_eq!(lines_starting(&obj, "vn ")[0], "vn 0 0 1");
+}
+
+#[test]
+fn writes_a_material_per_group() {
+    let file = file();
+    let (obj, _) = export(&file.file_info().unwrap());
+
+    // Faces after the mtllib and the vertices
+    let faces = obj
+        .lines()
+        .skip_while(|line| !line.starts_with("usemtl"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        faces,
+        [
+            "usemtl Skin",
+            "f 1/1/1 2/2/2 3/3/3",
+            "f 1/1/1 3/3/3 4/4/4",
+            "usemtl Cloth",
+            "f 4/4/4 3/3/3 6/6/6",
+            "f 4/4/4 6/6/6 5/5/5",
+        ]
+    );
+}
+
+#[test]
+fn indices_keep_counting_across_objects() {
+    let file = file();
+    let mut file_info = file.file_info().unwrap();
+    file_info.meshes.extend(file.file_info().unwrap().meshes);
+    let (obj, _) = export(&file_info);
+
+    assert_eq!(lines_starting(&obj, "o "), ["o Quad", "o Quad"]);
+    assert_eq!(lines_starting(&obj, "v ").len(), 12);
+    assert_eq!(lines_starting(&obj, "vt ").len(), 12);
+    assert_eq!(lines_starting(&obj, "vn ").len(), 12);
+
+    let faces = lines_starting(&obj, "f ");
+    assert_eq!(faces.len(), 8);
+    assert_eq!(faces[0], "f 1/1/1 2/2/2 3/3/3");
+    assert_eq!(faces[4], "f 7/7/7 8/8/8 9/9/9");
+    assert_eq!(faces[7], "f 10/10/10 12/12/12 11/11/11");
+    assert_eq!(
+        lines_starting(&obj, "usemtl"),
+        ["usemtl Skin", "usemtl Cloth", "usemtl Skin", "usemtl Cloth"]
+    );
+}
+
+#[test]
+fn writes_materials_with_textures() {
+    let file = file();
+    let (_, mtl) = export(&file.file_info().unwrap());
+
+    assert_eq!(
+        lines_starting(&mtl, "newmtl"),
+        [
+            "newmtl default",
+            "newmtl Skin",
+            "newmtl SkinDiffuse",
+            "newmtl Cloth"
+        ]
+    );
+    // Skin takes the texture of its diffuse map
+    assert_eq!(
+        lines_starting(&mtl, "map_Kd"),
+        ["map_Kd textures/skin.png", "map_Kd textures/skin.png"]
+    );
+    let skin = mtl.split("\n\n").nth(1).unwrap();
+    assert!(skin.contains("map_Kd textures/skin.png"), "{}", skin);
+    let cloth = mtl.split("\n\n").nth(3).unwrap();
+    assert!(!cloth.contains("map_Kd"), "{}", cloth);
+}
+
+#[test]
+fn material_names_are_unique() {
+    let file = file();
+    let mut file_info = file.file_info().unwrap();
+    file_info.materials[1].name = "Skin";
+    file_info.materials[2].name = "default";
+    let (obj, mtl) = export(&file_info);
+
+    assert_eq!(
+        lines_starting(&mtl, "newmtl"),
+        [
+            "newmtl default",
+            "newmtl Skin",
+            "newmtl Skin_1",
+            "newmtl default_2"
+        ]
+    );
+    assert_eq!(
+        lines_starting(&obj, "usemtl"),
+        ["usemtl Skin", "usemtl default_2"]
+    );
+}
+
+#[test]
+fn names_lose_whitespace() {
+    let file = file();
+    let mut file_info = file.file_info().unwrap();
+    file_info.meshes[0].name = "Upper Body";
+    file_info.materials[0].name = "Rough\tSkin";
+    file_info.materials[2].name = "";
+    let (obj, mtl) = export(&file_info);
+
+    assert_eq!(lines_starting(&obj, "o "), ["o Upper_Body"]);
+    assert_eq!(
+        lines_starting(&obj, "usemtl"),
+        ["usemtl Rough_Skin", "usemtl unnamed"]
+    );
+    assert!(mtl.contains("newmtl Rough_Skin\n"));
+}
+
+#[test]
+fn groups_without_material_use_default() {
+    let file = file();
+    let mut file_info = file.file_info().unwrap();
+    file_info.meshes[0].material_bindings[1] = None;
+    let (obj, _) = export(&file_info);
+
+    assert_eq!(
+        lines_starting(&obj, "usemtl"),
+        ["usemtl Skin", "usemtl default"]
+    );
+}
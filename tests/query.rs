@@ -0,0 +1,114 @@
+mod common;
+
+use common::fixture;
+use granny2::{granny2::element::Data, Granny2};
+
+fn file() -> Granny2 {
+    Granny2::from_bytes(&fixture("c4.gr2")).unwrap()
+}
+
+/// Paths of every match, as given by the query.
+fn paths(file: &Granny2, path: &str) -> Vec<String> {
+    let mut query = file.query(path);
+    let mut paths = vec![];
+    while query.next().is_some() {
+        paths.push(query.path());
+    }
+    paths
+}
+
+fn strings(file: &Granny2, path: &str) -> Vec<String> {
+    file.query(path)
+        .map(|element| match element.data.as_slice() {
+            [Data::String(value)] => value.to_string(),
+            data => panic!("{} is not a string but {:?}.", element.name, data),
+        })
+        .collect()
+}
+
+#[test]
+fn wildcards_match_every_item() {
+    let file = file();
+    assert_eq!(
+        paths(&file, "Skeletons/0/Bones/*/Name"),
+        [
+            "Skeletons/0/Bones/0/Name",
+            "Skeletons/0/Bones/1/Name",
+            "Skeletons/0/Bones/2/Name"
+        ]
+    );
+    assert_eq!(
+        strings(&file, "Skeletons/0/Bones/*/Name"),
+        ["root", "spine", "head"]
+    );
+    assert_eq!(
+        strings(&file, "Materials/?/Name"),
+        ["Skin", "SkinDiffuse", "Cloth"]
+    );
+    // Matches on several levels
+    assert_eq!(
+        paths(&file, "*Info/*Name"),
+        ["ArtToolInfo/FromArtToolName", "ExporterInfo/ExporterName"]
+    );
+}
+
+#[test]
+fn paths_go_through_references() {
+    let file = file();
+    // Arrays of references, and references
+    assert_eq!(
+        strings(&file, "Materials/*/Maps/*/Material/Name"),
+        ["SkinDiffuse"]
+    );
+    // Inlined objects
+    assert_eq!(
+        file.get("Textures/0/Layout/BytesPerPixel").unwrap().data,
+        [Data::Int32(4)]
+    );
+    // Variants
+    assert!(file
+        .get("Animations/0/TrackGroups/0/TransformTracks/1/PositionCurve/CurveData/Knots/1")
+        .is_some());
+}
+
+#[test]
+fn empty_segments_are_skipped() {
+    let file = file();
+    assert_eq!(paths(&file, "/Models//0/Name/"), ["Models/0/Name"]);
+    assert!(file.query("").next().is_none());
+    assert!(file.query("//").next().is_none());
+}
+
+#[test]
+fn missing_paths_match_nothing() {
+    let file = file();
+    assert!(file.get("Models/1/Name").is_none());
+    assert!(file.get("Models/0/Name/Name").is_none());
+    // Numbers are a single element
+    assert!(file.get("Textures/0/Layout/ShiftForComponent").is_some());
+    assert!(file.get("Textures/0/Layout/ShiftForComponent/0").is_none());
+    // Names are case sensitive
+    assert!(file.get("models/0/name").is_none());
+}
+
+#[test]
+fn elements_query_their_children() {
+    let file = file();
+    let skeleton = file.get("Skeletons/0").unwrap();
+    let names = skeleton
+        .query("Bones/*/ParentIndex")
+        .map(|element| element.data.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            vec![Data::Int32(-1)],
+            vec![Data::Int32(0)],
+            vec![Data::Int32(1)]
+        ]
+    );
+    assert_eq!(
+        skeleton.get("Name").unwrap().data,
+        [Data::String("Body".into())]
+    );
+}
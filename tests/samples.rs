@@ -0,0 +1,41 @@
+mod common;
+
+use std::{io::Cursor, path::PathBuf};
+
+use common::flatten;
+use granny2::Granny2;
+
+/// Files written by the Granny runtime, which can't be checked in.
+fn samples() -> Vec<PathBuf> {
+    let Some(dir) = std::env::var_os("GRANNY2_SAMPLES") else {
+        panic!("GRANNY2_SAMPLES must name a directory of .gr2 files.");
+    };
+    let mut samples = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "gr2"))
+        .collect::<Vec<_>>();
+    samples.sort();
+    assert!(!samples.is_empty(), "No .gr2 file in {:?}.", dir);
+    samples
+}
+
+#[test]
+#[ignore = "needs files written by the Granny runtime under GRANNY2_SAMPLES"]
+fn runtime_files_decompress() {
+    for path in samples() {
+        let bytes = std::fs::read(&path).unwrap();
+        let file =
+            Granny2::from_bytes(&bytes).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+
+        let mut decompressed = vec![];
+        Granny2::decompress(Cursor::new(&bytes), &mut decompressed).unwrap();
+        let uncompressed = Granny2::from_bytes(&decompressed).unwrap();
+        assert_eq!(
+            flatten(&uncompressed.root),
+            flatten(&file.root),
+            "{}",
+            path.display()
+        );
+    }
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::{fixture, flatten};
+use granny2::{
+    granny2::element::{Data, Element},
+    Granny2,
+};
+
+const FIXTURES: [&str; 3] = ["c4.gr2", "t4le.gr2", "t8be.gr2"];
+
+fn round_trip(file: &Granny2) -> Granny2 {
+    let json = serde_json::to_string(file).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn deserializing_keeps_tree() {
+    for name in FIXTURES {
+        let file = Granny2::from_bytes(&fixture(name)).unwrap();
+        let deserialized = round_trip(&file);
+        assert_eq!(flatten(&deserialized.root), flatten(&file.root), "{}", name);
+        assert_eq!(deserialized.definition(), file.definition(), "{}", name);
+    }
+}
+
+#[test]
+fn deserializing_keeps_definitions() {
+    // Empty arrays and null references only know their type from it
+    let file = Granny2::from_bytes(&fixture("c4.gr2")).unwrap();
+    let deserialized = round_trip(&file);
+    for path in [
+        "TriTopologies/*/Indices",
+        "Meshes/*/MaterialBindings/*/Material",
+        "TrackGroups/*/TransformLODErrors",
+        "Materials/*/ExtendedData",
+    ] {
+        let definitions = |file: &Granny2| {
+            file.query(path)
+                .map(|element| element.definition.clone())
+                .collect::<Vec<_>>()
+        };
+        assert!(file.query(path).next().is_some(), "{}", path);
+        assert_eq!(definitions(&deserialized), definitions(&file), "{}", path);
+    }
+}
+
+#[test]
+fn deserialized_file_writes_same_schema() {
+    for name in FIXTURES {
+        let file = Granny2::from_bytes(&fixture(name)).unwrap();
+        let mut bytes = vec![];
+        round_trip(&file).write(&mut bytes).unwrap();
+        let written = Granny2::from_bytes(&bytes).unwrap();
+        assert_eq!(flatten(&written.root), flatten(&file.root), "{}", name);
+        assert_eq!(written.definition(), file.definition(), "{}", name);
+    }
+}
+
+/// Object identifiers of the references of the tree, in tree order.
+fn objects(elements: &[Element], ids: &mut Vec<Option<usize>>) {
+    for element in elements {
+        if let [Data::Reference(pos)] = element.data[..] {
+            if pos != 0 {
+                ids.push(element.object);
+            }
+        }
+        objects(&element.children, ids);
+    }
+}
+
+#[test]
+fn deserialized_file_keeps_shared_objects() {
+    let file = Granny2::from_bytes(&fixture("c4.gr2")).unwrap();
+    let deserialized = round_trip(&file);
+    let (mut before, mut after) = (vec![], vec![]);
+    objects(&file.root, &mut before);
+    objects(&deserialized.root, &mut after);
+    assert_eq!(after, before);
+
+    // Written back, references to the same object still share it
+    let mut bytes = vec![];
+    deserialized.write(&mut bytes).unwrap();
+    let written = Granny2::from_bytes(&bytes).unwrap();
+    let mut rewritten = vec![];
+    objects(&written.root, &mut rewritten);
+    for i in 0..before.len() {
+        for j in (i + 1)..before.len() {
+            assert_eq!(before[i] == before[j], rewritten[i] == rewritten[j]);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use common::fixture;
+use granny2::{
+    granny2::transform::{Matrix, Transform},
+    Granny2,
+};
+
+fn file() -> Granny2 {
+    Granny2::from_bytes(&fixture("c4.gr2")).unwrap()
+}
+
+fn assert_near(found: [f32; 3], expected: [f32; 3]) {
+    assert!(
+        found
+            .iter()
+            .zip(expected)
+            .all(|(found, expected)| (found - expected).abs() < 1e-5),
+        "{:?} != {:?}",
+        found,
+        expected
+    );
+}
+
+fn assert_matrix_near(found: &Matrix, expected: &Matrix) {
+    assert!(
+        found
+            .0
+            .iter()
+            .zip(expected.0)
+            .all(|(found, expected)| (found - expected).abs() < 1e-5),
+        "{:?} != {:?}",
+        found,
+        expected
+    );
+}
+
+/// Quarter turn around Z, moved `translation` away from the parent.
+fn quarter_turn(translation: [f32; 3]) -> Transform {
+    Transform {
+        flags: Transform::HAS_POSITION | Transform::HAS_ORIENTATION,
+        translation,
+        rotation: [0., 0., FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+        ..Transform::IDENTITY
+    }
+}
+
+#[test]
+fn bones_keep_their_hierarchy() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    let skeleton = &info.skeletons[0];
+    let parents = skeleton
+        .bones
+        .iter()
+        .map(|bone| bone.parent_index)
+        .collect::<Vec<_>>();
+    assert_eq!(parents, [None, Some(0), Some(1)]);
+    assert_eq!(skeleton.bone_index("head"), Some(2));
+    assert_eq!(skeleton.bone_index("tail"), None);
+}
+
+#[test]
+fn world_matrices_compose_parents() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    let world = info.skeletons[0].world_matrices();
+
+    assert_matrix_near(&world[0], &Matrix::IDENTITY);
+    assert_near(world[1].translation(), [0., 1., 0.]);
+    // The head sits on top of the spine, turned a quarter around Z
+    assert_near(world[2].translation(), [0., 2., 0.]);
+    assert_near(world[2].transform_point([1., 0., 0.]), [0., 3., 0.]);
+}
+
+#[test]
+fn rest_pose_skins_to_identity() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    let skeleton = &info.skeletons[0];
+    let skinning = skeleton.skinning_matrices(&skeleton.world_matrices());
+    assert_eq!(skinning.len(), 3);
+    for matrix in &skinning {
+        assert_matrix_near(matrix, &Matrix::IDENTITY);
+    }
+}
+
+#[test]
+fn posed_matrices_follow_local_transforms() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    let skeleton = &info.skeletons[0];
+
+    let mut pose = skeleton
+        .bones
+        .iter()
+        .map(|bone| bone.local_transform.clone())
+        .collect::<Vec<_>>();
+    pose[1] = quarter_turn([0., 1., 0.]);
+    let world = skeleton.posed_world_matrices(&pose);
+
+    assert_near(world[1].translation(), [0., 1., 0.]);
+    // Turning the spine swings the head down to its left, half turned
+    assert_near(world[2].translation(), [-1., 1., 0.]);
+    assert_near(world[2].transform_point([1., 0., 0.]), [-2., 1., 0.]);
+
+    // Vertices follow their bone from the rest pose
+    let skinning = skeleton.skinning_matrices(&world);
+    assert_matrix_near(&skinning[0], &Matrix::IDENTITY);
+    assert_near(skinning[1].transform_point([0., 2., 0.]), [-1., 1., 0.]);
+    assert_near(skinning[2].transform_point([0., 3., 0.]), [-2., 1., 0.]);
+}
+
+#[test]
+fn missing_transforms_keep_rest_pose() {
+    let file = file();
+    let info = file.file_info().unwrap();
+    let skeleton = &info.skeletons[0];
+    let rest = skeleton.world_matrices();
+    assert_eq!(skeleton.posed_world_matrices(&[]), rest);
+
+    // Only the root moves, the rest follows it
+    let moved = Transform {
+        flags: Transform::HAS_POSITION,
+        translation: [5., 0., 0.],
+        ..Transform::IDENTITY
+    };
+    let world = skeleton.posed_world_matrices(&[moved]);
+    assert_near(world[1].translation(), [5., 1., 0.]);
+    assert_near(world[2].translation(), [5., 2., 0.]);
+}
@@ -0,0 +1,243 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::{fixture, flatten};
+use granny2::{
+    granny2::{
+        element::{Data, Element, TypeDefinition},
+        section::CompressionMode,
+    },
+    Granny2, ParseOptions, WriteOptions,
+};
+
+const FIXTURES: [&str; 5] = ["c4.gr2", "t4le.gr2", "t4be.gr2", "t8le.gr2", "t8be.gr2"];
+
+fn round_trip(file: &Granny2) -> Granny2 {
+    let mut bytes = vec![];
+    file.write(&mut bytes).unwrap();
+    Granny2::from_bytes(&bytes).unwrap()
+}
+
+/// Positions of the references in the tree, in tree order.
+fn references(elements: &[Element], positions: &mut Vec<u64>) {
+    for element in elements {
+        if let [Data::Reference(pos)] = element.data[..] {
+            if pos != 0 {
+                positions.push(pos);
+            }
+        }
+        references(&element.children, positions);
+    }
+}
+
+#[test]
+fn round_trip_keeps_tree() {
+    for name in FIXTURES {
+        let file = Granny2::from_bytes(&fixture(name)).unwrap();
+        let written = round_trip(&file);
+        assert_eq!(flatten(&written.root), flatten(&file.root), "{}", name);
+    }
+}
+
+#[test]
+fn round_trip_keeps_schema() {
+    // Empty arrays, null references and variants have no objects to take
+    // their type from
+    for name in FIXTURES {
+        let file = Granny2::from_bytes(&fixture(name)).unwrap();
+        let written = round_trip(&file);
+        assert_eq!(
+            file.definition().diff(&written.definition()),
+            [],
+            "{}",
+            name
+        );
+        assert_eq!(written.definition(), file.definition(), "{}", name);
+    }
+
+    let file = Granny2::from_bytes(&fixture("c4.gr2")).unwrap();
+    let written = round_trip(&file);
+    for path in [
+        "TriTopologies/*/Indices",
+        "Meshes/*/MaterialBindings/*/Material",
+        "Meshes/*/MaterialBindings/*/Material/Texture",
+        "TrackGroups/*/TransformLODErrors",
+        "Materials/*/ExtendedData",
+    ] {
+        let definitions = |file: &Granny2| {
+            file.query(path)
+                .map(|element| element.definition.clone())
+                .collect::<Vec<_>>()
+        };
+        assert!(file.query(path).next().is_some(), "{}", path);
+        assert_eq!(definitions(&written), definitions(&file), "{}", path);
+    }
+}
+
+/// Whether each pair of references of the tree, in tree order, points at
+/// the same position.
+fn shared_references(file: &Granny2) -> Vec<bool> {
+    let mut positions = vec![];
+    references(&file.root, &mut positions);
+    let mut shared = vec![];
+    for i in 0..positions.len() {
+        for j in (i + 1)..positions.len() {
+            shared.push(positions[i] == positions[j]);
+        }
+    }
+    shared
+}
+
+/// Copy of `elements` sharing no members, with their object identifiers
+/// mapped by `object`.
+fn rebuild(elements: &[Element], object: &impl Fn(Option<usize>) -> Option<usize>) -> Vec<Element> {
+    elements
+        .iter()
+        .map(|element| Element {
+            info: element.info.clone(),
+            name: element.name.clone(),
+            children: rebuild(&element.children, object).into(),
+            size: element.size,
+            data: element.data.clone(),
+            definition: element.definition.clone(),
+            object: object(element.object),
+        })
+        .collect()
+}
+
+#[test]
+fn round_trip_keeps_shared_objects() {
+    let file = Granny2::from_bytes(&fixture("c4.gr2")).unwrap();
+    let shared = shared_references(&file);
+    assert!(shared.contains(&true), "Fixture must share objects.");
+    assert_eq!(shared_references(&round_trip(&file)), shared);
+}
+
+#[test]
+fn rebuilt_tree_keeps_shared_objects() {
+    // Sharing comes from the identifiers, not from the members
+    let mut file = Granny2::from_bytes(&fixture("c4.gr2")).unwrap();
+    let shared = shared_references(&file);
+    file.root = rebuild(&file.root, &|object| object);
+    assert_eq!(shared_references(&round_trip(&file)), shared);
+
+    // Without them every reference gets its own object
+    file.root = rebuild(&file.root, &|_| None);
+    let written = round_trip(&file);
+    assert!(!shared_references(&written).contains(&true));
+    assert_eq!(flatten(&written.root), flatten(&file.root));
+}
+
+/// References of the tree, by the position of their object.
+fn referenced<'a>(elements: &'a [Element], objects: &mut Vec<(u64, &'a Element)>) {
+    for element in elements {
+        if let [Data::Reference(pos)] = element.data[..] {
+            if pos != 0 {
+                assert!(element.object.is_some());
+                objects.push((pos, element));
+            }
+        }
+        referenced(&element.children, objects);
+    }
+}
+
+#[test]
+fn references_share_members() {
+    // Written files must keep sharing them too, as objects are deduplicated
+    // by their identifier
+    let file = Granny2::from_bytes(&fixture("c4.gr2")).unwrap();
+    for file in [round_trip(&file), file] {
+        let mut objects = vec![];
+        referenced(&file.root, &mut objects);
+        for (pos, reference) in &objects {
+            for (other_pos, other) in &objects {
+                let same = pos == other_pos;
+                assert_eq!(same, Arc::ptr_eq(&reference.children, &other.children));
+                assert_eq!(same, reference.object == other.object);
+            }
+        }
+    }
+}
+
+#[test]
+fn round_trip_is_stable() {
+    for name in FIXTURES {
+        let written = round_trip(&Granny2::from_bytes(&fixture(name)).unwrap());
+        let mut first = vec![];
+        written.write(&mut first).unwrap();
+        let mut second = vec![];
+        round_trip(&written).write(&mut second).unwrap();
+        assert_eq!(first, second, "{}", name);
+    }
+}
+
+#[test]
+fn compressed_round_trip() {
+    let options = WriteOptions {
+        compression_mode: CompressionMode::Oodle1,
+    };
+    for name in FIXTURES {
+        let file = Granny2::from_bytes(&fixture(name)).unwrap();
+        let mut bytes = vec![];
+        file.write_with_options(&mut bytes, &options).unwrap();
+        let written = Granny2::from_bytes(&bytes).unwrap();
+        assert!(written
+            .sections
+            .iter()
+            .all(|section| section.compression_mode == CompressionMode::Oodle1));
+        assert_eq!(flatten(&written.root), flatten(&file.root), "{}", name);
+    }
+}
+
+#[test]
+fn round_trip_keeps_extra_ptr() {
+    let mut bytes = fixture("t4le.gr2");
+    let read_u32 = |bytes: &[u8], pos: usize| {
+        let Ok(value) = bytes[pos..(pos + 4)].try_into().map(u32::from_le_bytes) else {
+            unreachable!("Slice has 4 bytes.");
+        };
+        usize::try_from(value).unwrap()
+    };
+    // Last field of the first two members of the root type
+    let section_data = read_u32(&bytes, 32 + read_u32(&bytes, 44) + 4);
+    let root_type = section_data + read_u32(&bytes, 56);
+    bytes[(root_type + 28)..(root_type + 32)].copy_from_slice(&0x1234u32.to_le_bytes());
+    bytes[(root_type + 60)..(root_type + 64)].copy_from_slice(&0xabcdu32.to_le_bytes());
+
+    let options = ParseOptions {
+        verify_checksum: false,
+    };
+    let file = Granny2::from_bytes_with_options(&bytes, &options).unwrap();
+    assert_eq!(file.root[0].info.extra_ptr, 0x1234);
+    assert_eq!(file.root[1].info.extra_ptr, 0xabcd);
+
+    let written = round_trip(&file);
+    assert_eq!(written.root[0].info.extra_ptr, 0x1234);
+    assert_eq!(written.root[1].info.extra_ptr, 0xabcd);
+    assert_eq!(written.root[2].info.extra_ptr, 0);
+    let definition = written.definition();
+    assert_eq!(definition.members[0].extra_ptr, 0x1234);
+    assert_eq!(definition.members[1].extra_ptr, 0xabcd);
+}
+
+#[test]
+fn definitions_differing_by_extra_ptr_are_written_apart() {
+    // Child and the items share their type, until one of them gets another
+    // extra_ptr
+    let mut file = Granny2::from_bytes(&fixture("t4le.gr2")).unwrap();
+    let child = &mut file.root[2];
+    assert_eq!(child.name.as_ref(), "Child");
+    let mut definition = TypeDefinition::clone(child.definition.as_ref().unwrap());
+    definition.members[0].extra_ptr = 0x42;
+    assert_eq!(&definition, child.definition.as_deref().unwrap());
+    child.definition = Some(Arc::new(definition));
+    let mut children = rebuild(&child.children, &|object| object);
+    children[0].info.extra_ptr = 0x42;
+    child.children = children.into();
+
+    let written = round_trip(&file);
+    assert_eq!(written.get("Child/A").unwrap().info.extra_ptr, 0x42);
+    assert_eq!(written.get("Items/0/A").unwrap().info.extra_ptr, 0);
+    assert_eq!(written.get("Refs/1/A").unwrap().info.extra_ptr, 0);
+}